@@ -59,7 +59,10 @@ pub fn field_element_to_fr(fe: &FieldElement) -> Fr {
 /// 各係数を [`field_element_to_fr`] で変換し、`coefficients[i]` の並び
 /// （`x^i` の係数）をそのまま保つ。
 pub fn polynomial_to_fr_vec(poly: &Polynomial) -> Vec<Fr> {
-    poly.coefficients.iter().map(field_element_to_fr).collect()
+    poly.coefficients()
+        .iter()
+        .map(field_element_to_fr)
+        .collect()
 }
 
 /// QAP の多項式群（`a_polys` / `b_polys` / `c_polys` など）をまとめて変換する。
@@ -0,0 +1,113 @@
+//! Barrett 還元による `x mod p` の計算（法を固定した体の乗算の高速化）。
+//!
+//! Groth16 実装の Layer 1。[`crate::montgomery`] と同じく、乗算のたびに
+//! 多倍長の割り算 `% p` を取るコストを減らすための仕組み。Montgomery 形式と違って
+//! 元の表現を変えずに済むので、[`crate::fp::Fp`] と [`crate::field::FieldElement`] の
+//! `Mul` にそのまま差し込める。
+//!
+//! `k` を `p` のビット長として `μ = ⌊2^{2k} / p⌋` を 1 度だけ前計算しておけば、
+//! `x < p^2` の商 `⌊x / p⌋` は `⌊⌊x / 2^{k−1}⌋ · μ / 2^{k+1}⌋` で近似でき、
+//! 真の商との差は高々 2 になる。割り算はすべてシフトになるので、
+//! 還元は掛け算 2 回と引き算（と高々 2 回の補正）で済む。
+//!
+//! ## 主要型
+//! - [`BarrettReducer`]: 法 `p` ごとの前計算（`k` と `μ`）
+//!
+//! ## 主要メソッド
+//! - [`BarrettReducer::reduce`][]: `0 <= x < p^2` を `x mod p` に還元する
+
+use num_bigint::{BigInt, Sign};
+
+/// 法 `p` の Barrett 還元に使う前計算の値。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BarrettReducer {
+    /// 法 `p`
+    pub p: BigInt,
+    /// `p` のビット長 `k`
+    k: u64,
+    /// `μ = ⌊2^{2k} / p⌋`
+    mu: BigInt,
+}
+
+impl BarrettReducer {
+    /// 法 `p` の前計算を行う。
+    ///
+    /// # Panics
+    /// `p` が 2 未満の場合は panic する。
+    pub fn new(p: &BigInt) -> Self {
+        assert!(
+            *p > BigInt::from(1),
+            "Barrett 還元の法は 2 以上である必要があります: {}",
+            p
+        );
+        let k = p.bits();
+        let mu = (BigInt::from(1) << (2 * k)) / p;
+        BarrettReducer {
+            p: p.clone(),
+            k,
+            mu,
+        }
+    }
+
+    /// `x mod p` を返す。`x` は `0 <= x < p^2`（2 つの正規化済みの元の積）であること。
+    ///
+    /// `q = ⌊⌊x / 2^{k−1}⌋ · μ / 2^{k+1}⌋` は真の商 `⌊x / p⌋` 以下で、差は高々 2。
+    /// なので `r = x − q·p` は `0 <= r < 3p` に収まり、`p` を高々 2 回引けば正規化できる。
+    pub fn reduce(&self, x: &BigInt) -> BigInt {
+        debug_assert!(
+            x.sign() != Sign::Minus && *x < &self.p * &self.p,
+            "Barrett 還元の入力は 0 <= x < p^2 の範囲である必要があります"
+        );
+        let q = ((x >> (self.k - 1)) * &self.mu) >> (self.k + 1);
+        let mut r = x - q * &self.p;
+        while r >= self.p {
+            r -= &self.p;
+        }
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::FieldElement;
+
+    fn bn254_fr() -> BigInt {
+        BigInt::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            10,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reduce_matches_naive_mod_for_random_products() {
+        let mut rng = ark_std::test_rng();
+        for p in [BigInt::from(17), BigInt::from(u64::MAX - 58), bn254_fr()] {
+            let reducer = BarrettReducer::new(&p);
+            for _ in 0..1000 {
+                let a = FieldElement::random(&mut rng, &p).value;
+                let b = FieldElement::random(&mut rng, &p).value;
+                let x = &a * &b;
+                assert_eq!(reducer.reduce(&x), &x % &p, "p = {}, x = {}", p, x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reduce_exhaustive_small_modulus() {
+        // 0 <= x < p^2 をすべて試す（上限 p^2 − 1 = (p − 1)^2 + 2(p − 1) も含む）
+        for p in [2u64, 3, 17, 97] {
+            let reducer = BarrettReducer::new(&BigInt::from(p));
+            for x in 0..p * p {
+                assert_eq!(reducer.reduce(&BigInt::from(x)), BigInt::from(x % p));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "2 以上")]
+    fn test_new_rejects_trivial_modulus() {
+        BarrettReducer::new(&BigInt::from(1));
+    }
+}
@@ -0,0 +1,548 @@
+//! 有限体上の楕円曲線（短い Weierstrass 形）の点と群演算を提供する。
+//!
+//! Groth16 実装の Layer 1（数学的基盤）。自作 [`FieldElement`] の上に
+//! 曲線 `y^2 = x^3 + a·x + b` を定義し、離散対数が難しい群として使う。
+//! 本式の Groth16（[`crate::setup`] 以降）は arkworks の BN254 を使うので、
+//! こちらは群演算の仕組みを確かめるための自作版。
+//!
+//! ## 主要型
+//! - [`Curve`]: 曲線の係数 `a`, `b`
+//! - [`AffinePoint`]: アフィン座標の点（無限遠点 `O` を単位元として含む）
+//!
+//! ## 主要メソッド
+//! - [`AffinePoint::add`][] / [`AffinePoint::double`][] / [`AffinePoint::neg`][]: 群演算
+//! - [`AffinePoint::scalar_mul`][]: double-and-add によるスカラー倍 `k·P`
+//! - [`AffinePoint::scalar_mul_wnaf`][]: 幅 w の NAF によるスカラー倍（加算回数が少ない）
+//!
+//! ## 主要関数
+//! - [`msm`]: Pippenger のバケット法による multi-scalar multiplication `Σ k_i·P_i`
+//! - [`AffinePoint::is_on_curve`][]: 点が曲線上にあるか
+
+use std::fmt;
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{ToPrimitive, Zero};
+
+use crate::field::FieldElement;
+
+/// 楕円曲線の演算で起こりうるエラー。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EcError {
+    /// 点とスカラーの個数が一致しない（[`msm`]）。
+    LengthMismatch { points: usize, scalars: usize },
+    /// 入力が空で、結果の曲線が決まらない（[`msm`]）。
+    EmptyInput,
+}
+
+impl fmt::Display for EcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EcError::LengthMismatch { points, scalars } => write!(
+                f,
+                "length mismatch: {} points vs {} scalars",
+                points, scalars
+            ),
+            EcError::EmptyInput => write!(f, "input must not be empty"),
+        }
+    }
+}
+
+impl std::error::Error for EcError {}
+
+/// 短い Weierstrass 形の曲線 `y^2 = x^3 + a·x + b`。
+///
+/// `4a^3 + 27b^2 ≠ 0`（特異でない）ことは呼び出し側の責務とし、ここではチェックしない。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curve {
+    pub a: FieldElement,
+    pub b: FieldElement,
+}
+
+/// 曲線上の点（アフィン座標）。
+///
+/// `infinity` が真なら無限遠点 `O`（群の単位元）で、このとき `x`, `y` は 0 に揃える
+/// （導出した `PartialEq` で無限遠点どうしが等しくなるように）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffinePoint {
+    pub x: FieldElement,
+    pub y: FieldElement,
+    pub infinity: bool,
+    pub curve: Curve,
+}
+
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+impl AffinePoint {
+    /// 点 `(x, y)` を作る。曲線上にない場合は panic する。
+    pub fn new(x: FieldElement, y: FieldElement, curve: &Curve) -> Self {
+        let point = AffinePoint {
+            x,
+            y,
+            infinity: false,
+            curve: curve.clone(),
+        };
+        assert!(
+            point.is_on_curve(),
+            "点 ({}, {}) は曲線上にありません",
+            point.x,
+            point.y
+        );
+        point
+    }
+
+    /// 無限遠点 `O`（単位元）を返す。
+    pub fn identity(curve: &Curve) -> Self {
+        let zero = FieldElement::zero(&curve.a.p);
+        AffinePoint {
+            x: zero.clone(),
+            y: zero,
+            infinity: true,
+            curve: curve.clone(),
+        }
+    }
+
+    /// 点が曲線 `y^2 = x^3 + a·x + b` 上にあるかを返す。無限遠点は常に真。
+    pub fn is_on_curve(&self) -> bool {
+        if self.infinity {
+            return true;
+        }
+        let lhs = self.y.square();
+        let rhs = &(&(&self.x.square() * &self.x) + &(&self.curve.a * &self.x)) + &self.curve.b;
+        lhs == rhs
+    }
+
+    /// 逆元 `−P = (x, −y)` を返す。`−O = O`。
+    pub fn neg(&self) -> Self {
+        if self.infinity {
+            return self.clone();
+        }
+        AffinePoint {
+            x: self.x.clone(),
+            y: -&self.y,
+            infinity: false,
+            curve: self.curve.clone(),
+        }
+    }
+
+    /// 点の加算 `P + Q`（弦の規則）。
+    ///
+    /// `P = Q` なら [`double`](Self::double) に、`P = −Q` なら無限遠点になる。
+    pub fn add(&self, other: &Self) -> Self {
+        if self.infinity {
+            return other.clone();
+        }
+        if other.infinity {
+            return self.clone();
+        }
+        if self.x == other.x {
+            // 同じ x: P = Q なら接線、P = −Q なら縦線で O
+            return if self.y == other.y {
+                self.double()
+            } else {
+                AffinePoint::identity(&self.curve)
+            };
+        }
+
+        // λ = (y2 − y1) / (x2 − x1)
+        let lambda = &(&other.y - &self.y) / &(&other.x - &self.x);
+        self.chord_point(&lambda, &other.x)
+    }
+
+    /// 点の 2 倍 `2P`（接線の規則）。`y = 0` の点は位数 2 なので O になる。
+    pub fn double(&self) -> Self {
+        if self.infinity || self.y.is_zero() {
+            return AffinePoint::identity(&self.curve);
+        }
+
+        // λ = (3x^2 + a) / 2y
+        let three = FieldElement::from_u64(3, &self.x.p);
+        let lambda = &(&(&three * &self.x.square()) + &self.curve.a) / &self.y.double();
+        self.chord_point(&lambda, &self.x)
+    }
+
+    /// 傾き `λ` の直線が曲線と交わる 3 点目を x 軸で折り返した点を返す。
+    ///
+    /// `x3 = λ^2 − x1 − x2`, `y3 = λ(x1 − x3) − y1`。
+    fn chord_point(&self, lambda: &FieldElement, other_x: &FieldElement) -> Self {
+        let x3 = &(&lambda.square() - &self.x) - other_x;
+        let y3 = &(lambda * &(&self.x - &x3)) - &self.y;
+        AffinePoint {
+            x: x3,
+            y: y3,
+            infinity: false,
+            curve: self.curve.clone(),
+        }
+    }
+
+    /// スカラー倍 `k·P` を double-and-add（上位ビットから）で計算する。
+    ///
+    /// 負の `k` は `|k|·(−P)`、`k = 0` は無限遠点。計算量は `O(log k)` 回の群演算。
+    pub fn scalar_mul(&self, k: &BigInt) -> Self {
+        let (sign, magnitude) = (k.sign(), k.magnitude());
+        let base = if sign == Sign::Minus {
+            self.neg()
+        } else {
+            self.clone()
+        };
+
+        let mut acc = AffinePoint::identity(&self.curve);
+        for i in (0..magnitude.bits()).rev() {
+            acc = acc.double();
+            if magnitude.bit(i) {
+                acc = acc.add(&base);
+            }
+        }
+        acc
+    }
+
+    /// スカラー倍 `k·P` を幅 `window` の NAF（wNAF）で計算する。
+    ///
+    /// `k` を各桁が 0 か奇数 `±1, ±3, ..., ±(2^{w−1} − 1)` の符号付き表現に直すと、
+    /// 非ゼロ桁の間に必ず `w − 1` 個以上の 0 が入る。奇数倍の表
+    /// `P, 3P, ..., (2^{w−1} − 1)P` を先に作っておけば、加算は非ゼロ桁の数
+    /// （平均 `bits / (w + 1)`）で済む。負の桁は表の点の逆元を足す。
+    /// 結果は [`scalar_mul`](Self::scalar_mul) と一致する。
+    ///
+    /// # Panics
+    /// `window` が 2 未満または 16 より大きいとき panic。
+    pub fn scalar_mul_wnaf(&self, k: &BigInt, window: usize) -> Self {
+        assert!(
+            (2..=16).contains(&window),
+            "window は 2 以上 16 以下である必要があります: {}",
+            window
+        );
+        let base = if k.sign() == Sign::Minus {
+            self.neg()
+        } else {
+            self.clone()
+        };
+
+        // 奇数倍の表 table[i] = (2i + 1)·P
+        let table_size = 1usize << (window - 2);
+        let twice = base.double();
+        let mut table = Vec::with_capacity(table_size);
+        table.push(base);
+        for i in 1..table_size {
+            let next = table[i - 1].add(&twice);
+            table.push(next);
+        }
+
+        let mut acc = AffinePoint::identity(&self.curve);
+        for &digit in wnaf_digits(k.magnitude(), window).iter().rev() {
+            acc = acc.double();
+            if digit > 0 {
+                acc = acc.add(&table[(digit as usize - 1) / 2]);
+            } else if digit < 0 {
+                acc = acc.add(&table[((-digit) as usize - 1) / 2].neg());
+            }
+        }
+        acc
+    }
+}
+
+/// multi-scalar multiplication `Σ scalars[i]·points[i]` を Pippenger のバケット法で計算する。
+///
+/// スカラーを幅 `c` ビットの窓に区切り、窓ごとに「桁の値 d のバケット」へ点を足し込む。
+/// バケットの重み付き和 `Σ d·B_d` は累積和を 2 回取るだけで求まるので、
+/// 1 窓あたりの加算は `n + 2·2^c` 回程度で済む（個別に `scalar_mul` するより速い）。
+/// `c` は入力数 `n` から `≈ log2(n)` として選ぶ。負のスカラーは点の逆元に付け替える。
+///
+/// 長さが違えば [`EcError::LengthMismatch`]、空なら [`EcError::EmptyInput`] を返す。
+pub fn msm(points: &[AffinePoint], scalars: &[BigInt]) -> Result<AffinePoint, EcError> {
+    if points.len() != scalars.len() {
+        return Err(EcError::LengthMismatch {
+            points: points.len(),
+            scalars: scalars.len(),
+        });
+    }
+    let curve = &points.first().ok_or(EcError::EmptyInput)?.curve;
+
+    // 負のスカラーは (−P, |k|) に直して、以降は非負だけを扱う
+    let pairs: Vec<(AffinePoint, &BigUint)> = points
+        .iter()
+        .zip(scalars)
+        .map(|(point, k)| {
+            let base = if k.sign() == Sign::Minus {
+                point.neg()
+            } else {
+                point.clone()
+            };
+            (base, k.magnitude())
+        })
+        .collect();
+
+    let c = (usize::BITS - points.len().leading_zeros()).max(2) as u64;
+    let max_bits = pairs.iter().map(|(_, k)| k.bits()).max().unwrap_or(0);
+    let num_windows = max_bits.div_ceil(c);
+
+    let mut acc = AffinePoint::identity(curve);
+    for w in (0..num_windows).rev() {
+        for _ in 0..c {
+            acc = acc.double();
+        }
+
+        // buckets[d − 1] に、この窓の桁が d の点を集める
+        let mut buckets = vec![AffinePoint::identity(curve); (1 << c) - 1];
+        for (point, k) in &pairs {
+            let mut digit = 0usize;
+            for b in (0..c).rev() {
+                digit = (digit << 1) | usize::from(k.bit(w * c + b));
+            }
+            if digit != 0 {
+                buckets[digit - 1] = buckets[digit - 1].add(point);
+            }
+        }
+
+        // Σ d·B_d = Σ_{d} (B_d + B_{d+1} + ... + B_max)
+        let mut running = AffinePoint::identity(curve);
+        let mut window_sum = AffinePoint::identity(curve);
+        for bucket in buckets.iter().rev() {
+            running = running.add(bucket);
+            window_sum = window_sum.add(&running);
+        }
+        acc = acc.add(&window_sum);
+    }
+    Ok(acc)
+}
+
+/// `k` の幅 `window` の NAF 表現を下位桁から返す。
+///
+/// 各桁は 0 か、絶対値が `2^{window−1}` 未満の奇数。`Σ digits[i]·2^i = k`。
+fn wnaf_digits(k: &BigUint, window: usize) -> Vec<i64> {
+    let modulus = 1i64 << window;
+    let half = modulus / 2;
+    let mut k = k.clone();
+    let mut digits = Vec::new();
+    while !k.is_zero() {
+        let digit = if k.bit(0) {
+            // k mod 2^w を (−2^{w−1}, 2^{w−1}) に寄せる
+            let low = (&k % BigUint::from(modulus as u64)).to_i64().unwrap();
+            let d = if low >= half { low - modulus } else { low };
+            if d > 0 {
+                k -= BigUint::from(d as u64);
+            } else {
+                k += BigUint::from((-d) as u64);
+            }
+            d
+        } else {
+            0
+        };
+        digits.push(digit);
+        k >>= 1;
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::rand::Rng;
+
+    const P: i64 = 97;
+
+    fn fe(v: i64) -> FieldElement {
+        FieldElement::new(v, P)
+    }
+
+    /// F_97 上の `y^2 = x^3 + 2x + 3`
+    fn curve() -> Curve {
+        Curve { a: fe(2), b: fe(3) }
+    }
+
+    fn point(x: i64, y: i64) -> AffinePoint {
+        AffinePoint::new(fe(x), fe(y), &curve())
+    }
+
+    /// 曲線上の点をすべて列挙する（総当たり）。
+    fn all_points() -> Vec<AffinePoint> {
+        let c = curve();
+        let mut points = Vec::new();
+        for x in 0..P {
+            for y in 0..P {
+                let candidate = AffinePoint {
+                    x: fe(x),
+                    y: fe(y),
+                    infinity: false,
+                    curve: c.clone(),
+                };
+                if candidate.is_on_curve() {
+                    points.push(candidate);
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn is_on_curve_accepts_and_rejects() {
+        // 6^2 = 36 = 27 + 6 + 3
+        assert!(point(3, 6).is_on_curve());
+        let off = AffinePoint {
+            x: fe(3),
+            y: fe(7),
+            infinity: false,
+            curve: curve(),
+        };
+        assert!(!off.is_on_curve());
+        assert!(AffinePoint::identity(&curve()).is_on_curve());
+    }
+
+    #[test]
+    #[should_panic(expected = "曲線上にありません")]
+    fn new_rejects_point_off_curve() {
+        point(3, 7);
+    }
+
+    #[test]
+    fn add_identity_and_inverse() {
+        let p = point(3, 6);
+        let o = AffinePoint::identity(&curve());
+        assert_eq!(p.add(&o), p);
+        assert_eq!(o.add(&p), p);
+        assert_eq!(p.add(&p.neg()), o);
+        assert_eq!(o.neg(), o);
+    }
+
+    #[test]
+    fn add_results_stay_on_curve_and_commute() {
+        let points = all_points();
+        for p in points.iter().take(10) {
+            assert!(p.double().is_on_curve());
+            assert_eq!(p.add(p), p.double());
+            for q in points.iter().skip(10).take(10) {
+                let sum = p.add(q);
+                assert!(sum.is_on_curve());
+                assert_eq!(sum, q.add(p));
+            }
+        }
+    }
+
+    #[test]
+    fn add_is_associative() {
+        let points = all_points();
+        let sample: Vec<&AffinePoint> = points.iter().step_by(7).take(8).collect();
+        for p in &sample {
+            for q in &sample {
+                for r in &sample {
+                    assert_eq!(p.add(q).add(r), p.add(&q.add(r)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scalar_mul_matches_repeated_addition() {
+        let p = point(3, 6);
+        let mut expected = AffinePoint::identity(&curve());
+        for k in 0..40 {
+            assert_eq!(p.scalar_mul(&BigInt::from(k)), expected, "k = {}", k);
+            expected = expected.add(&p);
+        }
+        assert_eq!(
+            p.scalar_mul(&BigInt::from(-5)),
+            p.scalar_mul(&BigInt::from(5)).neg()
+        );
+    }
+
+    #[test]
+    fn scalar_mul_by_group_order_is_identity() {
+        // 点の総数（O を含む）を n とすると、ラグランジュの定理から n·P = O
+        let points = all_points();
+        let n = BigInt::from(points.len() + 1);
+        let o = AffinePoint::identity(&curve());
+        for p in points.iter().step_by(11) {
+            assert_eq!(p.scalar_mul(&n), o);
+        }
+    }
+
+    #[test]
+    fn wnaf_digits_reconstruct_scalar() {
+        let k = BigUint::from(3u32).pow(150);
+        for window in 2..=6 {
+            let digits = wnaf_digits(&k, window);
+            let mut acc = BigInt::zero();
+            for &d in digits.iter().rev() {
+                acc = acc * 2 + d;
+            }
+            assert_eq!(acc, BigInt::from(k.clone()), "window = {}", window);
+            // 非ゼロ桁は奇数で、直後の w − 1 桁は 0
+            for (i, &d) in digits.iter().enumerate() {
+                if d != 0 {
+                    assert!(d % 2 != 0 && d.abs() < 1 << (window - 1));
+                    assert!(digits[i + 1..].iter().take(window - 1).all(|&z| z == 0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scalar_mul_wnaf_agrees_with_double_and_add() {
+        let p = point(3, 6);
+        for window in 2..=5 {
+            for k in -50..200 {
+                let k = BigInt::from(k);
+                assert_eq!(
+                    p.scalar_mul_wnaf(&k, window),
+                    p.scalar_mul(&k),
+                    "k = {}, window = {}",
+                    k,
+                    window
+                );
+            }
+        }
+        let large = BigInt::from(3).pow(150);
+        assert_eq!(p.scalar_mul_wnaf(&large, 4), p.scalar_mul(&large));
+    }
+
+    #[test]
+    fn scalar_mul_wnaf_uses_fewer_additions_for_large_scalar() {
+        // 約 238 ビットのスカラーで、加算の回数を比べる（2 倍算の回数はほぼ同じ）
+        let k = BigUint::from(3u32).pow(150);
+        let double_and_add = k.count_ones() as usize;
+
+        let window = 4;
+        let precompute = 1usize << (window - 2); // 2P と表の (2^{w−2} − 1) 回の加算
+        let wnaf = wnaf_digits(&k, window).iter().filter(|&&d| d != 0).count() + precompute;
+
+        assert!(
+            wnaf * 2 < double_and_add,
+            "wNAF: {} 回, double-and-add: {} 回",
+            wnaf,
+            double_and_add
+        );
+    }
+
+    #[test]
+    fn msm_matches_naive_sum() {
+        let mut rng = ark_std::test_rng();
+        let g = point(3, 6);
+        let mut points = Vec::new();
+        let mut scalars = Vec::new();
+        for _ in 0..40 {
+            points.push(g.scalar_mul(&BigInt::from(rng.gen_range(1..1000u64))));
+            // 負数も混ぜる
+            scalars.push(BigInt::from(rng.gen_range(-(1i64 << 39)..(1i64 << 39))));
+        }
+
+        let naive = points
+            .iter()
+            .zip(&scalars)
+            .fold(AffinePoint::identity(&curve()), |acc, (p, k)| {
+                acc.add(&p.scalar_mul(k))
+            });
+        assert_eq!(msm(&points, &scalars).unwrap(), naive);
+    }
+
+    #[test]
+    fn msm_rejects_mismatched_and_empty_input() {
+        let p = point(3, 6);
+        assert_eq!(
+            msm(&[p.clone(), p], &[BigInt::from(1)]),
+            Err(EcError::LengthMismatch {
+                points: 2,
+                scalars: 1
+            })
+        );
+        assert_eq!(msm(&[], &[]), Err(EcError::EmptyInput));
+    }
+}
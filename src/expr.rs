@@ -0,0 +1,434 @@
+//! 算術式の文字列から R1CS 回路を組み立てる。
+//!
+//! Groth16 実装の Layer 2（回路の表現）。`"x*x*x + 5"` のような式を構文解析し、
+//! [`ConstraintSystem`] のゲートを直接発行する。`mul` / `add_const` などを手で
+//! 連鎖させる代わりに、計算そのものを式として書けるようにするためのもの。
+//!
+//! ## 文法
+//! ```text
+//! expr   := term (('+' | '-') term)*
+//! term   := factor ('*' factor)*
+//! factor := '-' factor | 整数 | 変数名 | '(' expr ')'
+//! ```
+//! 変数名は英字か `_` で始まり英数字と `_` が続くもの。整数は 10 進で、`p` で還元する。
+//!
+//! ## 発行する制約
+//! 足し算・引き算・定数倍は線形結合のまま持ち回り、制約を作らない。
+//! 制約になるのは変数どうしの掛け算（[`ConstraintSystem::mul`] 1 本）と、
+//! 線形結合を掛け算に渡すときの実体化（[`ConstraintSystem::linear_combination`] 1 本）、
+//! それに最後の出力への接続 `(式) · 1 = (out)` だけ。
+//! 出力 `out` は公開入力として確保するので、`x*x*x + 5` は main のデモと同じ回路になる。
+//!
+//! ## 主要関数
+//! - [`build_from_expr`]: 式から回路を組み立てて出力変数を返す（不正な式は panic）
+//! - [`try_build_from_expr`]: 同上、不正な式を [`ExprError`] で返す
+
+use std::collections::HashMap;
+use std::fmt;
+
+use num_bigint::BigInt;
+
+use crate::field::FieldElement;
+use crate::r1cs::{ConstraintSystem, LinearCombination, Variable, CS_ONE};
+
+/// 式の解析で起こりうるエラー。`pos` は式の文字列中のバイト位置。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    /// 字句として解釈できない文字があった。
+    InvalidChar { pos: usize, found: char },
+    /// 文法上ここに現れてはいけないトークンがあった。
+    UnexpectedToken { pos: usize },
+    /// 式が途中で終わった。
+    UnexpectedEnd,
+    /// `inputs` にない変数名が使われた。
+    UnknownVariable(String),
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExprError::InvalidChar { pos, found } => {
+                write!(f, "invalid character {:?} at {}", found, pos)
+            }
+            ExprError::UnexpectedToken { pos } => write!(f, "unexpected token at {}", pos),
+            ExprError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ExprError::UnknownVariable(name) => write!(f, "unknown variable: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// 式 `expr` から回路を組み立て、制約系と出力変数を返す。
+///
+/// 出力は [`CS_ONE`] の直後に公開入力として確保し、式に現れた変数は
+/// 初出順に秘密入力として（変数名付きで）確保して `inputs` の値を代入する。
+/// 返す制約系は witness まで埋まっているので、そのまま
+/// [`generate_witness`](ConstraintSystem::generate_witness) できる。
+///
+/// # Panics
+/// 式が不正なとき、または `inputs` にない変数が使われたとき panic する。
+/// panic させたくない場合は [`try_build_from_expr`] を使う。
+///
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+pub fn build_from_expr(
+    expr: &str,
+    inputs: &HashMap<&str, FieldElement>,
+    p: &BigInt,
+) -> (ConstraintSystem, Variable) {
+    try_build_from_expr(expr, inputs, p).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// 式 `expr` から回路を組み立て、制約系と出力変数を返す。
+///
+/// [`build_from_expr`] の panic しない版。式が不正なら [`ExprError`] を返す。
+pub fn try_build_from_expr(
+    expr: &str,
+    inputs: &HashMap<&str, FieldElement>,
+    p: &BigInt,
+) -> Result<(ConstraintSystem, Variable), ExprError> {
+    let tokens = tokenize(expr)?;
+
+    let mut cs = ConstraintSystem::new();
+    cs.init_one(FieldElement::one(p));
+    // 公開出力を前方に固める（秘密入力より先に確保する必要がある）
+    let out = cs.alloc_public_input();
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: expr.len(),
+        inputs,
+        vars: HashMap::new(),
+        cs,
+        p,
+    };
+    let value = parser.expr()?;
+    if let Some((pos, _)) = parser.tokens.get(parser.pos) {
+        return Err(ExprError::UnexpectedToken { pos: *pos });
+    }
+
+    // 制約： (式) * 1 = out
+    let mut cs = parser.cs;
+    let one = FieldElement::one(p);
+    cs.assign(out, value.evaluate(&cs));
+    let lc_b = LinearCombination::from((CS_ONE, one.clone()));
+    let lc_c = LinearCombination::from((out, one));
+    cs.enforce(value.into_lc(), lc_b, lc_c);
+
+    Ok((cs, out))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Num(BigInt),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    LParen,
+    RParen,
+}
+
+/// 式を `(バイト位置, トークン)` の列に分解する。空白は読み飛ばす。
+fn tokenize(expr: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.char_indices().peekable();
+    while let Some((pos, c)) = chars.next() {
+        let token = match c {
+            _ if c.is_whitespace() => continue,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            _ if c.is_ascii_digit() || c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = pos + c.len_utf8();
+                while let Some(&(i, next)) = chars.peek() {
+                    if !(next.is_ascii_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    end = i + next.len_utf8();
+                    chars.next();
+                }
+                let word = &expr[pos..end];
+                if c.is_ascii_digit() {
+                    // 数字で始まるなら全体が 10 進整数であること（`2x` のような連結は不可）
+                    let n = word
+                        .parse::<BigInt>()
+                        .map_err(|_| ExprError::UnexpectedToken { pos })?;
+                    Token::Num(n)
+                } else {
+                    Token::Ident(word.to_string())
+                }
+            }
+            _ => return Err(ExprError::InvalidChar { pos, found: c }),
+        };
+        tokens.push((pos, token));
+    }
+    Ok(tokens)
+}
+
+/// 解析中の部分式の値。線形結合 `Σ k_i · v_i + constant` のまま持ち、
+/// 変数どうしの掛け算が必要になったときだけ制約を発行する。
+struct Linear {
+    terms: Vec<(Variable, FieldElement)>,
+    constant: FieldElement,
+}
+
+impl Linear {
+    fn constant(c: FieldElement) -> Self {
+        Linear {
+            terms: Vec::new(),
+            constant: c,
+        }
+    }
+
+    fn variable(var: Variable, one: FieldElement) -> Self {
+        let zero = FieldElement::zero(&one.p);
+        Linear {
+            terms: vec![(var, one)],
+            constant: zero,
+        }
+    }
+
+    fn add(mut self, other: Linear) -> Self {
+        self.terms.extend(other.terms);
+        self.constant = &self.constant + &other.constant;
+        self
+    }
+
+    fn scale(self, k: &FieldElement) -> Self {
+        Linear {
+            terms: self.terms.into_iter().map(|(v, c)| (v, k * &c)).collect(),
+            constant: k * &self.constant,
+        }
+    }
+
+    /// 現在の代入値で評価する。
+    fn evaluate(&self, cs: &ConstraintSystem) -> FieldElement {
+        let mut acc = self.constant.clone();
+        for (v, k) in &self.terms {
+            let val = cs.assignments[v.0]
+                .as_ref()
+                .expect("variable in expression is unassigned");
+            acc = &acc + &(k * val);
+        }
+        acc
+    }
+
+    /// 制約に渡す [`LinearCombination`] にする。定数 0 なら [`CS_ONE`] の項は作らない。
+    fn into_lc(self) -> LinearCombination {
+        let mut lc = LinearCombination::new();
+        for (v, k) in self.terms {
+            lc.add_term(v, k);
+        }
+        if !self.constant.is_zero() {
+            lc.add_term(CS_ONE, self.constant);
+        }
+        lc
+    }
+}
+
+/// トークン列を読みながら `cs` にゲートを発行する再帰下降パーサ。
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// 式の長さ（エラー位置用）
+    end: usize,
+    inputs: &'a HashMap<&'a str, FieldElement>,
+    /// 確保済みの入力変数（同じ名前は同じ変数にする）
+    vars: HashMap<String, Variable>,
+    cs: ConstraintSystem,
+    p: &'a BigInt,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn next(&mut self) -> Result<(usize, Token), ExprError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or(ExprError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// `expr := term (('+' | '-') term)*`
+    fn expr(&mut self) -> Result<Linear, ExprError> {
+        let mut acc = self.term()?;
+        while let Some(op @ (Token::Plus | Token::Minus)) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            acc = match op {
+                Token::Plus => acc.add(rhs),
+                _ => acc.add(rhs.scale(&-FieldElement::one(self.p))),
+            };
+        }
+        Ok(acc)
+    }
+
+    /// `term := factor ('*' factor)*`
+    fn term(&mut self) -> Result<Linear, ExprError> {
+        let mut acc = self.factor()?;
+        while self.peek() == Some(&Token::Star) {
+            self.pos += 1;
+            let rhs = self.factor()?;
+            acc = self.mul(acc, rhs);
+        }
+        Ok(acc)
+    }
+
+    /// `factor := '-' factor | 整数 | 変数名 | '(' expr ')'`
+    fn factor(&mut self) -> Result<Linear, ExprError> {
+        let (pos, token) = self.next()?;
+        match token {
+            Token::Minus => Ok(self.factor()?.scale(&-FieldElement::one(self.p))),
+            Token::Num(n) => Ok(Linear::constant(FieldElement::new(n, self.p))),
+            Token::Ident(name) => self.input(&name).map(|v| Linear::variable(v, self.one())),
+            Token::LParen => {
+                let inner = self.expr()?;
+                match self.next() {
+                    Ok((_, Token::RParen)) => Ok(inner),
+                    Ok((pos, _)) => Err(ExprError::UnexpectedToken { pos }),
+                    Err(_) => Err(ExprError::UnexpectedToken { pos: self.end }),
+                }
+            }
+            _ => Err(ExprError::UnexpectedToken { pos }),
+        }
+    }
+
+    /// 片方が定数なら係数に掛けるだけ、両方が変数を含むなら掛け算ゲートを 1 本発行する。
+    fn mul(&mut self, a: Linear, b: Linear) -> Linear {
+        if a.terms.is_empty() {
+            return b.scale(&a.constant);
+        }
+        if b.terms.is_empty() {
+            return a.scale(&b.constant);
+        }
+        let va = self.materialize(a);
+        let vb = self.materialize(b);
+        let c = self.cs.mul(va, vb);
+        Linear::variable(c, self.one())
+    }
+
+    /// 線形結合を変数にする。すでに単独の変数（係数 1・定数 0）ならそのまま返す。
+    fn materialize(&mut self, value: Linear) -> Variable {
+        if let [(var, k)] = value.terms.as_slice() {
+            if k.is_one() && value.constant.is_zero() {
+                return *var;
+            }
+        }
+        self.cs.linear_combination(&value.terms, value.constant)
+    }
+
+    /// 入力変数 `name` を返す。初出なら確保して `inputs` の値を代入する。
+    fn input(&mut self, name: &str) -> Result<Variable, ExprError> {
+        if let Some(&var) = self.vars.get(name) {
+            return Ok(var);
+        }
+        let value = self
+            .inputs
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ExprError::UnknownVariable(name.to_string()))?;
+        let var = self.cs.alloc_named(name);
+        self.cs.assign(var, value);
+        self.vars.insert(name.to_string(), var);
+        Ok(var)
+    }
+
+    fn one(&self) -> FieldElement {
+        FieldElement::one(self.p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r1cs::x3_plus5_circuit;
+
+    const P: i64 = 101;
+
+    fn fe(v: i64) -> FieldElement {
+        FieldElement::new(v, P)
+    }
+
+    fn build(expr: &str, inputs: &[(&'static str, i64)]) -> (ConstraintSystem, Variable) {
+        let inputs: HashMap<&str, FieldElement> =
+            inputs.iter().map(|&(name, v)| (name, fe(v))).collect();
+        build_from_expr(expr, &inputs, &BigInt::from(P))
+    }
+
+    #[test]
+    fn cubic_matches_hand_built_demo() {
+        // main のデモと同じ y = x^3 + 5 回路（手で組んだもの）
+        let demo = x3_plus5_circuit(P);
+
+        let (cs, out) = build("x*x*x + 5", &[("x", 3)]);
+        assert_eq!(out, Variable(1));
+        assert_eq!(cs.names, demo.names);
+        assert_eq!(cs.num_public_variables, demo.num_public_variables);
+        assert_eq!(cs.to_matrices(), demo.to_matrices());
+
+        let witness = cs.generate_witness();
+        assert_eq!(witness, demo.generate_witness());
+        assert_eq!(witness[out.0], fe(32));
+        assert!(cs.is_satisfied(&witness));
+    }
+
+    #[test]
+    fn respects_precedence_parentheses_and_subtraction() {
+        let cases = [
+            ("1 + 2 * 3", 7),
+            ("(1 + 2) * 3", 9),
+            ("x - y - 1", 10 - 4 - 1),
+            ("(x + 1) * (y - 2) - -x", 11 * 2 + 10),
+            ("2 * (x * y) * 3", 240),
+            ("x * x - y * y", 100 - 16),
+        ];
+        for (expr, expected) in cases {
+            let (cs, out) = build(expr, &[("x", 10), ("y", 4)]);
+            let witness = cs.generate_witness();
+            assert_eq!(witness[out.0], fe(expected), "expr = {}", expr);
+            assert!(cs.is_satisfied(&witness), "expr = {}", expr);
+        }
+    }
+
+    #[test]
+    fn linear_parts_add_no_constraints() {
+        // 線形な式は出力への接続 1 本だけ。変数どうしの積ごとに 1 本増える
+        let (cs, _) = build("3 * x + 2 * (x - 7) + 1", &[("x", 5)]);
+        assert_eq!(cs.num_constraints(), 1);
+        // (x + 1) の実体化 1 本 + 積 1 本 + 出力 1 本
+        let (cs, _) = build("(x + 1) * x", &[("x", 5)]);
+        assert_eq!(cs.num_constraints(), 3);
+    }
+
+    #[test]
+    fn reports_malformed_expressions() {
+        let inputs: HashMap<&str, FieldElement> = [("x", fe(1))].into_iter().collect();
+        let p = BigInt::from(P);
+        let err = |expr: &str| try_build_from_expr(expr, &inputs, &p).err();
+        assert_eq!(err("x + y"), Some(ExprError::UnknownVariable("y".into())));
+        assert_eq!(
+            err("x / 2"),
+            Some(ExprError::InvalidChar { pos: 2, found: '/' })
+        );
+        assert_eq!(err("x +"), Some(ExprError::UnexpectedEnd));
+        assert_eq!(err("(x + 1"), Some(ExprError::UnexpectedToken { pos: 6 }));
+        assert_eq!(err("x x"), Some(ExprError::UnexpectedToken { pos: 2 }));
+        assert_eq!(err("2x"), Some(ExprError::UnexpectedToken { pos: 0 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown variable: z")]
+    fn build_from_expr_panics_on_error() {
+        build("z * z", &[("x", 1)]);
+    }
+}
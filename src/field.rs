@@ -3,15 +3,219 @@
 //! Groth16 実装の Layer 1。多項式・楕円曲線・QAPなどの上位レイヤーがこの上に構築される。
 //!
 //! ## 主要型
-//! - [`FieldElement`]: 法 `p` の元。`Add`, `Sub`, `Mul`, `Div` を実装。
+//! - [`FieldElement`]: 法 `p` の元。`Add`, `Sub`, `Mul`, `Div`, `Neg` を実装。
+//!   演算子は法の不一致で panic し、`checked_*` は [`FieldError`] を返す。
+//! - [`FieldError`]: 法の不一致・0 除算
+//! - [`SharedModulus`]: 元どうしで共有する法（`Rc<BigInt>`、`sync` feature で `Arc<BigInt>`）
 //!
 //! ## 制約
-//! - [`FieldElement::sqrt`] は `p ≡ 3 (mod 4)` の素数でのみ計算する。
-//!   それ以外は `None` を返す（Tonelli-Shanks 法は未実装）。
+//! - [`FieldElement::sqrt`] は奇素数 `p` を前提とする。`p ≡ 3 (mod 4)` は
+//!   高速パス、それ以外は Tonelli-Shanks 法で計算する。
 
-use num_bigint::BigInt;
+use ark_std::rand::Rng;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, Zero};
+use std::cell::RefCell;
 use std::fmt;
-use std::ops::{Add, Div, Mul, Sub};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::barrett::BarrettReducer;
+
+/// 有限体の演算で起こりうるエラー。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+    /// 法の異なる元どうしを演算しようとした。`left` / `right` は各オペランドの法。
+    ModulusMismatch { left: BigInt, right: BigInt },
+    /// 0 で割ろうとした。
+    DivisionByZero,
+    /// 法が素数でない（[`FieldElement::new_checked`] が Miller-Rabin で検出）。
+    NonPrimeModulus,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldError::ModulusMismatch { left, right } => {
+                write!(f, "異なる標数の体では計算できません: {} != {}", left, right)
+            }
+            FieldError::DivisionByZero => write!(f, "division by zero"),
+            FieldError::NonPrimeModulus => write!(f, "modulus is not prime"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Miller-Rabin のラウンドで使う証人（witness）。先頭から `rounds` 個を使う。
+const MILLER_RABIN_BASES: [u32; 20] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+];
+
+/// [`FieldElement::new_checked`] が使う Miller-Rabin のラウンド数。
+const PRIMALITY_ROUNDS: usize = 20;
+
+/// `n` が（おそらく）素数かを Miller-Rabin 法で判定する。
+///
+/// `n - 1 = d · 2^s`（`d` は奇数）と分解し、各証人 `a` について
+/// `a^d ≡ 1` または `a^(d·2^r) ≡ -1 (r < s)` のどれかが成り立つか確かめる。
+/// 成り立たない `a` が 1 つでもあれば `n` は合成数と確定する。
+///
+/// 証人は乱数ではなく小さい素数 2, 3, 5, ... の先頭 `rounds` 個（最大 20 個）を使う。
+/// 先頭 13 個で `n < 3.3 × 10^24` は決定的に判定でき、それより大きい `n` でも
+/// 誤判定の確率は 1 ラウンドあたり高々 1/4。カーマイケル数（561 など）も弾ける。
+pub fn is_probable_prime(n: &BigInt, rounds: usize) -> bool {
+    let zero = BigInt::from(0);
+    let one = BigInt::from(1);
+    let two = BigInt::from(2);
+
+    if n < &two {
+        return false;
+    }
+    // 小さい素数そのもの / その倍数を先に片付ける
+    for &b in MILLER_RABIN_BASES.iter() {
+        let b = BigInt::from(b);
+        if n == &b {
+            return true;
+        }
+        if n % &b == zero {
+            return false;
+        }
+    }
+
+    // n - 1 = d · 2^s
+    let n_minus_one = n - &one;
+    let mut d = n_minus_one.clone();
+    let mut s = 0u32;
+    while &d % &two == zero {
+        d /= &two;
+        s += 1;
+    }
+
+    'witness: for &a in MILLER_RABIN_BASES.iter().take(rounds) {
+        let mut x = BigInt::from(a).modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// `n` の相異なる素因数を昇順で返す（試し割り）。`n <= 1` のときは空。
+fn prime_factors(n: &BigInt) -> Vec<BigInt> {
+    let zero = BigInt::from(0);
+    let mut factors = Vec::new();
+    let mut rest = n.clone();
+    let mut d = BigInt::from(2);
+    while &d * &d <= rest {
+        if &rest % &d == zero {
+            factors.push(d.clone());
+            while &rest % &d == zero {
+                rest /= &d;
+            }
+        }
+        d += 1;
+    }
+    if rest > BigInt::from(1) {
+        factors.push(rest);
+    }
+    factors
+}
+
+/// 元どうしで共有する法 `p`。
+///
+/// 演算の結果は入力と同じ法を持つので、`BigInt` を毎回 clone する代わりに
+/// 参照カウントを増やすだけで済ませる（大きな素数ほど確保とコピーのコストが効く）。
+/// `sync` feature を有効にするとスレッド間で共有できる `Arc` になる。
+#[cfg(not(feature = "sync"))]
+pub type SharedModulus = std::rc::Rc<BigInt>;
+#[cfg(feature = "sync")]
+pub type SharedModulus = std::sync::Arc<BigInt>;
+
+/// [`FieldElement::new`] などが法として受け付ける型。
+///
+/// [`SharedModulus`] を渡せば共有され、`BigInt` や整数を渡せば新しく包む。
+/// 以前の `p: impl Into<BigInt>` / `p: &BigInt` で渡せた型（整数型、`BigUint`、
+/// `&&BigInt` のような参照の参照）はすべて受け付ける。
+pub trait IntoModulus {
+    /// [`SharedModulus`] に変換する。
+    fn into_modulus(self) -> SharedModulus;
+}
+
+impl IntoModulus for SharedModulus {
+    fn into_modulus(self) -> SharedModulus {
+        self
+    }
+}
+
+impl IntoModulus for &SharedModulus {
+    fn into_modulus(self) -> SharedModulus {
+        self.clone()
+    }
+}
+
+impl IntoModulus for BigInt {
+    fn into_modulus(self) -> SharedModulus {
+        SharedModulus::new(self)
+    }
+}
+
+impl IntoModulus for &BigInt {
+    fn into_modulus(self) -> SharedModulus {
+        SharedModulus::new(self.clone())
+    }
+}
+
+macro_rules! impl_into_modulus_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl IntoModulus for $t {
+                fn into_modulus(self) -> SharedModulus {
+                    SharedModulus::new(BigInt::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_modulus_for_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, BigUint);
+
+/// `&p`（`p: &BigInt` など）を渡していた呼び出しのため。以前は参照外しの型強制で通っていた。
+impl<T> IntoModulus for &&T
+where
+    for<'a> &'a T: IntoModulus,
+{
+    fn into_modulus(self) -> SharedModulus {
+        (*self).into_modulus()
+    }
+}
+
+thread_local! {
+    /// 直前に乗算で使った法と、その Barrett 前計算。
+    static BARRETT_CACHE: RefCell<Option<(SharedModulus, BarrettReducer)>> =
+        const { RefCell::new(None) };
+}
+
+/// 2 つの正規化済みの元の積 `x < p^2` を Barrett 還元で `x mod p` にする。
+///
+/// 前計算はスレッドごとに直前の法の分だけ持つ。同じ回路の元は同じ [`SharedModulus`]
+/// を共有するので、ほとんどの乗算はポインタ比較だけで前計算を使い回せる。
+fn barrett_reduce(p: &SharedModulus, x: &BigInt) -> BigInt {
+    BARRETT_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let hit = matches!(&*cache, Some((q, _)) if SharedModulus::ptr_eq(q, p) || q == p);
+        if !hit {
+            *cache = Some((p.clone(), BarrettReducer::new(p)));
+        }
+        cache.as_ref().unwrap().1.reduce(x)
+    })
+}
 
 /// 有限体 GF(p) 上の元を表す。
 /// 
@@ -25,18 +229,24 @@ use std::ops::{Add, Div, Mul, Sub};
 /// let b = FieldElement::new(5, 7);
 /// let sum = &a + &b; // 8 mod 7 = 1
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// `Hash` は正規化済みの `value` と `p` から計算するので `Eq` と整合し、
+/// `HashMap` / `HashSet` のキーに使える。
+///
+/// `p` は [`SharedModulus`] で持つので、演算結果や `clone` は法を複製せず共有する。
+/// 比較・ハッシュは `p` の指す値で行う（別々に作った同じ法の元も等しい）。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FieldElement {
-    pub value: BigInt, // 値
-    pub p: BigInt,     // 法となる素数
+    pub value: BigInt,    // 値
+    pub p: SharedModulus, // 法となる素数
 }
 
 impl FieldElement {
     /// 法 `p` のもとで `value` を正規化した `FieldElement` を生成する。
     ///
     /// 負の値や `p` を超える値も自動で `0 <= v < p` に丸める。
-    /// `value` と `p` は `Into<BigInt>` を満たす任意の型を受け付ける
-    /// （`i32`, `i64`, `BigInt` など）。
+    /// `value` は `Into<BigInt>` を満たす任意の型（`i32`, `i64`, `BigInt` など）、
+    /// `p` はそれに加えて [`SharedModulus`] も受け付ける（[`IntoModulus`]）。
     ///
     /// # 例
     ///
@@ -44,19 +254,193 @@ impl FieldElement {
     /// let a = FieldElement::new(-1, 7);  // value == 6
     /// let b = FieldElement::new(10, 7);  // value == 3
     /// ```
-    pub fn new(value: impl Into<BigInt>, p: impl Into<BigInt>) -> Self {
+    pub fn new(value: impl Into<BigInt>, p: impl IntoModulus) -> Self {
         let value = value.into();
-        let p = p.into();
+        let p = p.into_modulus();
         // 値が 0 <= value < p の範囲に収まるように正規化
         // Rust の % は余りを求める演算子であるため、負数を割ると結果がマイナスになる。
         // そこで、「負の数」を「正の整数」に無理やり引き戻す。
-        let normalized_value = ((value % &p) + &p) % &p;
+        let normalized_value = ((value % &*p) + &*p) % &*p;
         FieldElement {
             value: normalized_value,
             p,
         }
     }
 
+    /// [`new`](Self::new) の検査付き版。`p` が素数でなければ
+    /// [`FieldError::NonPrimeModulus`] を返す。
+    ///
+    /// 合成数の法では逆元が存在しない元があり、後段の `inverse()` や除算が
+    /// 失敗するので、外部から受け取った `p` はこちらで検査する。
+    /// 判定は [`is_probable_prime`] による（毎回走るので、法が既知の場合は `new` を使う）。
+    pub fn new_checked(value: impl Into<BigInt>, p: impl IntoModulus) -> Result<Self, FieldError> {
+        let p = p.into_modulus();
+        if !is_probable_prime(&p, PRIMALITY_ROUNDS) {
+            return Err(FieldError::NonPrimeModulus);
+        }
+        Ok(FieldElement::new(value, p))
+    }
+
+    /// 法 `p` のもとでの 0 を返す。
+    ///
+    /// `&x.p`（[`SharedModulus`]）を渡せば法を共有し、`&BigInt` なら 1 回だけ clone する。
+    pub fn zero(p: impl IntoModulus) -> Self {
+        FieldElement::new(0, p)
+    }
+
+    /// 法 `p` のもとでの 1 を返す。法の扱いは [`zero`](Self::zero) と同じ。
+    pub fn one(p: impl IntoModulus) -> Self {
+        FieldElement::new(1, p)
+    }
+
+    /// `u64` の値 `v` から法 `p` の元を作る。`p` 未満でなければ正規化される。
+    pub fn from_u64(v: u64, p: impl IntoModulus) -> Self {
+        FieldElement::new(v, p)
+    }
+
+    /// 法 `p` の元を `[0, p)` から一様ランダムに引く。
+    ///
+    /// `p` のビット長 `k` ぶんの乱数を作り（最上位バイトの余分なビットはマスク）、
+    /// `p` 以上なら引き直す（棄却サンプリング）。1 回あたりの棄却確率は 1/2 未満。
+    /// toxic waste や blinding factor の生成に使う想定で、乱数源は呼び出し側が渡す。
+    pub fn random<R: Rng>(rng: &mut R, p: impl IntoModulus) -> Self {
+        let p = p.into_modulus();
+        let bits = p.bits();
+        let num_bytes = bits.div_ceil(8) as usize;
+        // 最上位バイトで使うビット数（0 なら 8 ビット全部使う）
+        let top_bits = (bits % 8) as u32;
+        let mut bytes = vec![0u8; num_bytes];
+        loop {
+            rng.fill(&mut bytes[..]);
+            if top_bits != 0 {
+                bytes[0] &= (1u8 << top_bits) - 1;
+            }
+            let candidate = BigInt::from_bytes_be(Sign::Plus, &bytes);
+            if candidate < *p {
+                return FieldElement {
+                    value: candidate,
+                    p,
+                };
+            }
+        }
+    }
+
+    /// 乗法群 `F_p^*` の生成元（原始根）のうち最小のものを返す。
+    ///
+    /// `p - 1` を素因数分解し、`g = 2, 3, ...` を順に試して、`p - 1` の全ての
+    /// 素因数 `q` について `g^((p-1)/q) != 1` となる最初の `g` を返す
+    /// （そのとき `g` の位数はちょうど `p - 1`）。
+    /// 素因数分解は試し割りなので、`p - 1` が大きな素因数を持つ体
+    /// （BN254 の `Fr` など）では現実的な時間で終わらない。小さな体向け。
+    ///
+    /// `p` は素数前提。`p == 2` のときは 1 を返す。
+    pub fn primitive_root(p: &BigInt) -> Self {
+        let one = FieldElement::one(p);
+        let p_minus_one = p - BigInt::from(1);
+        let factors = prime_factors(&p_minus_one);
+
+        let mut g = one.clone();
+        loop {
+            let is_generator = factors
+                .iter()
+                .all(|q| !g.pow_ref(&(&p_minus_one / q)).is_one());
+            if is_generator {
+                return g;
+            }
+            g = &g + &one;
+        }
+    }
+
+    /// 位数がちょうど `n` の元（1 の原始 `n` 乗根）を返す。
+    ///
+    /// `F_p^*` は位数 `p - 1` の巡回群なので、`n | (p - 1)` のときに限り存在し、
+    /// 原始根 `g` から `g^((p-1)/n)` として得られる。割り切れなければ `None`。
+    /// 原始根の計算に [`primitive_root`](Self::primitive_root) を使うため、同じく小さな体向け。
+    pub fn nth_root_of_unity(n: u64, p: &BigInt) -> Option<Self> {
+        let p_minus_one = p - BigInt::from(1);
+        let n = BigInt::from(n);
+        if n == BigInt::from(0) || &p_minus_one % &n != BigInt::from(0) {
+            return None;
+        }
+        let g = FieldElement::primitive_root(p);
+        Some(g.pow_ref(&(&p_minus_one / &n)))
+    }
+
+    /// 値を固定長のビッグエンディアンのバイト列に変換する。
+    ///
+    /// 長さは `ceil(bits(p) / 8)` バイトで、足りない上位側は 0 埋めする。
+    /// よって 0 は全バイト 0 になる。
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let width = self.p.bits().div_ceil(8) as usize;
+        let (_sign, raw) = self.value.to_bytes_be();
+        let mut bytes = vec![0u8; width];
+        // value < p なので raw.len() <= width。0 のとき raw は [0] の 1 バイト
+        bytes[width - raw.len()..].copy_from_slice(&raw);
+        bytes
+    }
+
+    /// ビッグエンディアンのバイト列を読み、法 `p` で簡約した元を返す。
+    ///
+    /// [`to_bytes`](Self::to_bytes) の逆変換。幅より長い入力や `p` 以上の値も
+    /// panic せず `mod p` に丸める。空スライスは 0 になる。
+    pub fn from_bytes(bytes: &[u8], p: impl IntoModulus) -> Self {
+        FieldElement::new(BigInt::from_bytes_be(Sign::Plus, bytes), p)
+    }
+
+    /// 値が 0 かどうかを返す。`BigInt` を新たに確保せずに判定する。
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    /// 値が 1 かどうかを返す。`BigInt` を新たに確保せずに判定する。
+    pub fn is_one(&self) -> bool {
+        self.value.is_one()
+    }
+
+    /// 2 つの元の法が一致するか確認する。
+    fn check_modulus(&self, other: &Self) -> Result<(), FieldError> {
+        // 同じ法から作った元どうしは同じ SharedModulus を指すので、値の比較を省ける
+        if SharedModulus::ptr_eq(&self.p, &other.p) || self.p == other.p {
+            Ok(())
+        } else {
+            Err(FieldError::ModulusMismatch {
+                left: (*self.p).clone(),
+                right: (*other.p).clone(),
+            })
+        }
+    }
+
+    /// 加法。法が異なる場合は [`FieldError::ModulusMismatch`] を返す。
+    pub fn checked_add(&self, other: &Self) -> Result<Self, FieldError> {
+        self.check_modulus(other)?;
+        Ok(FieldElement::new(&self.value + &other.value, &self.p))
+    }
+
+    /// 減法。法が異なる場合は [`FieldError::ModulusMismatch`] を返す。
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, FieldError> {
+        self.check_modulus(other)?;
+        Ok(FieldElement::new(&self.value - &other.value, &self.p))
+    }
+
+    /// 乗法。法が異なる場合は [`FieldError::ModulusMismatch`] を返す。
+    ///
+    /// 積は `0 <= a·b < p^2` なので、`% p` の代わりに Barrett 還元で正規化する。
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, FieldError> {
+        self.check_modulus(other)?;
+        Ok(FieldElement {
+            value: barrett_reduce(&self.p, &(&self.value * &other.value)),
+            p: self.p.clone(),
+        })
+    }
+
+    /// 除法。法が異なる場合は [`FieldError::ModulusMismatch`]、
+    /// `other == 0` の場合は [`FieldError::DivisionByZero`] を返す。
+    pub fn checked_div(&self, other: &Self) -> Result<Self, FieldError> {
+        self.check_modulus(other)?;
+        let inv = other.inverse().ok_or(FieldError::DivisionByZero)?;
+        self.checked_mul(&inv)
+    }
+
     /// 逆元 a^-1 mod p を求める。0 の場合は None を返す。
     /// 
     /// 内部的には `BigInt::modinv` を使い、拡張ユークリッド法で計算する。
@@ -64,64 +448,162 @@ impl FieldElement {
     /// 同じ結果が得られるが、拡張ユークリッド法の方が速い。
     pub fn inverse(&self) -> Option<Self> {
         let inv_value = self.value.modinv(&self.p)?;
-        Some(FieldElement::new(inv_value, self.p.clone()))
+        Some(FieldElement::new(inv_value, &self.p))
+    }
+
+    /// `elems` の各元の逆元をまとめて求める（Montgomery's trick）。
+    ///
+    /// 前方累積積 `prefix[i] = e_0 · e_1 · ... · e_{i-1}` を作り、総積の逆元を
+    /// 1 回だけ計算してから後ろ向きに `e_i^{-1} = prefix[i] · (e_0...e_i)^{-1}` を
+    /// 取り出す。逆元計算 1 回 + 乗算 `O(n)` 回で済む。
+    /// 空スライスには空 vec を返す。
+    ///
+    /// # Panics
+    /// `elems` に 0 が含まれる場合 panic する（総積が 0 になり逆元が存在しないため）。
+    pub fn batch_inverse(elems: &[FieldElement]) -> Vec<FieldElement> {
+        if elems.is_empty() {
+            return Vec::new();
+        }
+
+        // 1. 前方累積積: prefix[i] = e_0 · ... · e_{i-1}
+        let mut prefix = Vec::with_capacity(elems.len());
+        let mut acc = FieldElement::one(&elems[0].p);
+        for e in elems {
+            prefix.push(acc.clone());
+            acc = &acc * e;
+        }
+
+        // 2. 総積の逆元を 1 回だけ計算
+        let mut inv_acc = acc
+            .inverse()
+            .expect("batch_inverse: elems contains zero");
+
+        // 3. 後ろから e_i^{-1} = prefix[i] · (e_0...e_i)^{-1} を取り出し、
+        //    (e_0...e_{i-1})^{-1} = (e_0...e_i)^{-1} · e_i に更新する
+        let mut result = vec![FieldElement::zero(&elems[0].p); elems.len()];
+        for i in (0..elems.len()).rev() {
+            result[i] = &prefix[i] * &inv_acc;
+            inv_acc = &inv_acc * &elems[i];
+        }
+        result
+    }
+
+    /// 割り算 `a / b = a * b^{-1}`。`b == 0` のときは `None` を返す。
+    ///
+    /// 法 `p` が異なる場合は他の演算と同様に panic する。
+    pub fn try_div(&self, other: &Self) -> Option<Self> {
+        Some(self * &other.inverse()?)
     }
 
     /// 割り算 `a / b = a * b^{-1}`。`b == 0` のとき panic する。
+    ///
+    /// 0 除算を回復したい場合は [`try_div`](Self::try_div) を使う。
     pub fn div(&self, other: &Self) -> Self {
-        self * &other.inverse().expect("division by zero")
+        self.try_div(other).expect("division by zero")
+    }
+
+    /// `self^2` を返す。
+    pub fn square(&self) -> Self {
+        self * self
+    }
+
+    /// `2 · self` を返す。
+    pub fn double(&self) -> Self {
+        self + self
     }
 
     /// `self^exponent mod p` を計算する。
     ///
     /// 繰り返し二乗法（square-and-multiply）で `O(log exponent)` 時間。
+    /// 指数を借用で済ませたい場合は [`pow_ref`](Self::pow_ref) を使う。
     pub fn pow(&self, exponent: impl Into<BigInt>) -> Self {
-        let mut res = FieldElement::new(BigInt::from(1), self.p.clone());
-        let mut base = self.clone();
-        let mut exp = exponent.into();
+        self.pow_ref(&exponent.into())
+    }
 
-        let zero = BigInt::from(0);
-        let two = BigInt::from(2);
+    /// [`pow`](Self::pow) の指数借用版。`exponent` を消費しない。
+    ///
+    /// 指数のビットを下位から走査し、ビットが 1 なら現在の `base` を結果に掛ける。
+    /// 最後のビットを処理した後は `base` の二乗を行わない。
+    /// `exponent <= 0` のときは 1 を返す。
+    pub fn pow_ref(&self, exponent: &BigInt) -> Self {
+        let mut res = FieldElement::one(&self.p);
+        if exponent <= &BigInt::from(0) {
+            return res;
+        }
 
-        while exp > zero {
-            // 指数の最下位ビットが1（奇数）なら、現在の base を結果に掛ける
-            if &exp % &two != zero {
+        let mut base = self.clone();
+        let num_bits = exponent.bits();
+        for i in 0..num_bits {
+            // 指数の i ビット目が 1 なら、現在の base を結果に掛ける
+            if exponent.bit(i) {
                 res = &res * &base;
             }
-            // base を二乗する（base = base^2）
-            base = &base * &base;
-
-            // 指数を半分にする（右シフト）
-            exp = &exp / &two;
+            // base を二乗する（base = base^2）。最終ビットの後は不要
+            if i + 1 < num_bits {
+                base = base.square();
+            }
         }
         res
     }
 
+    /// ルジャンドル記号 `(self / p)` を返す。
+    ///
+    /// - `0`: `self == 0`
+    /// - `1`: 平方剰余（`√self` が存在する）
+    /// - `-1`: 平方非剰余
+    ///
+    /// オイラーの規準 `self^((p-1)/2) ≡ ±1 (mod p)` で計算する。`p` は奇素数前提。
+    pub fn legendre(&self) -> i32 {
+        if self.is_zero() {
+            return 0;
+        }
+        let exponent = (&*self.p - BigInt::from(1)) / BigInt::from(2);
+        if self.pow(exponent).is_one() {
+            1
+        } else {
+            -1
+        }
+    }
+
     /// モジュラ平方根 `√self mod p` を返す。
     ///
     /// - 平方剰余でない場合: `None`
-    /// - `p % 4 != 3` の場合: `None`（Tonelli-Shanks 法は未実装）
     /// - それ以外: `Some(root)` を返す。`root` と `p - root` の 2 つの根のうち
     ///   どちらか一方が返る（どちらかは保証しない）。
     ///
+    /// `p = 2` では `0^2 = 0`, `1^2 = 1` なのでそのまま返す。
+    ///
     /// # アルゴリズム
     ///
-    /// `p ≡ 3 (mod 4)` のとき、`a^((p+1)/4) mod p` が候補となる。
-    /// 検算 (`root^2 == self`) で平方剰余かどうかを判定する。
+    /// `p ≡ 3 (mod 4)` のときは `a^((p+1)/4) mod p` が候補となる（高速パス）。
+    /// それ以外の素数では Tonelli-Shanks 法で計算する。
+    /// いずれも検算 (`root^2 == self`) で平方剰余かどうかを判定する。
     pub fn sqrt(&self) -> Option<Self> {
         // 1. 定数の準備
+        let one = BigInt::from(1);
         let three = BigInt::from(3);
         let four = BigInt::from(4);
-        let one = BigInt::from(1);
+
+        // F_2 には平方非剰余がなく Tonelli-Shanks の z 探索が止まらないので先に返す
+        if *self.p == BigInt::from(2) {
+            return Some(self.clone());
+        }
+
+        // √0 = 0、平方非剰余は根の計算前に弾く
+        match self.legendre() {
+            0 => return Some(self.clone()),
+            -1 => return None,
+            _ => {}
+        }
 
         // 2. 素数の型チェック（p % 4 == 3 か？）
-        // Tonelli-Shanks 法は未実装のため、上記以外の素数では None を返す
-        if &self.p % &four != three {
-            return None;
+        // 上記以外の素数では Tonelli-Shanks 法に回す
+        if &*self.p % &four != three {
+            return self.tonelli_shanks();
         }
 
         // 3. 指数の計算: exponent = (p + 1) / 4
-        let exponent = (&self.p + &one) / &four;
+        let exponent = (&*self.p + &one) / &four;
 
         // 4. 候補の計算: root = self^exponent
         let root = self.pow(exponent);
@@ -134,6 +616,67 @@ impl FieldElement {
             None
         }
     }
+
+    /// Tonelli-Shanks 法で `√self mod p` を求める（[`sqrt`](Self::sqrt) の一般パス）。
+    ///
+    /// `p - 1 = q · 2^s`（`q` は奇数）と分解し、平方非剰余 `z` を使って
+    /// 候補 `r = a^((q+1)/2)` の誤差 `t = a^q` を 2 冪位数の部分群の中で 1 に追い込む。
+    /// `self` は 0 でない平方剰余であることを前提とする（[`legendre`](Self::legendre) で確認済み）。
+    fn tonelli_shanks(&self) -> Option<Self> {
+        let one = BigInt::from(1);
+        let two = BigInt::from(2);
+        let p_minus_one = &*self.p - &one;
+        let fe_one = FieldElement::new(1, self.p.clone());
+
+        // 1. p - 1 = q · 2^s に分解
+        let mut q = p_minus_one.clone();
+        let mut s = 0u32;
+        while &q % &two == BigInt::from(0) {
+            q /= &two;
+            s += 1;
+        }
+
+        // 2. 平方非剰余 z を 2, 3, 4, ... から線形探索する
+        // （素数なら約半数が非剰余なのですぐ見つかる）
+        let mut z = FieldElement::new(2, self.p.clone());
+        while z.legendre() != -1 {
+            z = &z + &fe_one;
+        }
+
+        // 3. 初期値
+        let mut m = s;
+        let mut c = z.pow_ref(&q);
+        let mut t = self.pow_ref(&q);
+        let mut r = self.pow((&q + &one) / &two);
+
+        // 4. t == 1 になるまで位数を下げていく
+        while t != fe_one {
+            // t^(2^i) == 1 となる最小の i (0 < i < m) を探す
+            let mut i = 0u32;
+            let mut t2i = t.clone();
+            while t2i != fe_one {
+                t2i = t2i.square();
+                i += 1;
+                if i == m {
+                    // 平方剰余なら起こらない（legendre で弾いている）
+                    return None;
+                }
+            }
+
+            // b = c^(2^(m-i-1))
+            let mut b = c.clone();
+            for _ in 0..(m - i - 1) {
+                b = b.square();
+            }
+
+            m = i;
+            c = b.square();
+            t = &t * &c;
+            r = &r * &b;
+        }
+
+        Some(r)
+    }
 }
 
 /// `&a + &b`: 加法。法 `p` が異なる場合は panic する。
@@ -141,8 +684,7 @@ impl<'b> Add<&'b FieldElement> for &FieldElement {
     type Output = FieldElement;
 
     fn add(self, other: &'b FieldElement) -> FieldElement {
-        assert_eq!(self.p, other.p, "異なる標数の体では計算できません");
-        FieldElement::new(&self.value + &other.value, self.p.clone())
+        self.checked_add(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -151,8 +693,7 @@ impl<'b> Sub<&'b FieldElement> for &FieldElement {
     type Output = FieldElement;
 
     fn sub(self, other: &'b FieldElement) -> FieldElement {
-        assert_eq!(self.p, other.p, "異なる標数の体では計算できません");
-        FieldElement::new(&self.value - &other.value, self.p.clone())
+        self.checked_sub(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -161,8 +702,7 @@ impl<'b> Mul<&'b FieldElement> for &FieldElement {
     type Output = FieldElement;
 
     fn mul(self, other: &'b FieldElement) -> FieldElement {
-        assert_eq!(self.p, other.p, "異なる標数の体では計算できません");
-        FieldElement::new(&self.value * &other.value, self.p.clone())
+        self.checked_mul(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -172,9 +712,63 @@ impl<'b> Div<&'b FieldElement> for &FieldElement {
     type Output = FieldElement;
 
     fn div(self, other: &'b FieldElement) -> FieldElement {
-        assert_eq!(self.p, other.p, "異なる標数の体では計算できません");
         // 有限体の割り算は a * (bの逆元)
-        self * &other.inverse().expect("division by zero")
+        self.checked_div(other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+/// `-&a`: 加法逆元 `p - value`。`-0 == 0`。
+impl Neg for &FieldElement {
+    type Output = FieldElement;
+
+    fn neg(self) -> FieldElement {
+        // new() の正規化により value == 0 のとき p - 0 = p は 0 に丸められる
+        FieldElement::new(&*self.p - &self.value, &self.p)
+    }
+}
+
+/// `-a`: 値渡し版の加法逆元。参照版に委譲する。
+impl Neg for FieldElement {
+    type Output = FieldElement;
+
+    fn neg(self) -> FieldElement {
+        -&self
+    }
+}
+
+/// `a + b`: 値渡し版の加法。参照版に委譲するので法のチェックも同じ。
+impl Add<FieldElement> for FieldElement {
+    type Output = FieldElement;
+
+    fn add(self, other: FieldElement) -> FieldElement {
+        &self + &other
+    }
+}
+
+/// `a - b`: 値渡し版の減法。参照版に委譲するので法のチェックも同じ。
+impl Sub<FieldElement> for FieldElement {
+    type Output = FieldElement;
+
+    fn sub(self, other: FieldElement) -> FieldElement {
+        &self - &other
+    }
+}
+
+/// `a * b`: 値渡し版の乗法。参照版に委譲するので法のチェックも同じ。
+impl Mul<FieldElement> for FieldElement {
+    type Output = FieldElement;
+
+    fn mul(self, other: FieldElement) -> FieldElement {
+        &self * &other
+    }
+}
+
+/// `a / b`: 値渡し版の除法。参照版に委譲する（`b == 0` で panic）。
+impl Div<FieldElement> for FieldElement {
+    type Output = FieldElement;
+
+    fn div(self, other: FieldElement) -> FieldElement {
+        &self / &other
     }
 }
 
@@ -186,6 +780,76 @@ impl fmt::Display for FieldElement {
     }
 }
 
+/// `{:x}`: 値を 16 進（小文字）で表示する。`Display` と違い ` mod p` は付けない。
+///
+/// `{:#x}` で `0x` 接頭辞が付く。
+impl fmt::LowerHex for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.value, f)
+    }
+}
+
+/// `{:X}`: 値を 16 進（大文字）で表示する。`Display` と違い ` mod p` は付けない。
+impl fmt::UpperHex for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.value, f)
+    }
+}
+
+/// `value` と `p` を 10 進文字列として持つ構造体形式でシリアライズする。
+///
+/// `BigInt` は serde 非対応なので文字列を経由する。
+/// 例（JSON）: `{"value":"3","p":"7"}`
+#[cfg(feature = "serde")]
+impl serde::Serialize for FieldElement {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut st = serializer.serialize_struct("FieldElement", 2)?;
+        st.serialize_field("value", &self.value.to_str_radix(10))?;
+        st.serialize_field("p", &self.p.to_str_radix(10))?;
+        st.end()
+    }
+}
+
+/// [`Serialize`](serde::Serialize) の逆。読み込み後に [`FieldElement::new`] で正規化する。
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FieldElement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{self, MapAccess, Visitor};
+
+        struct FieldElementVisitor;
+
+        impl<'de> Visitor<'de> for FieldElementVisitor {
+            type Value = FieldElement;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a FieldElement { value, p } with decimal strings")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<FieldElement, A::Error> {
+                let mut value: Option<String> = None;
+                let mut p: Option<String> = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "value" => value = Some(map.next_value()?),
+                        "p" => p = Some(map.next_value()?),
+                        _ => return Err(de::Error::unknown_field(&key, &["value", "p"])),
+                    }
+                }
+                let value = value.ok_or_else(|| de::Error::missing_field("value"))?;
+                let p = p.ok_or_else(|| de::Error::missing_field("p"))?;
+                let parse = |s: &str| {
+                    BigInt::parse_bytes(s.as_bytes(), 10)
+                        .ok_or_else(|| de::Error::custom(format!("invalid decimal: {}", s)))
+                };
+                Ok(FieldElement::new(parse(&value)?, parse(&p)?))
+            }
+        }
+
+        deserializer.deserialize_struct("FieldElement", &["value", "p"], FieldElementVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +870,169 @@ mod tests {
         assert_eq!(fe(10, 7).value, BigInt::from(3));
     }
 
+    #[test]
+    fn is_probable_prime_known_primes() {
+        let bn254 = BigInt::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            10,
+        )
+        .unwrap();
+        for p in [2, 3, 7, 17, 41, 257, 65537, 1_000_000_007] {
+            assert!(is_probable_prime(&BigInt::from(p), 20), "p = {}", p);
+        }
+        assert!(is_probable_prime(&bn254, 20));
+    }
+
+    #[test]
+    fn is_probable_prime_rejects_composites() {
+        // 561 = 3·11·17 はカーマイケル数（フェルマーテストはすり抜ける）
+        for n in [0, 1, 4, 15, 561, 1105, 7919 * 7927] {
+            assert!(!is_probable_prime(&BigInt::from(n), 20), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn new_checked_rejects_composite_modulus() {
+        assert_eq!(
+            FieldElement::new_checked(3, 561),
+            Err(FieldError::NonPrimeModulus)
+        );
+        assert_eq!(FieldElement::new_checked(10, 7), Ok(fe(3, 7)));
+    }
+
+    #[test]
+    fn prime_factors_basic() {
+        let as_big = |v: &[i64]| v.iter().map(|&x| BigInt::from(x)).collect::<Vec<_>>();
+        assert_eq!(prime_factors(&BigInt::from(16)), as_big(&[2]));
+        assert_eq!(prime_factors(&BigInt::from(40)), as_big(&[2, 5]));
+        assert_eq!(prime_factors(&BigInt::from(97)), as_big(&[97]));
+        assert!(prime_factors(&BigInt::from(1)).is_empty());
+    }
+
+    #[test]
+    fn primitive_root_of_17_is_3() {
+        let p = BigInt::from(17);
+        let g = FieldElement::primitive_root(&p);
+        assert_eq!(g, fe(3, 17));
+        // g の冪で F_17^* の全元（16 個）を巡回すること
+        let mut seen = std::collections::HashSet::new();
+        let mut x = fe(1, 17);
+        for _ in 0..16 {
+            seen.insert(x.clone());
+            x = &x * &g;
+        }
+        assert_eq!(seen.len(), 16);
+    }
+
+    #[test]
+    fn nth_root_of_unity_has_exact_order() {
+        let p = BigInt::from(17);
+        for n in [1u64, 2, 4, 8, 16] {
+            let w = FieldElement::nth_root_of_unity(n, &p).unwrap();
+            assert!(w.pow(n).is_one());
+            // 位数がちょうど n（n 未満の正の冪では 1 にならない）
+            for k in 1..n {
+                assert!(!w.pow(k).is_one(), "n = {}, k = {}", n, k);
+            }
+        }
+        // 5 は 16 を割り切らない
+        assert!(FieldElement::nth_root_of_unity(5, &p).is_none());
+    }
+
+    #[test]
+    fn operations_share_modulus_and_match_bigint_arithmetic() {
+        // 結果は BigInt で直接計算した値と一致し、法は clone ではなく共有される
+        let p = SharedModulus::new(BigInt::from(101));
+        for a in [0i64, 1, 37, 100] {
+            for b in [1i64, 2, 58, 100] {
+                let (x, y) = (FieldElement::new(a, &p), FieldElement::new(b, &p));
+                let naive = |v: i64| BigInt::from(v.rem_euclid(101));
+                assert_eq!((&x + &y).value, naive(a + b));
+                assert_eq!((&x - &y).value, naive(a - b));
+                assert_eq!((&x * &y).value, naive(a * b));
+                assert_eq!(&(&x / &y) * &y, x);
+                for r in [
+                    &x + &y,
+                    &x - &y,
+                    &x * &y,
+                    &x / &y,
+                    -&x,
+                    x.square(),
+                    x.pow(5),
+                ] {
+                    assert!(SharedModulus::ptr_eq(&r.p, &p));
+                }
+            }
+        }
+        // 別々に作った同じ法の元どうしも等しく、演算できる
+        assert_eq!(FieldElement::new(3, &p), fe(3, 101));
+        assert_eq!(&FieldElement::new(3, &p) + &fe(4, 101), fe(7, 101));
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrows_for_generic_args)] // `&p_ref` の形そのものを確かめる
+    fn constructors_accept_previous_modulus_types() {
+        // 以前の `impl Into<BigInt>` / `&BigInt` の引数で渡せた形がそのまま通る
+        let expected = fe(3, 101);
+        let p = BigInt::from(101);
+        let p_ref = &p;
+        assert_eq!(FieldElement::new(3, 101u8), expected);
+        assert_eq!(FieldElement::new(3, 101i16), expected);
+        assert_eq!(FieldElement::new(3, 101u128), expected);
+        assert_eq!(FieldElement::new(3, 101isize), expected);
+        assert_eq!(FieldElement::new(3, BigUint::from(101u32)), expected);
+        assert_eq!(FieldElement::new(3, p.clone()), expected);
+        assert_eq!(FieldElement::new(3, &p), expected);
+        assert_eq!(FieldElement::from_u64(3, &p_ref), expected);
+        assert_eq!(FieldElement::zero(&p_ref), fe(0, 101));
+        assert_eq!(FieldElement::one(&&SharedModulus::new(p)), fe(1, 101));
+    }
+
+    #[test]
+    fn zero_one_from_u64_constructors() {
+        let p = BigInt::from(7);
+        assert_eq!(FieldElement::zero(&p), fe(0, 7));
+        assert_eq!(FieldElement::one(&p), fe(1, 7));
+        assert_eq!(FieldElement::from_u64(3, &p), fe(3, 7));
+        // p 以上の値は正規化される
+        assert_eq!(FieldElement::from_u64(10, &p), fe(3, 7));
+    }
+
+    #[test]
+    fn is_zero_and_is_one() {
+        assert!(fe(0, 7).is_zero());
+        assert!(fe(7, 7).is_zero()); // 正規化後は 0
+        assert!(!fe(1, 7).is_zero());
+        assert!(fe(1, 7).is_one());
+        assert!(fe(8, 7).is_one()); // 正規化後は 1
+        assert!(!fe(0, 7).is_one());
+    }
+
+    #[test]
+    fn mul_matches_naive_mod_across_moduli() {
+        // 法を交互に切り替えて、Barrett の前計算のキャッシュが入れ替わる経路も通す
+        let mut rng = ark_std::test_rng();
+        let moduli = [2u64, 17, 1_000_000_007].map(BigInt::from);
+        for i in 0..300 {
+            let p = &moduli[i % moduli.len()];
+            let a = FieldElement::random(&mut rng, p);
+            let b = FieldElement::random(&mut rng, p);
+            assert_eq!((&a * &b).value, (&a.value * &b.value) % p, "p = {}", p);
+        }
+    }
+
+    #[test]
+    fn random_stays_in_range_and_varies() {
+        let mut rng = ark_std::test_rng();
+        let p = BigInt::from(257); // 9 ビット: 最上位バイトのマスクが効く
+        let samples: Vec<FieldElement> =
+            (0..200).map(|_| FieldElement::random(&mut rng, &p)).collect();
+        assert!(samples
+            .iter()
+            .all(|x| x.value >= BigInt::from(0) && x.value < p));
+        assert_ne!(samples[0], samples[1]);
+    }
+
     #[test]
     fn add_sub_mul_basic() {
         // F_7 上で
@@ -226,11 +1053,74 @@ mod tests {
         let _ = &fe(1, 7) + &fe(1, 11);
     }
 
+    #[test]
+    fn neg_of_zero_is_zero() {
+        assert_eq!(-fe(0, 7), fe(0, 7));
+    }
+
+    #[test]
+    fn neg_basic_and_involution() {
+        // F_7: -3 == 4、-(-x) == x、x + (-x) == 0
+        let x = fe(3, 7);
+        assert_eq!(-&x, fe(4, 7));
+        assert_eq!(-(-&x), x);
+        assert_eq!(&x + &(-&x), fe(0, 7));
+    }
+
+    #[test]
+    fn checked_add_with_different_modulus_returns_error() {
+        assert_eq!(
+            fe(1, 7).checked_add(&fe(1, 11)),
+            Err(FieldError::ModulusMismatch {
+                left: BigInt::from(7),
+                right: BigInt::from(11),
+            })
+        );
+        assert_eq!(fe(3, 7).checked_add(&fe(5, 7)), Ok(fe(1, 7)));
+    }
+
+    #[test]
+    fn checked_ops_match_operators() {
+        let (a, b) = (fe(3, 7), fe(5, 7));
+        assert_eq!(a.checked_sub(&b), Ok(&a - &b));
+        assert_eq!(a.checked_mul(&b), Ok(&a * &b));
+        assert_eq!(a.checked_div(&b), Ok(&a / &b));
+        assert_eq!(a.checked_div(&fe(0, 7)), Err(FieldError::DivisionByZero));
+    }
+
+    #[test]
+    fn owned_operators_match_reference_operators() {
+        // F_7 上で、値渡し版が参照版と同じ結果になること
+        assert_eq!(fe(3, 7) + fe(5, 7), &fe(3, 7) + &fe(5, 7));
+        assert_eq!(fe(2, 7) - fe(5, 7), &fe(2, 7) - &fe(5, 7));
+        assert_eq!(fe(3, 7) * fe(5, 7), &fe(3, 7) * &fe(5, 7));
+        assert_eq!(fe(6, 7) / fe(3, 7), &fe(6, 7) / &fe(3, 7));
+    }
+
+    #[test]
+    #[should_panic(expected = "異なる標数")]
+    fn owned_mul_with_different_modulus_panics() {
+        let _ = fe(1, 7) * fe(1, 11);
+    }
+
     #[test]
     fn inverse_of_zero_returns_none() {
         assert!(fe(0, 7).inverse().is_none());
     }
 
+    #[test]
+    fn try_div_by_zero_returns_none() {
+        assert!(fe(3, 7).try_div(&fe(0, 7)).is_none());
+        // F_7: 6 / 3 == 2
+        assert_eq!(fe(6, 7).try_div(&fe(3, 7)), Some(fe(2, 7)));
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn div_by_zero_panics() {
+        let _ = &fe(3, 7) / &fe(0, 7);
+    }
+
     #[test]
     fn inverse_times_self_is_one() {
         // F_7 で 3 の逆元は 5（3*5 = 15 = 1 mod 7）
@@ -239,6 +1129,27 @@ mod tests {
         assert_eq!((&a * &inv).value, BigInt::from(1));
     }
 
+    #[test]
+    fn batch_inverse_matches_individual_inverse() {
+        let elems: Vec<FieldElement> = (1..17).map(|v| fe(v, 17)).collect();
+        let inverses = FieldElement::batch_inverse(&elems);
+        assert_eq!(inverses.len(), elems.len());
+        for (e, inv) in elems.iter().zip(&inverses) {
+            assert_eq!(*inv, e.inverse().unwrap());
+        }
+    }
+
+    #[test]
+    fn batch_inverse_empty_returns_empty() {
+        assert!(FieldElement::batch_inverse(&[]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "contains zero")]
+    fn batch_inverse_with_zero_panics() {
+        FieldElement::batch_inverse(&[fe(3, 7), fe(0, 7)]);
+    }
+
     #[test]
     fn pow_basic_cases() {
         let a = fe(3, 7);
@@ -248,6 +1159,40 @@ mod tests {
         assert_eq!(a.pow(BigInt::from(6)).value, BigInt::from(1));
     }
 
+    #[test]
+    fn legendre_mod_17() {
+        // F_17 の平方剰余は {1, 2, 4, 8, 9, 13, 15, 16}
+        let residues = [1, 2, 4, 8, 9, 13, 15, 16];
+        for v in 1..17 {
+            let expected = if residues.contains(&v) { 1 } else { -1 };
+            assert_eq!(fe(v, 17).legendre(), expected, "v = {}", v);
+        }
+        assert_eq!(fe(0, 17).legendre(), 0);
+    }
+
+    #[test]
+    fn square_and_double_match_naive() {
+        for v in 0..11 {
+            let x = fe(v, 11);
+            assert_eq!(x.square(), &x * &x);
+            assert_eq!(x.double(), &x + &x);
+        }
+    }
+
+    #[test]
+    fn pow_ref_matches_repeated_multiplication() {
+        let a = fe(5, 41);
+        let mut naive = fe(1, 41);
+        for e in 0..100 {
+            let exp = BigInt::from(e);
+            assert_eq!(a.pow_ref(&exp), naive);
+            assert_eq!(a.pow(exp), naive);
+            naive = &naive * &a;
+        }
+        // 負の指数は 1 扱い（従来の pow と同じ）
+        assert_eq!(a.pow_ref(&BigInt::from(-3)), fe(1, 41));
+    }
+
     #[test]
     fn sqrt_quadratic_residue() {
         // F_7 で 4 の平方根は 2 または 5（5 = -2 mod 7）
@@ -262,13 +1207,125 @@ mod tests {
     }
 
     #[test]
-    fn sqrt_unsupported_prime_returns_none() {
-        // p = 5 は 5 % 4 == 1 なので未対応
-        assert!(fe(4, 5).sqrt().is_none());
+    fn sqrt_p_1_mod_4_uses_tonelli_shanks() {
+        // p = 5 は 5 % 4 == 1。4 の平方根は 2 または 3
+        let root = fe(4, 5).sqrt().unwrap();
+        assert!(root.value == BigInt::from(2) || root.value == BigInt::from(3));
+    }
+
+    #[test]
+    fn sqrt_tonelli_shanks_p_1_mod_8() {
+        // p = 41 ≡ 1 (mod 8)。全ての平方剰余で root^2 == self になること
+        for v in 1..41 {
+            let a = fe(v, 41);
+            let square = &a * &a;
+            let root = square.sqrt().unwrap();
+            assert_eq!(&root * &root, square);
+        }
+    }
+
+    #[test]
+    fn sqrt_tonelli_shanks_non_residue_returns_none() {
+        // F_41 で 3 は平方非剰余
+        assert!(fe(3, 41).sqrt().is_none());
+    }
+
+    #[test]
+    fn sqrt_of_zero_is_zero() {
+        assert_eq!(fe(0, 41).sqrt(), Some(fe(0, 41)));
+    }
+
+    #[test]
+    fn sqrt_in_f2_returns_self() {
+        // p = 2 は p % 4 == 2 で Tonelli-Shanks に回っていた
+        assert_eq!(fe(0, 2).sqrt(), Some(fe(0, 2)));
+        assert_eq!(fe(1, 2).sqrt(), Some(fe(1, 2)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json_round_trip() {
+        let bn254 = BigInt::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            10,
+        )
+        .unwrap();
+        let cases = vec![
+            fe(3, 7),
+            fe(0, 17),
+            FieldElement::new(&bn254 - BigInt::from(1), bn254.clone()),
+        ];
+        for x in cases {
+            let json = serde_json::to_string(&x).unwrap();
+            let back: FieldElement = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, x);
+        }
+        assert_eq!(serde_json::to_string(&fe(3, 7)).unwrap(), r#"{"value":"3","p":"7"}"#);
+    }
+
+    #[test]
+    fn to_bytes_is_fixed_width_big_endian() {
+        // p = 257 は 9 ビット → 2 バイト幅
+        assert_eq!(fe(0, 257).to_bytes(), vec![0, 0]);
+        assert_eq!(fe(5, 257).to_bytes(), vec![0, 5]);
+        assert_eq!(fe(256, 257).to_bytes(), vec![1, 0]);
+        // p = 7 は 1 バイト幅
+        assert_eq!(fe(6, 7).to_bytes(), vec![6]);
+    }
+
+    #[test]
+    fn bytes_round_trip_across_widths() {
+        let moduli = [
+            BigInt::from(7),
+            BigInt::from(257),
+            BigInt::from(65537),
+            BigInt::parse_bytes(
+                b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+                10,
+            )
+            .unwrap(),
+        ];
+        for p in &moduli {
+            for v in [BigInt::from(0), BigInt::from(1), p - BigInt::from(1)] {
+                let x = FieldElement::new(v, p.clone());
+                assert_eq!(FieldElement::from_bytes(&x.to_bytes(), p), x);
+            }
+        }
+    }
+
+    #[test]
+    fn from_bytes_reduces_oversized_input() {
+        // 0x0102 = 258 ≡ 1 (mod 257)、幅 2 バイトより長い 0x010000 = 65536 ≡ 1 (mod 257)
+        let p = BigInt::from(257);
+        assert_eq!(FieldElement::from_bytes(&[1, 2], &p), fe(1, 257));
+        assert_eq!(FieldElement::from_bytes(&[1, 0, 0], &p), fe(1, 257));
+        assert_eq!(FieldElement::from_bytes(&[], &p), fe(0, 257));
+    }
+
+    #[test]
+    fn equal_elements_hash_to_one_set_entry() {
+        use std::collections::HashSet;
+        let mut set = HashSet::new();
+        set.insert(fe(3, 7));
+        set.insert(fe(10, 7)); // 正規化後は 3
+        set.insert(fe(-4, 7)); // 正規化後は 3
+        assert_eq!(set.len(), 1);
+        // 値が同じでも法が違えば別の元
+        set.insert(fe(3, 11));
+        assert_eq!(set.len(), 2);
     }
 
     #[test]
     fn display_format() {
         assert_eq!(format!("{}", fe(3, 7)), "3 mod 7");
     }
+
+    #[test]
+    fn hex_format() {
+        let x = FieldElement::new(255, 257);
+        assert_eq!(format!("{:x}", x), "ff");
+        assert_eq!(format!("{:X}", x), "FF");
+        assert_eq!(format!("{:#x}", x), "0xff");
+        assert_eq!(format!("{:x}", fe(0, 7)), "0");
+    }
 }
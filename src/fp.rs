@@ -0,0 +1,366 @@
+//! 法をコンパイル時の型パラメータで固定した有限体 `Fp<M>` を提供する。
+//!
+//! Groth16 実装の Layer 1。[`FieldElement`] は法 `p` を実行時の値として持つため、
+//! 異なる法どうしの演算は実行時の assert（"異なる標数"）でしか検出できない。
+//! `Fp<M>` は法をマーカー型 `M` で表すので、法の違う元どうしの演算は型エラーになる。
+//!
+//! ## 主要型
+//! - [`PrimeField`][]: 素体の元に共通する操作（法の取得、0/1、逆元、冪）
+//! - [`Modulus`][]: 法を表すマーカー型のトレイト
+//! - [`FieldParams`][]: 法ごとの前計算（法 `p` と乗算用の [`BarrettReducer`]）
+//! - [`Fp`][]: マーカー `M` で法を固定した体の元
+//! - [`Mod17`] / [`Bn254Fr`][]: 用意済みのマーカー（小さな教育用の体 / BN254 のスカラー体）
+//! - [`DynField`][]: 法を実行時に持つ従来型（= [`FieldElement`]）
+//!
+//! 新しい法は [`define_modulus!`] でマーカーを定義して追加する。
+
+use num_bigint::BigInt;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::barrett::BarrettReducer;
+use crate::field::FieldElement;
+
+/// 法を実行時に持つ体の元。法が型で決まらない場面のフォールバック。
+pub type DynField = FieldElement;
+
+/// 素体の元に共通する操作。
+///
+/// `modulus()` は型ごとに固定なので、インスタンスではなく型から取り出す。
+///
+/// 現在は unit test からのみ呼ばれる。回路側が `Fp` に移行したら attribute を外す。
+#[allow(dead_code)]
+pub trait PrimeField: Sized + Clone + PartialEq + fmt::Debug {
+    /// この体の法 `p`。
+    fn modulus() -> &'static BigInt;
+    /// 加法単位元 0。
+    fn zero() -> Self;
+    /// 乗法単位元 1。
+    fn one() -> Self;
+    /// 逆元。0 のときは `None`。
+    fn inverse(&self) -> Option<Self>;
+    /// `self^exponent`。`exponent <= 0` のときは 1。
+    fn pow(&self, exponent: &BigInt) -> Self;
+}
+
+/// 法ごとに 1 度だけ計算しておく値。
+///
+/// [`Fp`] の乗算は積を `% p` ではなく `barrett` で還元する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldParams {
+    /// 法 `p`。
+    pub modulus: BigInt,
+    /// 積 `a·b < p^2` の還元に使う Barrett の前計算。
+    pub barrett: BarrettReducer,
+}
+
+impl FieldParams {
+    /// 法 `p` の前計算を行う（Barrett の `μ = ⌊2^{2k} / p⌋` もここで求める）。
+    pub fn new(p: BigInt) -> Self {
+        let barrett = BarrettReducer::new(&p);
+        FieldParams {
+            modulus: p,
+            barrett,
+        }
+    }
+}
+
+/// 法を表すマーカー型のトレイト。
+///
+/// 実装は [`define_modulus!`] で生成する（`BigInt` は const にできないので、
+/// 10 進文字列を初回アクセス時にパースし、[`FieldParams`] ごとキャッシュする）。
+pub trait Modulus: Copy + Clone + fmt::Debug + PartialEq + Eq {
+    /// 法ごとの前計算。
+    fn params() -> &'static FieldParams;
+
+    /// 法 `p`。
+    fn modulus() -> &'static BigInt {
+        &Self::params().modulus
+    }
+}
+
+/// 法マーカー型を定義する。
+///
+/// ```text
+/// define_modulus!(Mod17, "17", "法 17 の小さな体。");
+/// type F17 = Fp<Mod17>;
+/// ```
+#[macro_export]
+macro_rules! define_modulus {
+    ($name:ident, $decimal:expr, $doc:expr) => {
+        #[doc = $doc]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        #[allow(dead_code)] // マーカー型なので値としては構築されない
+        pub struct $name;
+
+        impl $crate::fp::Modulus for $name {
+            fn params() -> &'static $crate::fp::FieldParams {
+                static PARAMS: std::sync::OnceLock<$crate::fp::FieldParams> =
+                    std::sync::OnceLock::new();
+                PARAMS.get_or_init(|| {
+                    $crate::fp::FieldParams::new(
+                        num_bigint::BigInt::parse_bytes($decimal.as_bytes(), 10)
+                            .expect("modulus must be a decimal string"),
+                    )
+                })
+            }
+        }
+    };
+}
+
+define_modulus!(Mod17, "17", "法 17 の教育用の小さな体のマーカー。");
+define_modulus!(
+    Bn254Fr,
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    "BN254 のスカラー体 `Fr` の法のマーカー。"
+);
+
+/// マーカー `M` で法を固定した有限体の元。
+///
+/// 内部の `value` は常に `0 <= value < M::modulus()` に正規化される。
+/// 法を値として持たないので、演算のたびに `p` を clone する必要もない。
+#[derive(Clone, PartialEq, Eq)]
+pub struct Fp<M: Modulus> {
+    value: BigInt,
+    _modulus: PhantomData<M>,
+}
+
+impl<M: Modulus> Fp<M> {
+    /// 法 `M::modulus()` のもとで `value` を正規化した元を生成する。
+    pub fn new(value: impl Into<BigInt>) -> Self {
+        let p = M::modulus();
+        Fp {
+            value: ((value.into() % p) + p) % p,
+            _modulus: PhantomData,
+        }
+    }
+
+    /// 正規化済みの値を返す。
+    pub fn value(&self) -> &BigInt {
+        &self.value
+    }
+
+    /// 割り算 `a / b`。`b == 0` のときは `None` を返す。
+    pub fn try_div(&self, other: &Self) -> Option<Self> {
+        Some(Fp::new(&self.value * other.inverse()?.value))
+    }
+
+    /// 実行時に法を持つ [`DynField`] に変換する。
+    pub fn to_dyn(&self) -> DynField {
+        FieldElement::new(self.value.clone(), M::modulus().clone())
+    }
+
+    /// [`DynField`] から変換する。法が `M` と異なれば panic する。
+    pub fn from_dyn(fe: &DynField) -> Self {
+        assert_eq!(&*fe.p, M::modulus(), "異なる標数の体では計算できません");
+        Fp::new(fe.value.clone())
+    }
+}
+
+impl<M: Modulus> PrimeField for Fp<M> {
+    fn modulus() -> &'static BigInt {
+        M::modulus()
+    }
+
+    fn zero() -> Self {
+        Fp::new(0)
+    }
+
+    fn one() -> Self {
+        Fp::new(1)
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        Some(Fp::new(self.value.modinv(M::modulus())?))
+    }
+
+    fn pow(&self, exponent: &BigInt) -> Self {
+        Fp::new(self.to_dyn().pow_ref(exponent).value)
+    }
+}
+
+/// `&a + &b`: 加法。法は型で一致が保証されるので assert 不要。
+impl<M: Modulus> Add<&Fp<M>> for &Fp<M> {
+    type Output = Fp<M>;
+
+    fn add(self, other: &Fp<M>) -> Fp<M> {
+        Fp::new(&self.value + &other.value)
+    }
+}
+
+/// `&a - &b`: 減法。
+impl<M: Modulus> Sub<&Fp<M>> for &Fp<M> {
+    type Output = Fp<M>;
+
+    fn sub(self, other: &Fp<M>) -> Fp<M> {
+        Fp::new(&self.value - &other.value)
+    }
+}
+
+/// `&a * &b`: 乗法。積は `0 <= a·b < p^2` なので、`% p` の代わりに Barrett 還元で正規化する。
+impl<M: Modulus> Mul<&Fp<M>> for &Fp<M> {
+    type Output = Fp<M>;
+
+    fn mul(self, other: &Fp<M>) -> Fp<M> {
+        Fp {
+            value: M::params().barrett.reduce(&(&self.value * &other.value)),
+            _modulus: PhantomData,
+        }
+    }
+}
+
+/// `&a / &b`: 除法 = `&a * &b.inverse()`。`b == 0` の場合は panic する。
+impl<M: Modulus> Div<&Fp<M>> for &Fp<M> {
+    type Output = Fp<M>;
+
+    fn div(self, other: &Fp<M>) -> Fp<M> {
+        self.try_div(other).expect("division by zero")
+    }
+}
+
+/// `-&a`: 加法逆元。
+impl<M: Modulus> Neg for &Fp<M> {
+    type Output = Fp<M>;
+
+    fn neg(self) -> Fp<M> {
+        Fp::new(M::modulus() - &self.value)
+    }
+}
+
+/// `a + b`: 値渡し版。参照版に委譲する。
+impl<M: Modulus> Add for Fp<M> {
+    type Output = Fp<M>;
+
+    fn add(self, other: Fp<M>) -> Fp<M> {
+        &self + &other
+    }
+}
+
+/// `a - b`: 値渡し版。参照版に委譲する。
+impl<M: Modulus> Sub for Fp<M> {
+    type Output = Fp<M>;
+
+    fn sub(self, other: Fp<M>) -> Fp<M> {
+        &self - &other
+    }
+}
+
+/// `a * b`: 値渡し版。参照版に委譲する。
+impl<M: Modulus> Mul for Fp<M> {
+    type Output = Fp<M>;
+
+    fn mul(self, other: Fp<M>) -> Fp<M> {
+        &self * &other
+    }
+}
+
+/// `a / b`: 値渡し版。参照版に委譲する（`b == 0` で panic）。
+impl<M: Modulus> Div for Fp<M> {
+    type Output = Fp<M>;
+
+    fn div(self, other: Fp<M>) -> Fp<M> {
+        &self / &other
+    }
+}
+
+/// `-a`: 値渡し版。参照版に委譲する。
+impl<M: Modulus> Neg for Fp<M> {
+    type Output = Fp<M>;
+
+    fn neg(self) -> Fp<M> {
+        -&self
+    }
+}
+
+/// [`FieldElement`] と同じ `"value mod p"` 形式で表示する。
+impl<M: Modulus> fmt::Display for Fp<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} mod {}", self.value, M::modulus())
+    }
+}
+
+impl<M: Modulus> fmt::Debug for Fp<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fp({} mod {})", self.value, M::modulus())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type F17 = Fp<Mod17>;
+
+    fn f(v: i64) -> F17 {
+        F17::new(v)
+    }
+
+    #[test]
+    fn new_normalizes_with_type_level_modulus() {
+        assert_eq!(F17::modulus(), &BigInt::from(17));
+        assert_eq!(f(-1).value(), &BigInt::from(16));
+        assert_eq!(f(20).value(), &BigInt::from(3));
+    }
+
+    #[test]
+    fn arithmetic_matches_dyn_field() {
+        // 同じ演算を DynField（FieldElement）でやった結果と一致すること
+        for a in 0..17 {
+            for b in 1..17 {
+                let (x, y) = (f(a), f(b));
+                let (dx, dy) = (x.to_dyn(), y.to_dyn());
+                assert_eq!((&x + &y).to_dyn(), &dx + &dy);
+                assert_eq!((&x - &y).to_dyn(), &dx - &dy);
+                assert_eq!((&x * &y).to_dyn(), &dx * &dy);
+                assert_eq!((&x / &y).to_dyn(), &dx / &dy);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_one_inverse_pow() {
+        assert_eq!(F17::zero(), f(0));
+        assert_eq!(F17::one(), f(1));
+        assert!(F17::zero().inverse().is_none());
+        // F_17: 3 · 6 = 18 ≡ 1
+        assert_eq!(f(3).inverse(), Some(f(6)));
+        // フェルマーの小定理: a^(p-1) == 1
+        assert_eq!(f(3).pow(&BigInt::from(16)), F17::one());
+        assert_eq!(-f(3), f(14));
+    }
+
+    #[test]
+    fn dyn_round_trip() {
+        let x = f(5);
+        assert_eq!(F17::from_dyn(&x.to_dyn()), x);
+    }
+
+    #[test]
+    #[should_panic(expected = "異なる標数")]
+    fn from_dyn_with_wrong_modulus_panics() {
+        let _ = F17::from_dyn(&FieldElement::new(1, 7));
+    }
+
+    #[test]
+    fn bn254_mul_matches_dyn_field() {
+        // Barrett 還元を通した積が FieldElement の `% p` と一致すること
+        let mut rng = ark_std::test_rng();
+        let p = Bn254Fr::modulus();
+        for _ in 0..200 {
+            let (a, b) = (
+                FieldElement::random(&mut rng, p),
+                FieldElement::random(&mut rng, p),
+            );
+            let prod = &Fp::<Bn254Fr>::from_dyn(&a) * &Fp::<Bn254Fr>::from_dyn(&b);
+            assert_eq!(prod.to_dyn(), &a * &b);
+        }
+    }
+
+    #[test]
+    fn bn254_marker_matches_fr_modulus() {
+        // p - 1 ≡ -1
+        let minus_one = Fp::<Bn254Fr>::new(-1);
+        assert_eq!(minus_one.value(), &(Bn254Fr::modulus() - BigInt::from(1)));
+    }
+}
@@ -0,0 +1,266 @@
+//! 2 次拡大体 `F_{p^2} = F_p[u] / (u^2 − β)` を提供する。
+//!
+//! Groth16 実装の Layer 1。ペアリング（[`crate::pairing`]）の値域 `G_T` は
+//! 基礎体 `F_p` ではなくその拡大体に入るので、[`FieldElement`] を係数にした
+//! `c0 + c1·u` の形で表す。`β`（non-residue）は `F_p` の平方非剰余で、
+//! `u^2 = β` を満たす `u` を `F_p` に添加する。
+//!
+//! ## 主要型
+//! - [`Fp2`]: `c0 + c1·u` の形の拡大体の元
+//!
+//! ## 主要メソッド
+//! - [`Fp2::square`][] / [`Fp2::inverse`][] / [`Fp2::pow`][]: 2 乗・逆元・冪
+//! - [`Fp2::frobenius`][]: Frobenius 写像 `x ↦ x^p`（`β` が平方非剰余なら共役 `c0 − c1·u`）
+//! - [`Fp2::norm`][]: ノルム `x · x^p = c0^2 − β·c1^2 ∈ F_p`
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_bigint::BigInt;
+
+use crate::field::FieldElement;
+
+/// `F_{p^2}` の元 `c0 + c1·u`（`u^2 = non_residue`）。
+///
+/// [`FieldElement`] が法 `p` を値として持つのと同じく、拡大の定義 `β` も値として持つ。
+/// `β` が異なる元どうしの演算は panic する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fp2 {
+    pub c0: FieldElement,
+    pub c1: FieldElement,
+    pub non_residue: FieldElement,
+}
+
+impl Fp2 {
+    /// `c0 + c1·u` を作る。`non_residue` は `F_p` の平方非剰余であること（呼び出し側の責務）。
+    pub fn new(c0: FieldElement, c1: FieldElement, non_residue: &FieldElement) -> Self {
+        Fp2 {
+            c0,
+            c1,
+            non_residue: non_residue.clone(),
+        }
+    }
+
+    /// 基礎体の元 `c0` を `c0 + 0·u` として埋め込む。
+    pub fn from_base(c0: FieldElement, non_residue: &FieldElement) -> Self {
+        let c1 = FieldElement::zero(&c0.p);
+        Fp2::new(c0, c1, non_residue)
+    }
+
+    /// 加法単位元 `0` を返す。
+    pub fn zero(non_residue: &FieldElement) -> Self {
+        Fp2::from_base(FieldElement::zero(&non_residue.p), non_residue)
+    }
+
+    /// 乗法単位元 `1` を返す。
+    pub fn one(non_residue: &FieldElement) -> Self {
+        Fp2::from_base(FieldElement::one(&non_residue.p), non_residue)
+    }
+
+    /// `0` かどうかを返す。
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    /// `1` かどうかを返す。
+    pub fn is_one(&self) -> bool {
+        self.c0.is_one() && self.c1.is_zero()
+    }
+
+    /// `self^2` を返す。
+    ///
+    /// `(c0 + c1·u)^2 = (c0^2 + β·c1^2) + 2·c0·c1·u`。
+    pub fn square(&self) -> Self {
+        let c0 = &self.c0.square() + &(&self.non_residue * &self.c1.square());
+        let c1 = (&self.c0 * &self.c1).double();
+        Fp2::new(c0, c1, &self.non_residue)
+    }
+
+    /// Frobenius 写像 `x ↦ x^p` を返す。
+    ///
+    /// `(c0 + c1·u)^p = c0 + c1·u^p` で、`u^p = u·(u^2)^{(p−1)/2} = β^{(p−1)/2}·u`。
+    /// `β` が平方非剰余ならオイラーの規準より `β^{(p−1)/2} = −1` なので、共役 `c0 − c1·u` になる。
+    pub fn frobenius(&self) -> Self {
+        let exponent = (&*self.c0.p - BigInt::from(1)) / BigInt::from(2);
+        let coeff = self.non_residue.pow_ref(&exponent);
+        Fp2::new(self.c0.clone(), &self.c1 * &coeff, &self.non_residue)
+    }
+
+    /// ノルム `N(x) = x · x^p = c0^2 − β·c1^2` を返す（`F_p` の元）。
+    ///
+    /// 乗法的（`N(xy) = N(x)·N(y)`）で、`x ≠ 0` なら 0 にならない。
+    pub fn norm(&self) -> FieldElement {
+        &self.c0.square() - &(&self.non_residue * &self.c1.square())
+    }
+
+    /// 乗法逆元を返す。`self == 0` なら `None`。
+    ///
+    /// 共役 `c0 − c1·u` を掛けると [`norm`](Self::norm) `∈ F_p` になるので、
+    /// `(c0 + c1·u)^{-1} = (c0 − c1·u) / N(c0 + c1·u)`。
+    /// `β` が平方非剰余ならノルムは `self ≠ 0` で 0 にならない。
+    pub fn inverse(&self) -> Option<Self> {
+        let inv = self.norm().inverse()?;
+        Some(Fp2::new(
+            &self.c0 * &inv,
+            -&(&self.c1 * &inv),
+            &self.non_residue,
+        ))
+    }
+
+    /// `self^exponent` を二乗乗算法（下位ビットから）で計算する。
+    ///
+    /// `exponent` は 0 以上であること。`0^0 = 1` とする。
+    pub fn pow(&self, exponent: &BigInt) -> Self {
+        let mut res = Fp2::one(&self.non_residue);
+        let mut base = self.clone();
+        let num_bits = exponent.bits();
+        for i in 0..num_bits {
+            if exponent.bit(i) {
+                res = &res * &base;
+            }
+            if i + 1 < num_bits {
+                base = base.square();
+            }
+        }
+        res
+    }
+
+    fn assert_same_extension(&self, other: &Self) {
+        assert_eq!(
+            self.non_residue, other.non_residue,
+            "異なる拡大体の元どうしは演算できません"
+        );
+    }
+}
+
+/// `&a + &b`: 係数ごとの加法。
+impl<'b> Add<&'b Fp2> for &Fp2 {
+    type Output = Fp2;
+
+    fn add(self, other: &'b Fp2) -> Fp2 {
+        self.assert_same_extension(other);
+        Fp2::new(
+            &self.c0 + &other.c0,
+            &self.c1 + &other.c1,
+            &self.non_residue,
+        )
+    }
+}
+
+/// `&a - &b`: 係数ごとの減法。
+impl<'b> Sub<&'b Fp2> for &Fp2 {
+    type Output = Fp2;
+
+    fn sub(self, other: &'b Fp2) -> Fp2 {
+        self.assert_same_extension(other);
+        Fp2::new(
+            &self.c0 - &other.c0,
+            &self.c1 - &other.c1,
+            &self.non_residue,
+        )
+    }
+}
+
+/// `&a * &b`: `(a0 + a1·u)(b0 + b1·u) = (a0·b0 + β·a1·b1) + (a0·b1 + a1·b0)·u`。
+impl<'b> Mul<&'b Fp2> for &Fp2 {
+    type Output = Fp2;
+
+    fn mul(self, other: &'b Fp2) -> Fp2 {
+        self.assert_same_extension(other);
+        let c0 = &(&self.c0 * &other.c0) + &(&self.non_residue * &(&self.c1 * &other.c1));
+        let c1 = &(&self.c0 * &other.c1) + &(&self.c1 * &other.c0);
+        Fp2::new(c0, c1, &self.non_residue)
+    }
+}
+
+/// `-&a`: 係数ごとの加法逆元。
+impl Neg for &Fp2 {
+    type Output = Fp2;
+
+    fn neg(self) -> Fp2 {
+        Fp2::new(-&self.c0, -&self.c1, &self.non_residue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = 17;
+
+    /// F_17 の平方剰余は {1, 2, 4, 8, 9, 13, 15, 16} なので 3 は非剰余。
+    fn beta() -> FieldElement {
+        FieldElement::from_u64(3, BigInt::from(P))
+    }
+
+    fn fp2(c0: u64, c1: u64) -> Fp2 {
+        let p = BigInt::from(P);
+        Fp2::new(
+            FieldElement::from_u64(c0, &p),
+            FieldElement::from_u64(c1, &p),
+            &beta(),
+        )
+    }
+
+    /// F_{17^2} の 0 以外の全元。
+    fn all_nonzero() -> Vec<Fp2> {
+        (0..P)
+            .flat_map(|c0| (0..P).map(move |c1| (c0, c1)))
+            .filter(|&(c0, c1)| c0 != 0 || c1 != 0)
+            .map(|(c0, c1)| fp2(c0, c1))
+            .collect()
+    }
+
+    #[test]
+    fn test_inverse_roundtrip() {
+        let one = Fp2::one(&beta());
+        for x in all_nonzero() {
+            let inv = x.inverse().expect("0 以外は逆元を持つ");
+            assert_eq!(&x * &inv, one, "x = {:?}", x);
+        }
+    }
+
+    #[test]
+    fn test_inverse_of_zero_is_none() {
+        assert!(Fp2::zero(&beta()).inverse().is_none());
+    }
+
+    #[test]
+    fn test_mul_matches_expanded_formula() {
+        // (a0 + a1·u)(b0 + b1·u) = (a0·b0 + 3·a1·b1) + (a0·b1 + a1·b0)·u  (mod 17)
+        let cases = [(2, 5, 7, 11), (16, 16, 16, 16), (0, 1, 0, 1), (9, 0, 4, 13)];
+        for (a0, a1, b0, b1) in cases {
+            let expected = fp2((a0 * b0 + 3 * a1 * b1) % P, (a0 * b1 + a1 * b0) % P);
+            assert_eq!(&fp2(a0, a1) * &fp2(b0, b1), expected);
+        }
+        // u^2 = β
+        assert_eq!(&fp2(0, 1) * &fp2(0, 1), fp2(3, 0));
+    }
+
+    #[test]
+    fn test_square_matches_mul() {
+        for x in all_nonzero().iter().step_by(7) {
+            assert_eq!(x.square(), x * x);
+        }
+    }
+
+    #[test]
+    fn test_frobenius_is_pth_power_and_conjugation() {
+        let p = BigInt::from(P);
+        for x in all_nonzero().iter().step_by(5) {
+            let frob = x.frobenius();
+            assert_eq!(frob, x.pow(&p));
+            assert_eq!(frob, Fp2::new(x.c0.clone(), -&x.c1, &beta()));
+            // 2 回で元に戻る（x^{p^2} = x）
+            assert_eq!(frob.frobenius(), *x);
+        }
+    }
+
+    #[test]
+    fn test_norm_is_multiplicative_and_in_base_field() {
+        let x = fp2(4, 9);
+        let y = fp2(13, 2);
+        let n = x.norm();
+        assert_eq!(&x * &x.frobenius(), Fp2::from_base(n.clone(), &beta()));
+        assert_eq!((&x * &y).norm(), &n * &y.norm());
+    }
+}
@@ -0,0 +1,285 @@
+//! 自作の楕円曲線・ペアリング・QAP を組み合わせた Groth16 の setup / prove / verify。
+//!
+//! Groth16 実装の Layer 3（プロトコル）。本式（[`crate::setup`] / [`crate::prover`] /
+//! [`crate::verifier`]）は arkworks の BN254 を使うが、こちらは [`crate::pairing`] の
+//! 小さな曲線の上で同じ式を組み立てる。スカラー体は群の位数 `r = 131` の `F_r` なので、
+//! 回路（R1CS → QAP）も `F_r` の上で作ること。
+//!
+//! 本式と違い、[`prove`] はランダム値 `r, s` による blinding を行わない
+//! （同じ witness からは常に同じ証明が出るので zero-knowledge ではない）。
+//!
+//! ## 主要型
+//! - [`ProvingKey`] / [`VerifyingKey`]: trusted setup の出力
+//! - [`Proof`]: 証明 `(A, B, C)`
+//!
+//! ## 主要関数
+//! - [`setup`]: QAP から pk/vk を生成する（toxic waste は内部で引いて破棄）
+//! - [`prove`]: witness から証明を作る
+//! - [`verify`]: `e(A, B) = e(α, β)·e(vk_x, γ)·e(C, δ)` を確かめる
+
+use ark_std::rand::Rng;
+use num_bigint::BigInt;
+
+use crate::ec::{msm, AffinePoint};
+use crate::field::FieldElement;
+use crate::pairing::{curve, g1_generator, g2_generator, group_order, pairing, G1, G2};
+use crate::polynomial::Polynomial;
+use crate::qap::Qap;
+use crate::setup::trusted_setup;
+
+/// 自作 Groth16 の証明。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    /// `[A]_1 = [ α + Σ_i a_i·u_i(τ) ]_1`
+    pub a: G1,
+    /// `[B]_2 = [ β + Σ_i a_i·v_i(τ) ]_2`
+    pub b: G2,
+    /// `[C]_1 = [ Σ_{i>ℓ} a_i·(β·u_i(τ) + α·v_i(τ) + w_i(τ))/δ + h(τ)·t(τ)/δ ]_1`
+    pub c: G1,
+}
+
+/// 自作 Groth16 の proving key。
+///
+/// 本式の [`crate::setup::ProvingKey`] と違い `h(x)` の計算に使う QAP と `t(x)` も持つ。
+/// blinding をしないので `[β]_1`, `[δ]_1`, `[δ]_2` は不要。
+pub struct ProvingKey {
+    /// `[α]_1`
+    pub alpha_g1: G1,
+    /// `[β]_2`
+    pub beta_g2: G2,
+    /// `{[u_i(τ)]_1}`（全変数、長さ `m`）
+    pub a_query: Vec<G1>,
+    /// `{[v_i(τ)]_2}`（全変数、長さ `m`）
+    pub b_query: Vec<G2>,
+    /// `{[(β·u_i(τ) + α·v_i(τ) + w_i(τ))/δ]_1}_{i=ℓ+1..m-1}`（長さ `m − num_public`）
+    pub private_query: Vec<G1>,
+    /// `{[τ^i·t(τ)/δ]_1}_{i=0..n-2}`（長さ `n − 1`）
+    pub h_query: Vec<G1>,
+    /// 証明対象の QAP（`F_r` 上）
+    pub qap: Qap,
+    /// ターゲット多項式 `t(x)`
+    pub z: Polynomial,
+}
+
+/// 自作 Groth16 の verifying key。
+pub struct VerifyingKey {
+    /// `[α]_1`
+    pub alpha_g1: G1,
+    /// `[β]_2`
+    pub beta_g2: G2,
+    /// `[γ]_2`
+    pub gamma_g2: G2,
+    /// `[δ]_2`
+    pub delta_g2: G2,
+    /// `IC = {[(β·u_i(τ) + α·v_i(τ) + w_i(τ))/γ]_1}_{i=0..ℓ}`（長さ `num_public`）
+    pub ic: Vec<G1>,
+}
+
+/// QAP から pk/vk を生成する trusted setup。
+///
+/// α, β, γ, δ を `F_r^*` から引き、τ は [`trusted_setup`] の powers of tau を使う
+/// （`t(τ) = 0` になる τ は `h` の項が消えるので引き直す）。
+/// 各変数の `u_i(τ), v_i(τ), w_i(τ)` は [`Qap::evaluate_all_at`] で求め、
+/// 本式の [`crate::setup::generate_groth16_keys`] と同じく public は `/γ`、private は `/δ` で焼き込む。
+/// toxic waste は関数内で破棄される。
+///
+/// # Panics
+/// `num_constraints == 0` のとき panic する。
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+pub fn setup<R: Rng>(rng: &mut R, qap: Qap, num_constraints: usize) -> (ProvingKey, VerifyingKey) {
+    assert!(
+        num_constraints >= 1,
+        "Groth16 setup requires at least one constraint"
+    );
+    let r = group_order();
+    let z = qap.target_polynomial(num_constraints, &r);
+
+    let (params, waste) = loop {
+        let (params, waste) = trusted_setup(rng, num_constraints - 1, &r);
+        if !z.evaluate(&waste.tau).is_zero() {
+            break (params, waste);
+        }
+    };
+    let alpha = random_nonzero(rng, &r);
+    let beta = random_nonzero(rng, &r);
+    let gamma = random_nonzero(rng, &r);
+    let delta = random_nonzero(rng, &r);
+    let gamma_inv = gamma.inverse().unwrap();
+    let delta_inv = delta.inverse().unwrap();
+
+    let g1 = g1_generator();
+    let g2 = g2_generator();
+    let (u, v, w) = qap.evaluate_all_at(&waste.tau);
+
+    let a_query = u.iter().map(|ui| g1.scalar_mul(&ui.value)).collect();
+    let b_query = v.iter().map(|vi| g2.scalar_mul(&vi.value)).collect();
+
+    let mut ic = Vec::with_capacity(qap.num_public);
+    let mut private_query = Vec::with_capacity(u.len().saturating_sub(qap.num_public));
+    for i in 0..u.len() {
+        let combo = &(&(&beta * &u[i]) + &(&alpha * &v[i])) + &w[i];
+        if i < qap.num_public {
+            ic.push(g1.scalar_mul(&(&combo * &gamma_inv).value));
+        } else {
+            private_query.push(g1.scalar_mul(&(&combo * &delta_inv).value));
+        }
+    }
+
+    let t_tau_div_delta = &z.evaluate(&waste.tau) * &delta_inv;
+    let h_query = params.powers_of_tau[..num_constraints - 1]
+        .iter()
+        .map(|tp| g1.scalar_mul(&(tp * &t_tau_div_delta).value))
+        .collect();
+
+    let pk = ProvingKey {
+        alpha_g1: g1.scalar_mul(&alpha.value),
+        beta_g2: g2.scalar_mul(&beta.value),
+        a_query,
+        b_query,
+        private_query,
+        h_query,
+        qap,
+        z,
+    };
+    let vk = VerifyingKey {
+        alpha_g1: g1.scalar_mul(&alpha.value),
+        beta_g2: g2.scalar_mul(&beta.value),
+        gamma_g2: g2.scalar_mul(&gamma.value),
+        delta_g2: g2.scalar_mul(&delta.value),
+        ic,
+    };
+    (pk, vk)
+}
+
+/// witness（`[1, 公開入力..., 秘密/中間...]`、`F_r` 上）から証明を作る。
+///
+/// `h(x) = (A(x)·B(x) − C(x)) / t(x)` は余りを捨てて求める。witness が制約を
+/// 満たさなければ余りが残り、その分だけ `C` がずれるので [`verify`] で弾かれる。
+/// 各点の線形結合は [`msm`] で計算する。
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+pub fn prove(pk: &ProvingKey, witness: &[FieldElement]) -> Proof {
+    let (a_poly, b_poly, c_poly) = pk.qap.compose(witness);
+    let (h, _remainder) = (&(&a_poly * &b_poly) - &c_poly).div_rem(&pk.z);
+
+    let b_points: Vec<AffinePoint> = pk.b_query.iter().map(|q| q.point.clone()).collect();
+    let a = pk.alpha_g1.add(&linear_combination(&pk.a_query, witness));
+    let b = G2 {
+        point: pk
+            .beta_g2
+            .point
+            .add(&linear_combination(&b_points, witness)),
+    };
+
+    let num_public = pk.qap.num_public;
+    let c = linear_combination(&pk.private_query, &witness[num_public..])
+        .add(&linear_combination(&pk.h_query, h.coefficients()));
+
+    Proof { a, b, c }
+}
+
+/// `e(A, B) = e(α, β)·e(vk_x, γ)·e(C, δ)` をペアリングで確かめる。
+///
+/// `public` は公開入力 `a_1..a_ℓ`（CS_ONE の `a_0 = 1` は含めない。長さ ℓ）。
+/// `vk_x = IC_0 + Σ a_i·IC_i` の作り方は本式の [`crate::verifier::verify`] と同じ。
+///
+/// # Panics
+/// `public.len() != vk.ic.len() - 1` のとき panic する。
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+pub fn verify(vk: &VerifyingKey, public: &[FieldElement], proof: &Proof) -> bool {
+    assert_eq!(
+        public.len(),
+        vk.ic.len() - 1,
+        "public length must equal ℓ (vk.ic.len() - 1)"
+    );
+
+    let vk_x = vk.ic[0].add(&linear_combination(&vk.ic[1..], public));
+
+    let lhs = pairing(&proof.a, &proof.b);
+    let rhs = &(&pairing(&vk.alpha_g1, &vk.beta_g2) * &pairing(&vk_x, &vk.gamma_g2))
+        * &pairing(&proof.c, &vk.delta_g2);
+    lhs == rhs
+}
+
+/// `Σ scalars[i]·points[i]` を返す。長さが違う場合は短い方に合わせ、空なら無限遠点。
+fn linear_combination(points: &[AffinePoint], scalars: &[FieldElement]) -> AffinePoint {
+    let len = points.len().min(scalars.len());
+    if len == 0 {
+        return AffinePoint::identity(&curve());
+    }
+    let scalars: Vec<BigInt> = scalars[..len].iter().map(|s| s.value.clone()).collect();
+    msm(&points[..len], &scalars).expect("長さは揃えてあり、空でもない")
+}
+
+/// `F_r^*` から一様に引く（toxic waste 用）。
+fn random_nonzero<R: Rng>(rng: &mut R, r: &BigInt) -> FieldElement {
+    loop {
+        let x = FieldElement::random(rng, r);
+        if !x.is_zero() {
+            return x;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r1cs::x3_plus5_circuit;
+
+    /// `y = x^3 + 5`（x = 3 秘密、y = 32 公開）を `F_r` 上で組み、pk/vk と witness を返す。
+    fn x3_plus5_keys() -> (ProvingKey, VerifyingKey, Vec<FieldElement>) {
+        let cs = x3_plus5_circuit(group_order());
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+
+        let mut rng = ark_std::test_rng();
+        let (pk, vk) = setup(&mut rng, Qap::from_r1cs(&cs), cs.num_constraints());
+        (pk, vk, cs.generate_witness())
+    }
+
+    #[test]
+    fn test_groth16_honest_proof_verifies() {
+        let (pk, vk, witness) = x3_plus5_keys();
+        let proof = prove(&pk, &witness);
+        assert!(verify(&vk, &witness[1..2], &proof));
+    }
+
+    #[test]
+    fn test_groth16_tampered_witness_fails() {
+        let (pk, vk, mut witness) = x3_plus5_keys();
+        // 秘密入力 x = 3 を 4 に改ざん（中間値はそのまま）
+        witness[2] = FieldElement::from_u64(4, group_order());
+        let proof = prove(&pk, &witness);
+        assert!(!verify(&vk, &witness[1..2], &proof));
+    }
+
+    #[test]
+    fn test_groth16_wrong_public_input_fails() {
+        let (pk, vk, witness) = x3_plus5_keys();
+        let proof = prove(&pk, &witness);
+        let wrong = [FieldElement::from_u64(33, group_order())];
+        assert!(!verify(&vk, &wrong, &proof));
+    }
+
+    #[test]
+    fn test_groth16_tampered_proof_fails() {
+        let (pk, vk, witness) = x3_plus5_keys();
+        let mut proof = prove(&pk, &witness);
+        proof.c = proof.c.add(&g1_generator());
+        assert!(!verify(&vk, &witness[1..2], &proof));
+    }
+
+    #[test]
+    fn test_keys_have_expected_lengths() {
+        let (pk, vk, witness) = x3_plus5_keys();
+        let m = witness.len();
+        // t(x) の次数 = 制約数 n
+        let n = pk.z.degree().unwrap();
+        assert_eq!(pk.a_query.len(), m);
+        assert_eq!(pk.b_query.len(), m);
+        assert_eq!(vk.ic.len(), pk.qap.num_public);
+        assert_eq!(pk.private_query.len() + vk.ic.len(), m);
+        assert_eq!(pk.h_query.len(), n - 1);
+    }
+}
@@ -0,0 +1,178 @@
+//! KZG 多項式コミットメント（[`crate::pairing`] の小さな曲線の上の自作版）。
+//!
+//! Groth16 実装の Layer 3。多項式 `p(x)` を 1 点 `[p(τ)]_1` にコミットし、
+//! 後から任意の点 `z` での値 `p(z)` を、商 `q(x) = (p(x) − p(z)) / (x − z)` への
+//! コミットメント 1 点だけで証明する。検証はペアリング 2 回:
+//! `e(C − [v]_1, [1]_2) = e(π, [τ]_2 − [z]_2)`（`q(τ)·(τ − z) = p(τ) − v` の確認）。
+//!
+//! 係数と評価点はペアリングの群の位数 `r` の体 `F_r` の上で考える。
+//!
+//! ## 主要関数
+//! - [`setup`]: SRS `[τ^i]_1`（`i = 0..=degree`）と `[1]_2, [τ]_2` を作る
+//! - [`commit`]: 係数と SRS の MSM で `[p(τ)]_1` を計算する
+//! - [`open`]: `z` での値と商のコミットメント（opening proof）を返す
+//! - [`verify`]: opening proof をペアリングで検証する
+
+use ark_std::rand::Rng;
+use num_bigint::BigInt;
+
+use crate::ec::{msm, AffinePoint};
+use crate::field::FieldElement;
+use crate::pairing::{curve, g1_generator, g2_generator, group_order, pairing, G1, G2};
+use crate::polynomial::Polynomial;
+use crate::setup::trusted_setup;
+
+/// 次数 `degree` 以下の多項式用の SRS を作る。
+///
+/// 返り値は `([τ^0]_1, ..., [τ^degree]_1)` と `([1]_2, [τ]_2)`。
+/// τ は [`trusted_setup`] で引き、関数内で破棄する。
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+pub fn setup<R: Rng>(rng: &mut R, degree: usize) -> (Vec<G1>, Vec<G2>) {
+    let (params, waste) = trusted_setup(rng, degree, &group_order());
+    let g1 = g1_generator();
+    let g2 = g2_generator();
+    let srs = params
+        .powers_of_tau
+        .iter()
+        .map(|tp| g1.scalar_mul(&tp.value))
+        .collect();
+    let srs_g2 = vec![g2.clone(), g2.scalar_mul(&waste.tau.value)];
+    (srs, srs_g2)
+}
+
+/// `[p(τ)]_1 = Σ p_i·[τ^i]_1` を計算する（係数と SRS の MSM）。
+///
+/// 係数が空の多項式（零多項式）のコミットメントは無限遠点 `[0]_1`。
+///
+/// # Panics
+/// `poly` の係数が SRS より多い（次数が SRS の上限を超える）とき panic する。
+pub fn commit(poly: &Polynomial, srs: &[G1]) -> G1 {
+    let coeffs = poly.coefficients();
+    assert!(
+        coeffs.len() <= srs.len(),
+        "polynomial of {} coefficients exceeds SRS size {}",
+        coeffs.len(),
+        srs.len()
+    );
+    if coeffs.is_empty() {
+        return AffinePoint::identity(&curve());
+    }
+    let scalars: Vec<BigInt> = coeffs.iter().map(|c| c.value.clone()).collect();
+    msm(&srs[..coeffs.len()], &scalars).expect("coefficients are non-empty")
+}
+
+/// `z` での値 `p(z)` と opening proof `[q(τ)]_1` を返す。
+///
+/// 商 `q(x) = (p(x) − p(z)) / (x − z)` は [`Polynomial::div_by_linear`] の組立除法で求める。
+/// `p(x)` を `(x − z)` で割った余りがちょうど `p(z)` なので、商は `p(x) − p(z)` を
+/// 割ったものと同じで、値 `p(z)` も同じ 1 回の計算で得られる。
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+pub fn open(poly: &Polynomial, z: &FieldElement, srs: &[G1]) -> (FieldElement, G1) {
+    let (quotient, value) = poly.div_by_linear(z);
+    (value, commit(&quotient, srs))
+}
+
+/// `p(z) = value` の opening proof を検証する。
+///
+/// `e(C − [v]_1, [1]_2) = e(π, [τ]_2 − [z]_2)` を確かめる。
+/// `srs_g2` は [`setup`] の返す `([1]_2, [τ]_2)`。
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+pub fn verify(
+    commitment: &G1,
+    z: &FieldElement,
+    value: &FieldElement,
+    proof: &G1,
+    srs_g2: &[G2],
+) -> bool {
+    let g1 = g1_generator();
+    let lhs_g1 = commitment.add(&g1.scalar_mul(&value.value).neg());
+    let tau_minus_z = G2 {
+        point: srs_g2[1]
+            .point
+            .add(&srs_g2[0].point.scalar_mul(&z.value).neg()),
+    };
+    pairing(&lhs_g1, &srs_g2[0]) == pairing(proof, &tau_minus_z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fe(v: u64) -> FieldElement {
+        FieldElement::from_u64(v, group_order())
+    }
+
+    /// `p(x) = 3 + 5x + 7x^2 + x^3`
+    fn sample_poly() -> Polynomial {
+        Polynomial::new(vec![fe(3), fe(5), fe(7), fe(1)])
+    }
+
+    #[test]
+    fn test_kzg_honest_opening_verifies() {
+        let mut rng = ark_std::test_rng();
+        let (srs, srs_g2) = setup(&mut rng, 3);
+        let poly = sample_poly();
+        let commitment = commit(&poly, &srs);
+
+        for z in [0u64, 1, 10, 130] {
+            let z = fe(z);
+            let (value, proof) = open(&poly, &z, &srs);
+            assert_eq!(value, poly.evaluate(&z));
+            assert!(verify(&commitment, &z, &value, &proof, &srs_g2));
+        }
+    }
+
+    #[test]
+    fn test_kzg_wrong_value_fails() {
+        let mut rng = ark_std::test_rng();
+        let (srs, srs_g2) = setup(&mut rng, 3);
+        let poly = sample_poly();
+        let commitment = commit(&poly, &srs);
+
+        let z = fe(10);
+        let (value, proof) = open(&poly, &z, &srs);
+        let wrong = &value + &fe(1);
+        assert!(!verify(&commitment, &z, &wrong, &proof, &srs_g2));
+    }
+
+    #[test]
+    fn test_kzg_commit_is_linear() {
+        // [p(τ)]_1 + [q(τ)]_1 = [(p + q)(τ)]_1
+        let mut rng = ark_std::test_rng();
+        let (srs, _) = setup(&mut rng, 3);
+        let p = sample_poly();
+        let q = Polynomial::new(vec![fe(1), fe(0), fe(2)]);
+        assert_eq!(
+            commit(&p, &srs).add(&commit(&q, &srs)),
+            commit(&(&p + &q), &srs)
+        );
+    }
+
+    #[test]
+    fn test_kzg_zero_polynomial_commits_to_identity() {
+        let mut rng = ark_std::test_rng();
+        let (srs, srs_g2) = setup(&mut rng, 3);
+        let z = fe(10);
+        // 係数が空のものと [0] のもの、どちらも零多項式
+        for poly in [Polynomial::new(vec![]), Polynomial::new(vec![fe(0)])] {
+            let commitment = commit(&poly, &srs);
+            assert_eq!(commitment, AffinePoint::identity(&curve()));
+
+            let (value, proof) = open(&poly, &z, &srs);
+            assert!(value.is_zero());
+            assert!(verify(&commitment, &z, &value, &proof, &srs_g2));
+            assert!(!verify(&commitment, &z, &fe(1), &proof, &srs_g2));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds SRS size")]
+    fn test_kzg_commit_rejects_too_high_degree() {
+        let mut rng = ark_std::test_rng();
+        let (srs, _) = setup(&mut rng, 2);
+        commit(&sample_poly(), &srs);
+    }
+}
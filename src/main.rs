@@ -1,10 +1,20 @@
 mod adapter;
+mod barrett;
+mod ec;
+mod expr;
 mod field;
+mod fp;
+mod fp2;
+mod groth16;
+mod kzg;
+mod montgomery;
+mod pairing;
 mod polynomial;
 mod prover;
 mod qap;
 mod r1cs;
 mod setup;
+mod snarkjs;
 mod verifier;
 
 use field::FieldElement;
@@ -14,12 +24,12 @@ use ark_bn254::Fr;
 
 use crate::{
     adapter::{field_element_to_fr, polynomial_to_fr_vec, polys_to_fr_vecs},
-    polynomial::Polynomial,
-    prover::prove,
+    fp::{Fp, Mod17},
+    prover::{prove, Prover},
     qap::Qap,
     r1cs::{ConstraintSystem, LinearCombination, CS_ONE},
-    setup::{generate_groth16_keys, QapFr, ToxicWaste},
-    verifier::verify,
+    setup::{generate_groth16_keys, trusted_setup, QapFr, ToxicWaste},
+    verifier::{verify, verify_pinocchio, Verifier},
 };
 
 fn main() {
@@ -41,23 +51,26 @@ fn main() {
     cs.assign(y, fe(32));
     let x = cs.alloc_variable(); // 秘密入力 x
     cs.assign(x, fe(3));
-    let v1 = cs.mul(x, x);
-    let v2 = cs.mul(v1, x);
-    // 制約: (v2 + 5) · 1 = y
-    let mut lc_a = LinearCombination::new();
-    lc_a.add_term(v2, fe(1));
-    lc_a.add_term(CS_ONE, fe(5));
-    let mut lc_b = LinearCombination::new();
-    lc_b.add_term(CS_ONE, fe(1));
-    let mut lc_c = LinearCombination::new();
-    lc_c.add_term(y, fe(1));
+    // x^3 = (x·x)·x の 2 制約
+    let x_cubed = cs.pow(x, 3);
+    // 制約: (x^3 + 5) · 1 = y
+    let lc_a = LinearCombination::from((x_cubed, fe(1))) + (CS_ONE, fe(5)).into();
+    let lc_b = LinearCombination::from((CS_ONE, fe(1)));
+    let lc_c = LinearCombination::from((y, fe(1)));
     cs.enforce(lc_a, lc_b, lc_c);
 
-    let num_constraints = cs.constraints.len();
+    // 同じ式を法を型で固定した小さな体 F_17 でも計算する（法の取り違えは型エラーになる）
+    let x17 = Fp::<Mod17>::new(3);
+    let y17 = &(&(&x17 * &x17) * &x17) + &Fp::new(5);
+    println!("  In Fp<Mod17>: x^3 + 5 = {}", y17);
+
+    let num_constraints = cs.num_constraints();
     let num_public = cs.num_public_variables;
     println!(
         "  {} constraints, {} variables ({} public incl. CS_ONE)",
-        num_constraints, cs.next_var_index, num_public
+        num_constraints,
+        cs.num_variables(),
+        num_public
     );
 
     // Step 2: R1CS -> QAP -> Fr
@@ -72,8 +85,48 @@ fn main() {
     // Step 3: h(x) = (A(x)*B(x) - C(x)) / Z(x)
     println!("\nStep 3: Computing h(x)...");
     let witness_fe = cs.generate_witness();
-    let h_poly = compute_h_poly(&qap, &witness_fe, num_constraints, &p);
-    println!("  h(x) degree: {}", h_poly.degree());
+    assert!(
+        cs.is_satisfied(&witness_fe),
+        "witness does not satisfy the R1CS"
+    );
+    let prover = Prover::new(qap, num_constraints, &p);
+    assert!(
+        prover.verify_divisibility(&witness_fe),
+        "P(x) is not divisible by Z(x)"
+    );
+    let qap_proof = prover.prove(&witness_fe);
+    let qap_verifier = Verifier {
+        z: prover.z.clone(),
+    };
+    assert!(
+        qap_verifier.verify(&qap_proof.a, &qap_proof.b, &qap_proof.c, &qap_proof.h),
+        "A(x)·B(x) − C(x) != h(x)·Z(x)"
+    );
+    // 同じ等式を秘密点 τ での値だけで確かめる（ペアリング前の段階）。
+    // A(τ) などは setup が各変数について求めた u_i(τ), v_i(τ), w_i(τ) と witness の内積
+    let (_, field_waste) = trusted_setup(&mut ark_std::test_rng(), num_constraints, &p);
+    let tau = field_waste.tau;
+    let (u_tau, v_tau, w_tau) = prover.qap.evaluate_all_at(&tau);
+    let inner = |evals: &[FieldElement]| {
+        evals
+            .iter()
+            .zip(&witness_fe)
+            .fold(fe(0), |acc, (e, w)| &acc + &(e * w))
+    };
+    assert!(
+        verify_pinocchio(
+            &inner(&u_tau),
+            &inner(&v_tau),
+            &inner(&w_tau),
+            &qap_proof.h.evaluate(&tau),
+            &prover.z.evaluate(&tau),
+        ),
+        "A(τ)·B(τ) − C(τ) != h(τ)·Z(τ)"
+    );
+    match qap_proof.h.degree() {
+        Some(d) => println!("  h(x) degree: {}", d),
+        None => println!("  h(x) is the zero polynomial"),
+    }
 
     // Step 4: Trusted setup（本式 pk/vk。デモ用に toxic waste は固定値、本番は破棄）
     println!("\nStep 4: Generating proving/verifying keys (demo toxic waste)...");
@@ -89,7 +142,7 @@ fn main() {
     // Step 5: Prove（r, s はデモ用固定。本番では毎回ランダムに引く = zero-knowledge）
     println!("\nStep 5: Generating proof (r, s fixed for demo)...");
     let witness: Vec<Fr> = witness_fe.iter().map(field_element_to_fr).collect();
-    let h_coeffs = polynomial_to_fr_vec(&h_poly);
+    let h_coeffs = polynomial_to_fr_vec(&qap_proof.h);
     let proof = prove(
         &pk,
         &qap_fr,
@@ -101,47 +154,14 @@ fn main() {
 
     // Step 6: Verify
     println!("\nStep 6: Verifying proof...");
-    let public_inputs = vec![witness[y.0]];
+    let public_inputs: Vec<Fr> = cs
+        .public_inputs(&witness_fe)
+        .iter()
+        .map(field_element_to_fr)
+        .collect();
     if verify(&vk, &public_inputs, &proof) {
         println!("  OK! Proof verified");
     } else {
         println!("  NG..Proof rejected");
     }
 }
-
-/// h(x) = (A(x)*B(x) - C(x)) / Z(x) を計算する
-fn compute_h_poly(
-    qap: &Qap,
-    witness: &[FieldElement],
-    num_constraints: usize,
-    p: &BigInt,
-) -> Polynomial {
-    let zero = FieldElement::new(0, p.clone());
-    let one = FieldElement::new(1, p.clone());
-
-    // A(x), B(x), C(x) = sum_i witness[i] * poly_i(x)
-    let mut a = Polynomial::new(vec![zero.clone()]);
-    let mut b = Polynomial::new(vec![zero.clone()]);
-    let mut c = Polynomial::new(vec![zero.clone()]);
-    for (i, w) in witness.iter().enumerate() {
-        a = &a + &qap.a_polys[i].scale(w);
-        b = &b + &qap.b_polys[i].scale(w);
-        c = &c + &qap.c_polys[i].scale(w);
-    }
-
-    // P(x) = A(x)*B(x) - C(x)
-    let minus_one = &zero - &one;
-    let p_poly = &(&a * &b) + &c.scale(&minus_one);
-
-    // Z(x) = (x - 0)(x - 1)...(x - (n - 1))
-    let mut z_poly = Polynomial::new(vec![one.clone()]);
-    for i in 0..num_constraints {
-        let neg_i = &zero - &FieldElement::new(i, p.clone());
-        z_poly = &z_poly * &Polynomial::new(vec![neg_i, one.clone()]);
-    }
-
-    // h(x) = P(x) / Z(x)
-    let (h, remainder) = p_poly.div_rem(&z_poly);
-    assert!(remainder.is_zero(), "P(x) is not divisible by Z(x)");
-    h
-}
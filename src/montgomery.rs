@@ -0,0 +1,335 @@
+//! Montgomery 形式による有限体の乗算（剰余を割り算なしで取る高速化）。
+//!
+//! Groth16 実装の Layer 1。[`FieldElement`] の乗算は BigInt の積のあと `% p`
+//! （多倍長の割り算）を取るので、QAP の補間や多項式の積で支配的なコストになる。
+//! Montgomery 形式では元 `a` を `ã = a·R mod p`（`R = 2^{64·s}`、`s` は `p` の語数）で持ち、
+//! 積 `ã·b̃` を `R` で「割る」操作を語ごとの掛け算と足し算だけで行う（REDC）。
+//! 変換（[`MontgomeryField::to_montgomery`] / [`MontgomeryField::from_montgomery`]）は
+//! 最初と最後の 1 回ずつなので、乗算を繰り返す計算ほど得になる。
+//!
+//! ## 主要型
+//! - [`MontgomeryField`]: 法 `p` ごとの前計算（`R mod p`, `R^2 mod p`, `p' = −p^{-1} mod 2^64`）
+//! - [`MontgomeryElement`]: Montgomery 形式の元（64 ビット語の列、下位語から）
+//!
+//! ## 主要メソッド
+//! - [`MontgomeryField::mul`][]: CIOS（Coarsely Integrated Operand Scanning）法による乗算
+//! - [`MontgomeryField::add`][] / [`MontgomeryField::sub`][]: 語ごとの加減算と条件付き補正
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::field::FieldElement;
+
+/// 法 `p` の Montgomery 乗算に使う前計算の値。
+///
+/// `p` は奇素数であること（`p` と `R = 2^{64·s}` が互いに素でないと `p'` が存在しない）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MontgomeryField {
+    /// 法 `p`（[`FieldElement`] との変換用）
+    pub p: BigInt,
+    /// `p` の 64 ビット語（下位から、長さ `s`）
+    modulus: Vec<u64>,
+    /// `p' = −p^{-1} mod 2^64`
+    inv: u64,
+    /// `R mod p`（Montgomery 形式の 1）
+    r: Vec<u64>,
+    /// `R^2 mod p`（通常の値を Montgomery 形式に移すのに使う）
+    r2: Vec<u64>,
+}
+
+/// Montgomery 形式 `a·R mod p` の元。語数は [`MontgomeryField`] の `s` に揃える。
+///
+/// 法を持たないので、演算は作った [`MontgomeryField`] のメソッド経由で行う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MontgomeryElement {
+    pub limbs: Vec<u64>,
+}
+
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+impl MontgomeryField {
+    /// 法 `p` の前計算を行う。
+    ///
+    /// # Panics
+    /// `p` が 3 未満の場合や偶数の場合は panic する。
+    pub fn new(p: &BigInt) -> Self {
+        assert!(
+            p.sign() == Sign::Plus && p.bit(0) && *p > BigInt::from(1),
+            "Montgomery 形式の法は 3 以上の奇数である必要があります: {}",
+            p
+        );
+        let modulus = p.magnitude().to_u64_digits();
+        let s = modulus.len();
+
+        // Newton 法で p^{-1} mod 2^64（1 回ごとに正しいビット数が倍になる: 1 → 64 は 6 回）
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+        }
+
+        let r = (BigUint::from(1u8) << (64 * s)) % p.magnitude();
+        let r2 = (BigUint::from(1u8) << (128 * s)) % p.magnitude();
+        MontgomeryField {
+            p: p.clone(),
+            modulus,
+            inv: inv.wrapping_neg(),
+            r: to_limbs(&r, s),
+            r2: to_limbs(&r2, s),
+        }
+    }
+
+    /// 通常の元 `a` を Montgomery 形式 `a·R mod p` に変換する（`REDC(a · R^2)`）。
+    ///
+    /// # Panics
+    /// `a` の法が `self.p` と異なる場合は panic する。
+    pub fn to_montgomery(&self, a: &FieldElement) -> MontgomeryElement {
+        assert_eq!(*a.p, self.p, "異なる標数の元は変換できません");
+        let a = MontgomeryElement {
+            limbs: to_limbs(a.value.magnitude(), self.modulus.len()),
+        };
+        self.mul(
+            &a,
+            &MontgomeryElement {
+                limbs: self.r2.clone(),
+            },
+        )
+    }
+
+    /// Montgomery 形式から通常の元に戻す（`REDC(ã · 1) = a`）。
+    #[allow(clippy::wrong_self_convention)] // `from_*` だが前計算した法の情報が要るので &self
+    pub fn from_montgomery(&self, a: &MontgomeryElement) -> FieldElement {
+        let mut one = vec![0u64; self.modulus.len()];
+        one[0] = 1;
+        let plain = self.mul(a, &MontgomeryElement { limbs: one });
+        FieldElement::new(
+            BigInt::from_biguint(
+                Sign::Plus,
+                BigUint::from_slice(&to_u32_digits(&plain.limbs)),
+            ),
+            self.p.clone(),
+        )
+    }
+
+    /// Montgomery 形式の 1（`R mod p`）を返す。
+    pub fn one(&self) -> MontgomeryElement {
+        MontgomeryElement {
+            limbs: self.r.clone(),
+        }
+    }
+
+    /// Montgomery 乗算 `REDC(ã · b̃) = ã·b̃·R^{-1} mod p`（= `(a·b)·R mod p`）。
+    ///
+    /// CIOS 法: `b` の語を 1 つずつ取り、`t += a · b_i` のあと
+    /// `m = t_0 · p' mod 2^64` として `t += m · p` で最下位語を 0 にし、1 語右にずらす。
+    /// これを `s` 回繰り返すと `t = ã·b̃·R^{-1}` かつ `t < 2p` なので、最後に 1 回だけ `p` を引く。
+    /// 作業領域は `s + 2` 語で、多倍長の割り算は一度も行わない。
+    pub fn mul(&self, a: &MontgomeryElement, b: &MontgomeryElement) -> MontgomeryElement {
+        let n = &self.modulus;
+        let s = n.len();
+        let mut t = vec![0u64; s + 2];
+        for i in 0..s {
+            // t += a · b_i
+            let mut carry = 0u128;
+            for (t_j, &a_j) in t.iter_mut().zip(&a.limbs) {
+                let sum = *t_j as u128 + (a_j as u128) * (b.limbs[i] as u128) + carry;
+                *t_j = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[s] as u128 + carry;
+            t[s] = sum as u64;
+            t[s + 1] = (sum >> 64) as u64;
+
+            // t = (t + m · p) / 2^64
+            let m = t[0].wrapping_mul(self.inv);
+            let sum = t[0] as u128 + (m as u128) * (n[0] as u128);
+            let mut carry = sum >> 64;
+            for j in 1..s {
+                let sum = t[j] as u128 + (m as u128) * (n[j] as u128) + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[s] as u128 + carry;
+            t[s - 1] = sum as u64;
+            t[s] = t[s + 1] + (sum >> 64) as u64;
+        }
+
+        let mut limbs = t[..s].to_vec();
+        if t[s] != 0 || !less_than(&limbs, n) {
+            sub_in_place(&mut limbs, n);
+        }
+        MontgomeryElement { limbs }
+    }
+
+    /// 加算。Montgomery 形式は線形なので `ã + b̃ = (a + b)·R` で、`p` 以上なら `p` を引く。
+    pub fn add(&self, a: &MontgomeryElement, b: &MontgomeryElement) -> MontgomeryElement {
+        let mut limbs = a.limbs.clone();
+        let carry = add_in_place(&mut limbs, &b.limbs);
+        if carry || !less_than(&limbs, &self.modulus) {
+            sub_in_place(&mut limbs, &self.modulus);
+        }
+        MontgomeryElement { limbs }
+    }
+
+    /// 減算。借りが出たら（`a < b`）`p` を足して戻す。
+    pub fn sub(&self, a: &MontgomeryElement, b: &MontgomeryElement) -> MontgomeryElement {
+        let mut limbs = a.limbs.clone();
+        if sub_in_place(&mut limbs, &b.limbs) {
+            add_in_place(&mut limbs, &self.modulus);
+        }
+        MontgomeryElement { limbs }
+    }
+}
+
+/// `x` を `s` 語（下位から）に広げる。`x < 2^{64·s}` が前提。
+fn to_limbs(x: &BigUint, s: usize) -> Vec<u64> {
+    let mut limbs = x.to_u64_digits();
+    limbs.resize(s, 0);
+    limbs
+}
+
+/// `BigUint::from_slice` 用に 64 ビット語を 32 ビット語に分ける。
+fn to_u32_digits(limbs: &[u64]) -> Vec<u32> {
+    limbs
+        .iter()
+        .flat_map(|&l| [l as u32, (l >> 32) as u32])
+        .collect()
+}
+
+/// 同じ語数の `a < b` を上位語から比べる。
+fn less_than(a: &[u64], b: &[u64]) -> bool {
+    for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+        if x != y {
+            return x < y;
+        }
+    }
+    false
+}
+
+/// `a += b`。最上位からの桁あふれがあれば `true`。
+fn add_in_place(a: &mut [u64], b: &[u64]) -> bool {
+    let mut carry = false;
+    for (x, y) in a.iter_mut().zip(b) {
+        let (sum, c1) = x.overflowing_add(*y);
+        let (sum, c2) = sum.overflowing_add(carry as u64);
+        *x = sum;
+        carry = c1 || c2;
+    }
+    carry
+}
+
+/// `a -= b`。借りが出た（`a < b` だった）なら `true`。
+fn sub_in_place(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    for (x, y) in a.iter_mut().zip(b) {
+        let (diff, b1) = x.overflowing_sub(*y);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        *x = diff;
+        borrow = b1 || b2;
+    }
+    borrow
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::rand::Rng;
+    use std::time::Instant;
+
+    fn bn254_fr() -> BigInt {
+        BigInt::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            10,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip_conversion() {
+        let p = bn254_fr();
+        let field = MontgomeryField::new(&p);
+        for v in [0u64, 1, 2, 12345, u64::MAX] {
+            let a = FieldElement::from_u64(v, &p);
+            assert_eq!(field.from_montgomery(&field.to_montgomery(&a)), a);
+        }
+        let minus_one = -&FieldElement::one(&p);
+        assert_eq!(
+            field.from_montgomery(&field.to_montgomery(&minus_one)),
+            minus_one
+        );
+        assert_eq!(field.from_montgomery(&field.one()), FieldElement::one(&p));
+    }
+
+    #[test]
+    fn test_arithmetic_matches_field_element() {
+        let mut rng = ark_std::test_rng();
+        for p in [BigInt::from(17), BigInt::from(u64::MAX - 58), bn254_fr()] {
+            let field = MontgomeryField::new(&p);
+            for _ in 0..200 {
+                let a = FieldElement::random(&mut rng, &p);
+                let b = FieldElement::random(&mut rng, &p);
+                let (ma, mb) = (field.to_montgomery(&a), field.to_montgomery(&b));
+                assert_eq!(field.from_montgomery(&field.mul(&ma, &mb)), &a * &b);
+                assert_eq!(field.from_montgomery(&field.add(&ma, &mb)), &a + &b);
+                assert_eq!(field.from_montgomery(&field.sub(&ma, &mb)), &a - &b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_field_exhaustive_mul() {
+        let p = BigInt::from(17);
+        let field = MontgomeryField::new(&p);
+        for a in 0..17u64 {
+            for b in 0..17u64 {
+                let fa = FieldElement::from_u64(a, &p);
+                let fb = FieldElement::from_u64(b, &p);
+                let prod = field.mul(&field.to_montgomery(&fa), &field.to_montgomery(&fb));
+                assert_eq!(field.from_montgomery(&prod), &fa * &fb);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "奇数")]
+    fn test_even_modulus_panics() {
+        MontgomeryField::new(&BigInt::from(16));
+    }
+
+    /// `cargo test -- --ignored --nocapture bench_montgomery` で実行する簡易ベンチマーク。
+    ///
+    /// BN254 のスカラー体で同じ累積積を `FieldElement` と Montgomery 形式で計算し、時間を比べる。
+    #[test]
+    #[ignore]
+    fn bench_montgomery_vs_field_element_mul() {
+        const ITERATIONS: usize = 200_000;
+        let p = bn254_fr();
+        let field = MontgomeryField::new(&p);
+        let mut rng = ark_std::test_rng();
+        let x = FieldElement::random(&mut rng, &p);
+        let start_value = FieldElement::from_u64(rng.gen_range(1..1000), &p);
+
+        let start = Instant::now();
+        let mut acc = start_value.clone();
+        for _ in 0..ITERATIONS {
+            acc = &acc * &x;
+        }
+        let plain = start.elapsed();
+
+        let start = Instant::now();
+        let mx = field.to_montgomery(&x);
+        let mut macc = field.to_montgomery(&start_value);
+        for _ in 0..ITERATIONS {
+            macc = field.mul(&macc, &mx);
+        }
+        let result = field.from_montgomery(&macc);
+        let montgomery = start.elapsed();
+
+        assert_eq!(result, acc);
+        eprintln!(
+            "{} muls: FieldElement {:?}, Montgomery {:?} ({:.1}x)",
+            ITERATIONS,
+            plain,
+            montgomery,
+            plain.as_secs_f64() / montgomery.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! 小さな pairing-friendly 曲線の上の双線形ペアリング（reduced Tate pairing）を提供する。
+//!
+//! Groth16 の検証は `e(A, B) = e(α, β)·e(L, γ)·e(C, δ)` のようなペアリングの等式で行う。
+//! 本式（[`crate::verifier`]）は arkworks の BN254 を使うので、こちらは
+//! Miller loop と final exponentiation の仕組みを確かめるための自作版。
+//!
+//! ## 曲線
+//! 超特異曲線 `E: y^2 = x^3 + x` を `p = 523`（`p ≡ 3 mod 4`）上で使う。
+//! - `#E(F_p) = p + 1 = 4·131` なので、位数 `r = 131` の部分群を `G1` とする
+//! - 埋め込み次数は 2（`r | p^2 − 1`）で、`G_T` は `F_{p^2} = F_p[u]/(u^2 + 1)` の
+//!   1 の `r` 乗根の群
+//! - distortion map `ψ(x, y) = (−x, u·y)` は `E(F_p)` を `E(F_{p^2})` の別の部分群へ移す。
+//!   `G2 = ψ(G1)` とし、点は `ψ` を掛ける前の `E(F_p)` の点として持つ
+//!   （`ψ` は準同型なので、スカラー倍は写す前に計算してよい）
+//!
+//! ## 主要型
+//! - [`G1`]: `E(F_p)[r]` の点（= [`AffinePoint`]）
+//! - [`G2`]: `ψ(E(F_p)[r])` の点
+//! - [`Gt`]: ペアリングの値（= [`Fp2`]）
+//!
+//! ## 主要関数
+//! - [`pairing`]: `e(P, Q) = f_{r,P}(ψ(Q))^{(p^2 − 1)/r}`
+//! - [`g1_generator`] / [`g2_generator`]: 各群の生成元
+
+use num_bigint::BigInt;
+
+use crate::ec::{AffinePoint, Curve};
+use crate::field::FieldElement;
+use crate::fp2::Fp2;
+
+/// 基礎体の法 `p`。`p ≡ 3 (mod 4)` なので `−1` が平方非剰余になり、`u^2 = −1` で拡大できる。
+const FIELD_MODULUS: u64 = 523;
+
+/// `G1`, `G2`, `G_T` の位数 `r`（素数、`p + 1 = 4r`）。
+const GROUP_ORDER: u64 = 131;
+
+/// `E(F_p)` の位数 `p + 1` を `r` で割った余因子。
+const COFACTOR: u64 = 4;
+
+/// 第 1 引数の群: `E(F_p)` の位数 `r` の部分群。
+pub type G1 = AffinePoint;
+
+/// ペアリングの値域: `F_{p^2}^*` の位数 `r` の部分群。
+pub type Gt = Fp2;
+
+/// 第 2 引数の群: `G1` を distortion map `ψ` で写した `E(F_{p^2})` の部分群。
+///
+/// `point` は `ψ` を掛ける前の `E(F_p)` の点。`ψ(point)` の座標は
+/// [`pairing`] の中で直線の評価に使うときにだけ作る。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct G2 {
+    pub point: AffinePoint,
+}
+
+impl G2 {
+    /// スカラー倍 `k·Q`。`ψ(k·P) = k·ψ(P)` なので写す前の点で計算する。
+    pub fn scalar_mul(&self, k: &BigInt) -> Self {
+        G2 {
+            point: self.point.scalar_mul(k),
+        }
+    }
+}
+
+/// 基礎体の法 `p` を返す。
+pub fn field_modulus() -> BigInt {
+    BigInt::from(FIELD_MODULUS)
+}
+
+/// 群の位数 `r` を返す（スカラーはこの法で考える）。
+pub fn group_order() -> BigInt {
+    BigInt::from(GROUP_ORDER)
+}
+
+/// 曲線 `y^2 = x^3 + x` を返す。
+pub fn curve() -> Curve {
+    let p = field_modulus();
+    Curve {
+        a: FieldElement::one(&p),
+        b: FieldElement::zero(&p),
+    }
+}
+
+/// `F_{p^2}` の定義に使う平方非剰余 `β = −1` を返す。
+fn non_residue() -> FieldElement {
+    -&FieldElement::one(field_modulus())
+}
+
+/// `G1` の生成元を返す。
+///
+/// `x = 1, 2, ...` の順に曲線上の点を探し、余因子倍 `4·P` が無限遠点でない
+/// 最初の点を使う（`r` は素数なので、無限遠点でなければ位数はちょうど `r`）。
+pub fn g1_generator() -> G1 {
+    let curve = curve();
+    let p = field_modulus();
+    let cofactor = BigInt::from(COFACTOR);
+    for x in 1..FIELD_MODULUS {
+        let x = FieldElement::from_u64(x, &p);
+        let rhs = &(&x.square() * &x) + &x;
+        let Some(y) = rhs.sqrt() else {
+            continue;
+        };
+        let candidate = AffinePoint::new(x, y, &curve).scalar_mul(&cofactor);
+        if !candidate.infinity {
+            return candidate;
+        }
+    }
+    unreachable!("E(F_p) は位数 r の点を持つ")
+}
+
+/// `G2` の生成元 `ψ(G1 の生成元)` を返す。
+///
+/// 超特異曲線では `e(P, ψ(P)) ≠ 1` なので、同じ点を写したものを使ってよい。
+pub fn g2_generator() -> G2 {
+    G2 {
+        point: g1_generator(),
+    }
+}
+
+/// 双線形ペアリング `e(P, Q)` を計算する（reduced Tate pairing）。
+///
+/// 1. Miller loop: `r` のビットを上位から走査しながら `T = P` を 2 倍・加算していき、
+///    その都度 `T` を通る接線・弦 `l` を `ψ(Q)` で評価した値を `f` に掛け込む。
+///    最後には `f = f_{r,P}(ψ(Q))`（因子 `r(P) − r(O)` を持つ関数の値）になる
+/// 2. final exponentiation: `f^{(p^2 − 1)/r}` で 1 の `r` 乗根に揃える
+///
+/// 縦線 `x − x_T` の `ψ(Q)` での値 `−x_Q − x_T` は `F_p` に入るが、`F_p^*` の元は
+/// `(p^2 − 1)/r = (p − 1)·(p + 1)/r` 乗で 1 になるので、Miller loop では掛けない
+/// （denominator elimination）。
+///
+/// `P` か `Q` が無限遠点なら `1` を返す。
+pub fn pairing(p: &G1, q: &G2) -> Gt {
+    let beta = non_residue();
+    if p.infinity || q.point.infinity {
+        return Fp2::one(&beta);
+    }
+
+    // ψ(Q) = (−x_Q, u·y_Q)
+    let qx = -&q.point.x;
+    let qy = &q.point.y;
+
+    let r = group_order();
+    let mut f = Fp2::one(&beta);
+    let mut t = p.clone();
+    for i in (0..r.bits() - 1).rev() {
+        // 接線: λ = (3x_T^2 + a) / 2y_T
+        let three = FieldElement::from_u64(3, &t.x.p);
+        let lambda = &(&(&three * &t.x.square()) + &t.curve.a) / &t.y.double();
+        f = &f.square() * &line_value(&t, &lambda, &qx, qy, &beta);
+        t = t.double();
+
+        if r.bit(i) {
+            if t.x == p.x {
+                // T = −P: 弦は縦線になり、値は F_p に入るので掛けない
+                t = t.add(p);
+            } else {
+                // 弦: λ = (y_P − y_T) / (x_P − x_T)
+                let lambda = &(&p.y - &t.y) / &(&p.x - &t.x);
+                f = &f * &line_value(&t, &lambda, &qx, qy, &beta);
+                t = t.add(p);
+            }
+        }
+    }
+    debug_assert!(t.infinity, "r·P は無限遠点になるはず");
+
+    let p2_minus_one = &field_modulus() * &field_modulus() - BigInt::from(1);
+    f.pow(&(p2_minus_one / &r))
+}
+
+/// `T` を通る傾き `λ` の直線 `l(x, y) = y − y_T − λ(x − x_T)` を `ψ(Q) = (qx, qy·u)` で評価する。
+///
+/// `qx` は `F_p` の元なので、`y` の項だけが `u` の係数に入る。
+fn line_value(
+    t: &AffinePoint,
+    lambda: &FieldElement,
+    qx: &FieldElement,
+    qy: &FieldElement,
+    beta: &FieldElement,
+) -> Fp2 {
+    let c0 = -&(&t.y + &(lambda * &(qx - &t.x)));
+    Fp2::new(c0, qy.clone(), beta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generators_have_order_r() {
+        let g1 = g1_generator();
+        assert!(!g1.infinity);
+        assert!(g1.scalar_mul(&group_order()).infinity);
+        assert!(g2_generator().scalar_mul(&group_order()).point.infinity);
+    }
+
+    #[test]
+    fn test_pairing_is_non_degenerate_and_in_gt() {
+        let e = pairing(&g1_generator(), &g2_generator());
+        assert!(!e.is_one(), "e(P, Q) が 1 に退化している");
+        assert!(e.pow(&group_order()).is_one(), "e(P, Q)^r は 1 になるはず");
+    }
+
+    #[test]
+    fn test_pairing_bilinearity() {
+        let g1 = g1_generator();
+        let g2 = g2_generator();
+        let base = pairing(&g1, &g2);
+        for a in 1u64..=5 {
+            for b in 1u64..=5 {
+                let lhs = pairing(
+                    &g1.scalar_mul(&BigInt::from(a)),
+                    &g2.scalar_mul(&BigInt::from(b)),
+                );
+                let rhs = base.pow(&BigInt::from(a * b));
+                assert_eq!(lhs, rhs, "e({}P, {}Q) != e(P, Q)^{}", a, b, a * b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pairing_linear_in_each_argument() {
+        // e(P1 + P2, Q) = e(P1, Q)·e(P2, Q)
+        let g1 = g1_generator();
+        let g2 = g2_generator();
+        let p1 = g1.scalar_mul(&BigInt::from(7));
+        let p2 = g1.scalar_mul(&BigInt::from(40));
+        assert_eq!(
+            pairing(&p1.add(&p2), &g2),
+            &pairing(&p1, &g2) * &pairing(&p2, &g2)
+        );
+    }
+
+    #[test]
+    fn test_pairing_with_identity_is_one() {
+        let o = AffinePoint::identity(&curve());
+        assert!(pairing(&o, &g2_generator()).is_one());
+        let o2 = G2 { point: o };
+        assert!(pairing(&g1_generator(), &o2).is_one());
+    }
+}
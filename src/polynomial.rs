@@ -6,21 +6,61 @@
 //! ## 主要型
 //! - [`Polynomial`]: [`FieldElement`] を係数とする dense 表現。
 //!   `Add`, `Sub`, `Mul`, `Div` を実装。
+//!   演算子は 0 除算や法の不一致で panic し、`try_*` は [`PolyError`] を返す。
+//! - [`PolyError`]: 0 多項式による除算・空多項式のオペランド・法の不一致
 //!
 //! ## 主要メソッド
 //! - [`Polynomial::evaluate`][]: ホーナー法で多項式を評価
+//! - [`Polynomial::evaluate_with_powers`][]: 前計算した冪 [`powers`] で評価
+//!   （同じ点で多数の多項式を評価するとき用）
 //! - [`Polynomial::div_rem`][]: 多項式の長除法（商と余りを返す）
+//! - [`Polynomial::div_by_linear`][]: `(x − a)` で割る組立除法（`O(n)`、余りは `self(a)`）
+//! - [`Polynomial::from_roots`][]: 根の列から `Π (x − r_i)` を構築
+//! - [`Polynomial::roots`][]: 全元の総当たりで根を求める（小さな体専用）
 //! - [`Polynomial::lagrange_interpolation`][]: x = 0, 1, 2, ... の点列からラグランジュ補間
+//! - [`Polynomial::lagrange_interpolation_at`][]: 任意の点列 `(x_i, y_i)` からラグランジュ補間
+//! - [`Polynomial::lagrange_interpolation_sparse`][]: x = 0, 1, 2, ... のうち非零の点だけを渡して補間
+//! - [`Polynomial::lagrange_basis`][] / [`Polynomial::from_lagrange_basis`][]: 同じ補間点で
+//!   何度も補間するときに基底多項式 `L_i(x)` を使い回す
 
 use crate::field::FieldElement;
+use ark_std::rand::Rng;
 use num_bigint::BigInt;
-use std::ops::{Add, Div, Mul, Sub};
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, RemAssign, Sub};
+
+/// 多項式の演算で起こりうるエラー。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolyError {
+    /// 0 多項式（空多項式を含む）で割ろうとした。
+    DivByZero,
+    /// 係数を 1 つも持たない空多項式をオペランドに渡した（法 `p` を取り出せない）。
+    EmptyOperand,
+    /// 法の異なる係数の多項式どうしを演算しようとした。`left` / `right` は各オペランドの法。
+    ModulusMismatch { left: BigInt, right: BigInt },
+}
+
+impl fmt::Display for PolyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolyError::DivByZero => write!(f, "0多項式で割ることはできません"),
+            PolyError::EmptyOperand => write!(f, "空多項式は演算できません"),
+            PolyError::ModulusMismatch { left, right } => {
+                write!(f, "異なる標数の体では計算できません: {} != {}", left, right)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolyError {}
 
 /// 有限体係数の多項式を dense 表現で保持する。
 ///
 /// `coefficients[i]` が x^i の係数。例： `[1, 2, 3]` は `1 + 2x + 3x^2` を表す。
 /// 末尾の 0 係数は [`Polynomial::new`] で自動的に取り除かれるため、
 /// 意味的な次数と `coefficients.len() - 1` は常に一致する。
+/// この不変条件を保つため係数列は private にし、読み出しは [`Polynomial::coefficients`] /
+/// [`Polynomial::coefficient`]、書き換えは [`Polynomial::set_coefficient`] で行う。
 ///
 /// # 例
 ///
@@ -30,11 +70,11 @@ use std::ops::{Add, Div, Mul, Sub};
 ///     FieldElement::new(2, 7),
 /// ]); // 1 + 2x in F_7
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub struct Polynomial {
     // coefficients[i] が x^i の係数
     // Dense（密）表現 を採用
-    pub coefficients: Vec<FieldElement>,
+    coefficients: Vec<FieldElement>,
 }
 
 impl Polynomial {
@@ -49,30 +89,86 @@ impl Polynomial {
     /// [1, 2, 0, 0] → [1, 2]  (1 + 2x)
     /// [0, 0, 0]    → [0]     (定数 0)
     ///```
-    pub fn new(mut coefficients: Vec<FieldElement>) -> Self {
-        while coefficients.len() > 1 && coefficients.last().unwrap().value == BigInt::from(0) {
-            coefficients.pop();
+    pub fn new(coefficients: Vec<FieldElement>) -> Self {
+        let mut poly = Polynomial { coefficients };
+        poly.trim();
+        poly
+    }
+
+    /// 末尾の 0 係数を取り除く（1 つは残す）。[`new`](Self::new) と同じ正規化。
+    fn trim(&mut self) {
+        while self.coefficients.len() > 1 && self.coefficients.last().unwrap().is_zero() {
+            self.coefficients.pop();
+        }
+    }
+
+    /// 係数列 `[c_0, c_1, ...]`（`c_i` が x^i の係数）を返す。末尾の 0 は除かれている。
+    pub fn coefficients(&self) -> &[FieldElement] {
+        &self.coefficients
+    }
+
+    /// x^i の係数を返す。次数を超える `i` では 0 を返す。
+    ///
+    /// # Panics
+    /// 空多項式では法 `p` を取り出せないので panic する。
+    pub fn coefficient(&self, i: usize) -> FieldElement {
+        match self.coefficients.get(i) {
+            Some(c) => c.clone(),
+            None => FieldElement::zero(
+                &self
+                    .coefficients
+                    .first()
+                    .expect("空多項式の係数は法が決まりません")
+                    .p,
+            ),
+        }
+    }
+
+    /// x^i の係数を `c` にする。
+    ///
+    /// 次数を超える `i` なら間を 0 で埋めて伸ばし、最高次を 0 にしたときは
+    /// [`new`](Self::new) と同じく末尾の 0 を取り除く。空多項式なら `c` の法を使う。
+    ///
+    /// # Panics
+    /// `c` の法が既存の係数と異なる場合は panic する。
+    pub fn set_coefficient(&mut self, i: usize, c: FieldElement) {
+        if let Some(c0) = self.coefficients.first() {
+            assert_eq!(c0.p, c.p, "異なる標数の係数は設定できません");
+        }
+        if i >= self.coefficients.len() {
+            self.coefficients.resize(i + 1, FieldElement::zero(&c.p));
         }
-        Polynomial { coefficients }
+        self.coefficients[i] = c;
+        self.trim();
     }
 
-    /// 多項式の次数を返す。
+    /// 多項式の次数を返す。0 多項式の次数は未定義なので `None` を返す。
     ///
-    /// 定数 `c` の次数は 0、空多項式 (`coefficients.is_empty()`) の場合も 0 を返す。
-    /// 両者を区別したい場合は `coefficients.is_empty()` で判定すること。
-    pub fn degree(&self) -> usize {
-        if self.coefficients.is_empty() {
-            return 0;
+    /// 0 でない定数 `c` の次数は `Some(0)`。
+    /// 0 多項式（`[0]`）と空多項式（`coefficients.is_empty()`）はどちらも `None`。
+    pub fn degree(&self) -> Option<usize> {
+        if self.is_zero() {
+            return None;
         }
-        self.coefficients.len() - 1
+        Some(self.coefficients.len() - 1)
     }
 
     /// 多項式が 0 多項式かどうかを返す。
     ///
-    /// [`Polynomial::new`] の正規化ルール（全 0 のとき `[0]` を残す）に依存。
-    /// よって「係数 1 個 かつ それが 0」という単純判定を行う。
+    /// 空多項式も「項を持たない」ので 0 多項式とみなす。
+    /// [`Polynomial::new`] を通した値なら、0 多項式は `[0]` か空のどちらか。
     pub fn is_zero(&self) -> bool {
-        self.coefficients.len() == 1 && self.coefficients[0].value == BigInt::from(0)
+        self.coefficients.iter().all(|c| c.is_zero())
+    }
+
+    /// 最高次係数を返す。0 多項式（空を含む）では `None`。
+    ///
+    /// [`Polynomial::new`] で末尾の 0 が除かれているので、`Some` なら常に非 0。
+    pub fn leading_coefficient(&self) -> Option<&FieldElement> {
+        if self.is_zero() {
+            return None;
+        }
+        self.coefficients.last()
     }
 
     /// 与えられた `x` で多項式を評価し、`P(x)` を返す。
@@ -97,66 +193,276 @@ impl Polynomial {
         result
     }
 
+    /// 前計算した冪 `powers = [x^0, x^1, ...]` を使って `P(x) = Σ c_i · x^i` を返す。
+    ///
+    /// `powers.len()` は係数の数（`次数 + 1`）以上であること。余分な冪は使わない。
+    /// [`evaluate`](Self::evaluate) と乗算の回数は同じだが、冪は [`powers`] で
+    /// 点ごとに 1 度だけ作ればよいので、同じ点で多数の多項式を評価する場合
+    /// （QAP の全列を τ で評価するなど）に各多項式の計算が内積だけで済む。
+    ///
+    /// # Panics
+    /// `powers` が空の場合や、係数の数より短い場合は panic する。
+    pub fn evaluate_with_powers(&self, powers: &[FieldElement]) -> FieldElement {
+        assert!(
+            powers.len() >= self.coefficients.len(),
+            "冪の数が足りません: {} < {}",
+            powers.len(),
+            self.coefficients.len()
+        );
+        let zero = FieldElement::zero(&powers.first().expect("powers must not be empty").p);
+        self.coefficients
+            .iter()
+            .zip(powers)
+            .fold(zero, |acc, (c, x_i)| &acc + &(c * x_i))
+    }
+
+    /// 複数の点 `points` で多項式を評価し、`[P(x_0), P(x_1), ...]` を返す。
+    ///
+    /// 各点で [`evaluate`](Self::evaluate) を呼ぶだけの素朴な実装なので、
+    /// 計算量は `O(点数 · 次数)`。
+    pub fn evaluate_batch(&self, points: &[FieldElement]) -> Vec<FieldElement> {
+        points.iter().map(|x| self.evaluate(x)).collect()
+    }
+
+    /// QAP の補間点列 `x = 0, 1, ..., n-1` で評価した値を返す。
+    ///
+    /// [`evaluate_batch`](Self::evaluate_batch) の糖衣で、計算量も同じ `O(n · 次数)`。
+    /// 法 `p` を係数から取り出すため、空多項式では panic する。
+    pub fn evaluate_domain(&self, n: usize) -> Vec<FieldElement> {
+        let p = &self
+            .coefficients
+            .first()
+            .expect("cannot evaluate an empty polynomial on a domain")
+            .p;
+        let points: Vec<FieldElement> = (0..n).map(|i| FieldElement::new(i, p.clone())).collect();
+        self.evaluate_batch(&points)
+    }
+
+    /// 係数表現から評価表現に変換する: `domain` の各点での値 `[P(d_0), P(d_1), ...]`。
+    ///
+    /// 中身は [`evaluate_batch`](Self::evaluate_batch) と同じ。
+    /// [`from_evaluations`](Self::from_evaluations) で元に戻すには
+    /// `domain.len() > degree` で、`domain` の点が互いに異なる必要がある。
+    pub fn to_evaluations(&self, domain: &[FieldElement]) -> Vec<FieldElement> {
+        self.evaluate_batch(domain)
+    }
+
+    /// 評価表現から係数表現に戻す: `evals[i] = P(domain[i])` を満たす多項式を補間する。
+    ///
+    /// [`lagrange_interpolation_at`](Self::lagrange_interpolation_at) に委譲するので、
+    /// 結果の次数は `domain.len() - 1` 以下。元の多項式の次数が `domain.len()` 以上だと
+    /// 別の多項式に化けるので、往復には `domain.len() > degree` が必要。
+    ///
+    /// # Panics
+    ///
+    /// `evals` と `domain` の長さが異なる場合、または `domain` に重複がある場合。
+    pub fn from_evaluations(evals: &[FieldElement], domain: &[FieldElement]) -> Polynomial {
+        assert_eq!(
+            evals.len(),
+            domain.len(),
+            "評価値と評価点の個数が一致しません"
+        );
+        let points: Vec<(FieldElement, FieldElement)> =
+            domain.iter().cloned().zip(evals.iter().cloned()).collect();
+        Polynomial::lagrange_interpolation_at(&points)
+    }
+
+    /// 多項式の合成 `self(inner(x))` を返す。
+    ///
+    /// [`evaluate`](Self::evaluate) と同じホーナー法を、体の元の代わりに多項式の
+    /// 乗算・加算で回す。`inner` が定数 `c` なら結果は定数 `self.evaluate(c)` になる。
+    /// どちらかが空多項式なら空多項式を返す。
+    pub fn compose(&self, inner: &Polynomial) -> Polynomial {
+        if self.coefficients.is_empty() || inner.coefficients.is_empty() {
+            return Polynomial::new(vec![]);
+        }
+        let mut result = Polynomial::new(vec![FieldElement::zero(&self.coefficients[0].p)]);
+        for coeff in self.coefficients.iter().rev() {
+            result = &(&result * inner) + &Polynomial::new(vec![coeff.clone()]);
+        }
+        result
+    }
+
     /// 多項式の長除法を行い、`(quotient, remainder)` を返す。
     ///
     /// 結果は不変式 `self == divisor * quotient + remainder` を満たし、
     /// `remainder` の次数は `divisor` より厳密に小さい。
     /// 被除数の次数が除数より小さいときは `(0, self)` を返す。
     ///
+    /// 余りは被除数の係数を写した 1 本のバッファの上で、最高次から
+    /// `ratio · divisor` を該当位置に直接引いていく（途中で `Polynomial` を作らない）。
+    /// 除数の最高次係数の逆元は最初に 1 回だけ求める。
+    ///
     /// # Panics
     ///
-    /// `divisor` が 0 多項式の場合 panic する。
+    /// `divisor` が 0 多項式の場合や、法が異なる場合 panic する。
+    /// 回復したい場合は [`try_div_rem`](Self::try_div_rem) を使う。
     pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
-        let p = self.coefficients[0].p.clone();
+        self.try_div_rem(divisor)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        // 0 で割ろうとした場合はパニック
-        if divisor.is_zero() {
-            panic!("0多項式で割ることはできません");
+    /// [`div_rem`](Self::div_rem) の panic しない版。
+    ///
+    /// `divisor` が 0 多項式（空多項式を含む）なら [`PolyError::DivByZero`]、
+    /// 係数の法が異なれば [`PolyError::ModulusMismatch`] を返す。
+    /// 空の被除数は 0 多項式として扱う（商も余りも 0）。
+    pub fn try_div_rem(&self, divisor: &Polynomial) -> Result<(Polynomial, Polynomial), PolyError> {
+        let deg_d = divisor.degree().ok_or(PolyError::DivByZero)?;
+        let p = divisor.coefficients[0].p.clone();
+        if let Some(c) = self.coefficients.first() {
+            check_modulus(&c.p, &p)?;
         }
+        let zero_poly = || Polynomial::new(vec![FieldElement::new(BigInt::from(0), p.clone())]);
+
+        // 被除数が 0 多項式なら商も余りも 0
+        let deg_s = match self.degree() {
+            Some(d) => d,
+            None => return Ok((zero_poly(), zero_poly())),
+        };
 
         // 被除数の次数が除数より低い場合、商は 0、余りは被除数自身
-        if self.degree() < divisor.degree() {
-            return (
-                Polynomial::new(vec![FieldElement::new(BigInt::from(0), p.clone())]),
-                self.clone(),
-            );
+        if deg_s < deg_d {
+            return Ok((zero_poly(), self.clone()));
         }
 
-        let mut quotient_coeffs = vec![
-            FieldElement::new(BigInt::from(0), p.clone());
-            self.degree() - divisor.degree() + 1
-        ];
-        let mut remainder = self.clone();
-
-        // 長除法のメインループ
-        while remainder.degree() >= divisor.degree() && !remainder.is_zero() {
-            let deg_r = remainder.degree();
-            let deg_d = divisor.degree();
+        let mut quotient_coeffs =
+            vec![FieldElement::new(BigInt::from(0), p.clone()); deg_s - deg_d + 1];
+        let mut remainder = self.coefficients[..=deg_s].to_vec();
+        let divisor_coeffs = &divisor.coefficients[..=deg_d];
+        let leading_inv = divisor_coeffs[deg_d]
+            .inverse()
+            .expect("leading coefficient of a non-zero divisor is non-zero");
+
+        // 長除法のメインループ: 余りの deg_r 次の係数を消す
+        for deg_r in (deg_d..=deg_s).rev() {
+            if remainder[deg_r].is_zero() {
+                // この次数の商の係数は 0 のまま
+                continue;
+            }
 
             // a. 最高次の項同士の割り算
-            let leading_r = remainder.coefficients.last().unwrap();
-            let leading_d = divisor.coefficients.last().unwrap();
-            let ratio = leading_r.div(leading_d); // FieldElement の割り算
+            let ratio = &remainder[deg_r] * &leading_inv;
 
-            // 次数の差
+            // b. 余りの deg_r − deg_d 次以降から ratio · divisor をその場で引く
             let deg_diff = deg_r - deg_d;
-            quotient_coeffs[deg_diff] = ratio.clone();
-
-            // b. 減算用の多項式（ratio * x^deg_diff * divisor）を作成
-            let mut sub_coeffs = vec![
-                FieldElement::new(BigInt::from(0), p.clone());
-                deg_diff + divisor.coefficients.len()
-            ];
-            for (i, coeff) in divisor.coefficients.iter().enumerate() {
-                sub_coeffs[i + deg_diff] = coeff * &ratio;
+            for (r, d) in remainder[deg_diff..].iter_mut().zip(divisor_coeffs) {
+                *r = &*r - &(&ratio * d);
             }
-            let sub_poly = Polynomial::new(sub_coeffs);
+            quotient_coeffs[deg_diff] = ratio;
+        }
 
-            // c. 余りから引く
-            remainder = &remainder - &sub_poly;
+        // deg_d 次以上はすべて 0 になっている（除数が定数なら余りは 0 多項式）
+        remainder.truncate(deg_d.max(1));
+        Ok((Polynomial::new(quotient_coeffs), Polynomial::new(remainder)))
+    }
+
+    /// `self` を `(x − a)` で割った商と余りを組立除法（Ruffini の方法）で返す。
+    ///
+    /// 上位の係数から `q_{k−1} = c_k + a · q_k` と下ろしていき、最後に残る値が余り。
+    /// 剰余の定理より余りは `self(a)` に等しい（ホーナー法と同じ計算）。
+    /// 一般の [`div_rem`](Self::div_rem) は `O(n^2)` だが、1 次式で割るなら `O(n)`。
+    /// KZG の opening `(p(x) − p(z)) / (x − z)` や消失多項式の因数を外すときに使う。
+    ///
+    /// 定数多項式なら商は 0、余りはその定数。空多項式は 0 多項式として扱う。
+    pub fn div_by_linear(&self, a: &FieldElement) -> (Polynomial, FieldElement) {
+        let n = self.coefficients.len();
+        if n < 2 {
+            let remainder = self
+                .coefficients
+                .first()
+                .cloned()
+                .unwrap_or_else(|| FieldElement::zero(&a.p));
+            return (Polynomial::new(vec![FieldElement::zero(&a.p)]), remainder);
         }
+        // 上位から q_{k-1} = c_k + a · q_k
+        let mut quotient = vec![FieldElement::zero(&a.p); n - 1];
+        let mut carry = self.coefficients[n - 1].clone();
+        for k in (0..n - 1).rev() {
+            quotient[k] = carry.clone();
+            carry = &self.coefficients[k] + &(a * &carry);
+        }
+        (Polynomial::new(quotient), carry)
+    }
 
-        (Polynomial::new(quotient_coeffs), remainder)
+    /// 商 `self / divisor` を返す（余りは捨てる）。エラーは [`try_div_rem`](Self::try_div_rem) と同じ。
+    pub fn try_div(&self, divisor: &Polynomial) -> Result<Polynomial, PolyError> {
+        self.try_div_rem(divisor).map(|(q, _r)| q)
+    }
+
+    /// 積 `self · other` を返す（係数の畳み込み、計算量 `O(n·m)`）。
+    ///
+    /// どちらかが空多項式なら [`PolyError::EmptyOperand`]、
+    /// 係数の法が異なれば [`PolyError::ModulusMismatch`] を返す。
+    pub fn try_mul(&self, other: &Polynomial) -> Result<Polynomial, PolyError> {
+        let (Some(a0), Some(b0)) = (self.coefficients.first(), other.coefficients.first()) else {
+            return Err(PolyError::EmptyOperand);
+        };
+        check_modulus(&a0.p, &b0.p)?;
+
+        let p = a0.p.clone();
+        // どちらの多項式にも含まれている 0次のオフセットを、重複して数えないように調整
+        let new_len = self.coefficients.len() + other.coefficients.len() - 1;
+        let mut res_coeffs = vec![FieldElement::new(BigInt::from(0), p.clone()); new_len];
+
+        for i in 0..self.coefficients.len() {
+            for j in 0..other.coefficients.len() {
+                let product = &self.coefficients[i] * &other.coefficients[j];
+                res_coeffs[i + j] = &res_coeffs[i + j] + &product;
+            }
+        }
+
+        Ok(Polynomial::new(res_coeffs))
+    }
+
+    /// 根 `r_0, r_1, ...` から `(x - r_0)(x - r_1)...` を展開したモニック多項式を返す。
+    ///
+    /// QAP の消失多項式 `Z(x) = Π (x − j)` の構築に使う。
+    /// 根が空のときは定数 1 を返す（法は `p` から取る）。
+    pub fn from_roots(roots: &[FieldElement], p: &BigInt) -> Polynomial {
+        let one = FieldElement::one(p);
+        let mut result = Polynomial::new(vec![one.clone()]);
+        for r in roots {
+            // (x - r) の係数は [-r, 1]
+            result = &result * &Polynomial::new(vec![-r, one.clone()]);
+        }
+        result
+    }
+
+    /// `F_p` の中の根を、全元 `0, 1, ..., p − 1` を評価して小さい順に返す（重根も 1 回だけ）。
+    ///
+    /// 総当たりなので計算量は `O(p · deg)`。`F_17` のような教材用の小さな体で
+    /// 消失多項式の根を確かめる用途に限る（BN254 の `F_r` では終わらない）。
+    /// 0 多項式はすべての元で 0 になるので `F_p` の全元を返す。
+    pub fn roots(&self, p: &BigInt) -> Vec<FieldElement> {
+        let mut roots = Vec::new();
+        let mut x = BigInt::from(0);
+        while x < *p {
+            let candidate = FieldElement::new(x.clone(), p);
+            if self.evaluate(&candidate).is_zero() {
+                roots.push(candidate);
+            }
+            x += 1;
+        }
+        roots
+    }
+
+    /// 次数がちょうど `degree` のランダムな多項式を返す。
+    ///
+    /// 各係数を [`FieldElement::random`] で一様に引き、最高次係数だけは
+    /// 0 が出たら引き直す（0 だと `new()` で切り詰められ次数が下がるため）。
+    /// blinding 多項式やプロパティテストの入力生成に使う。
+    pub fn random<R: Rng>(rng: &mut R, degree: usize, p: &BigInt) -> Polynomial {
+        let mut coeffs: Vec<FieldElement> =
+            (0..degree).map(|_| FieldElement::random(rng, p)).collect();
+        let leading = loop {
+            let c = FieldElement::random(rng, p);
+            if !c.is_zero() {
+                break c;
+            }
+        };
+        coeffs.push(leading);
+        Polynomial::new(coeffs)
     }
 
     /// `y_values[i]` を `x = i` での値とする多項式を補間して返す。
@@ -173,23 +479,106 @@ impl Polynomial {
     /// assert_eq!(p.evaluate(&fe(2)), fe(2));
     /// ```
     pub fn lagrange_interpolation(y_values: &[FieldElement]) -> Polynomial {
-        if y_values.is_empty() {
+        // 補間点 x_i = 0, 1, 2 ... を明示して一般版に委譲する
+        let points: Vec<(FieldElement, FieldElement)> = y_values
+            .iter()
+            .enumerate()
+            .map(|(i, y_i)| (FieldElement::from_u64(i as u64, &y_i.p), y_i.clone()))
+            .collect();
+        Polynomial::lagrange_interpolation_at(&points)
+    }
+
+    /// 補間点 `x = 0, 1, ..., n − 1` のうち、値が非零の点 `(i, y_i)` だけを受け取って補間する。
+    ///
+    /// 載っていない点の値は 0 とみなすので、結果は 0 埋めした密ベクトルを
+    /// [`lagrange_interpolation`](Self::lagrange_interpolation) に渡したものと同じ。
+    /// R1CS の列のように大半が 0 の場合に、密ベクトルを作らず非零の項だけを足し込む。
+    /// 同じ `i` が複数回現れたら値を足し合わせる（線形結合の項と同じ扱い）。
+    ///
+    /// `z` は補間点の消失多項式 `Z(x) = Π_j (x − j)`（次数 `n`）。展開に `O(n^2)` かかるので
+    /// 呼び出し側で [`from_roots`](Self::from_roots) で 1 度だけ作り、同じ補間点の全列で使い回す。
+    /// 各項の分子 `Z(x) / (x − i)` は組立除法で、分母 `Π_{j≠i} (i − j) = (−1)^{n−1−i} · i! · (n−1−i)!`
+    /// は階乗から求めるので、1 回の呼び出しは `O(n + 非零の数 · n)`。
+    /// 空の入力には空多項式（0 多項式）を返す。
+    ///
+    /// # Panics
+    /// `z` が 0 多項式の場合、`i >= n` の点がある場合、`n > p`（補間点が `F_p` で重複する）の場合。
+    pub fn lagrange_interpolation_sparse(
+        nonzero: &[(usize, FieldElement)],
+        z: &Polynomial,
+    ) -> Polynomial {
+        let num_nodes = z.degree().expect("消失多項式が 0 多項式です");
+        let Some((_, first)) = nonzero.first() else {
+            return Polynomial::new(vec![]);
+        };
+        let p = first.p.clone();
+        if let Some((i, _)) = nonzero.iter().find(|(i, _)| *i >= num_nodes) {
+            panic!("補間点の範囲外です: {} >= {}", i, num_nodes);
+        }
+        assert!(
+            BigInt::from(num_nodes) <= *p,
+            "補間点の x 座標が重複しています: num_nodes = {} > p",
+            num_nodes
+        );
+
+        // factorials[k] = k!
+        let mut factorials = vec![FieldElement::one(&p)];
+        for k in 1..num_nodes {
+            factorials.push(&factorials[k - 1] * &FieldElement::from_u64(k as u64, &p));
+        }
+
+        let mut total = Polynomial::new(vec![FieldElement::zero(&p)]);
+        for (i, y_i) in nonzero {
+            if y_i.is_zero() {
+                continue;
+            }
+            let mut denominator = &factorials[*i] * &factorials[num_nodes - 1 - i];
+            if (num_nodes - 1 - i) % 2 == 1 {
+                denominator = -denominator;
+            }
+            let weight = y_i / &denominator;
+            let (numerator, _) = z.div_by_linear(&FieldElement::from_u64(*i as u64, &p));
+            total = &total + &numerator.scale(&weight);
+        }
+        total
+    }
+
+    /// 任意の点列 `(x_i, y_i)` を通る多項式を補間して返す。
+    ///
+    /// [`Polynomial::lagrange_interpolation`] と違い、補間点 `x_i` を呼び出し側が指定できる。
+    /// n 点から n-1 次以下の多項式が一意に定まる。計算量は `O(n^2)`。
+    ///
+    /// 同じ x 座標が 2 回現れると基底の分母 `x_i - x_j` が 0 になるため panic する。
+    ///
+    /// # 例
+    ///
+    /// ```text
+    /// // F_17 で (2, 5), (7, 3), (11, 9) を通る 2 次多項式
+    /// let p = Polynomial::lagrange_interpolation_at(&[(fe(2), fe(5)), (fe(7), fe(3)), (fe(11), fe(9))]);
+    /// assert_eq!(p.evaluate(&fe(7)), fe(3));
+    /// ```
+    pub fn lagrange_interpolation_at(points: &[(FieldElement, FieldElement)]) -> Polynomial {
+        if points.is_empty() {
             return Polynomial::new(vec![]);
         }
 
+        // 重複した x 座標は分母 0 になるので先に弾く
+        for (i, (xi, _)) in points.iter().enumerate() {
+            if points[..i].iter().any(|(xj, _)| xj == xi) {
+                panic!("補間点の x 座標が重複しています: {}", xi);
+            }
+        }
+
         // 素数 p を取得（計算に必要）
-        let p = y_values[0].p.clone();
+        let p = points[0].0.p.clone();
 
         // 合計用の多項式（最初は 0）
         let mut total_poly = Polynomial::new(vec![FieldElement::new(BigInt::from(0), p.clone())]);
 
-        let num_points = y_values.len();
-
-        // 各点 x_i = 0, 1, 2 ... についてループする
-        for (i, y_i) in y_values.iter().enumerate() {
+        for (i, (xi, y_i)) in points.iter().enumerate() {
             // y_i が 0 なら計算しても結果は 0 なのでスキップ（高速化）
             // ただし厳密には基底計算が必要だが、結果に寄与しないのでOK
-            if y_i.value == BigInt::from(0) {
+            if y_i.is_zero() {
                 continue;
             }
 
@@ -200,34 +589,27 @@ impl Polynomial {
                 Polynomial::new(vec![FieldElement::new(BigInt::from(1), p.clone())]);
             let mut denominator = FieldElement::new(BigInt::from(1), p.clone());
 
-            let xi = FieldElement::new(BigInt::from(i), p.clone());
-
-            for j in 0..num_points {
+            for (j, (xj, _)) in points.iter().enumerate() {
                 // 自分自身はスキップ
                 if i == j {
                     continue;
                 }
 
-                let xj = FieldElement::new(BigInt::from(j), p.clone());
-
                 // 分子に (x - xj) をかける
                 // (x - xj) という多項式は、係数が [-xj, 1]
-                // つまり [xj * -1, 1]
-                let zero = FieldElement::new(BigInt::from(0), p.clone());
-                let neg_xj = &zero - &xj;
                 let one = FieldElement::new(BigInt::from(1), p.clone());
-                let term = Polynomial::new(vec![neg_xj, one]);
+                let term = Polynomial::new(vec![-xj, one]);
                 numerator = &numerator * &term; // 多項式の掛け算
 
                 // 分母に (xi - xj) をかける
-                let diff = &xi - &xj;
+                let diff = xi - xj;
                 denominator = &denominator * &diff; // スカラーの掛け算
             }
 
             // 分母の逆数を計算して、分子にかける（割り算の代わり）
             let denom_inv = denominator
                 .inverse()
-                .expect("xi - xj is non-zero by construction (i != j");
+                .expect("xi - xj is non-zero (duplicates rejected above)");
             let basis_poly = numerator.scale(&denom_inv);
 
             // 高さをあわせて合計に足す： total += y_i * basis_poly
@@ -238,140 +620,653 @@ impl Polynomial {
         total_poly
     }
 
-    /// 全係数に `factor` を掛けたスカラー倍多項式を返す。
-    pub fn scale(&self, factor: &FieldElement) -> Polynomial {
-        let new_coeffs = self.coefficients.iter().map(|c| c * factor).collect();
-        Polynomial::new(new_coeffs)
-    }
-}
+    /// 補間点 `xs` のラグランジュ基底多項式 `L_0(x), ..., L_{n-1}(x)` を返す。
+    ///
+    /// `L_i(x) = Π_{j≠i} (x − x_j) / Π_{j≠i} (x_i − x_j)` で、`L_i(x_j) = δ_ij`。
+    /// 補間点が同じなら基底は値 `y_i` によらないので、QAP 構築のように
+    /// 同じ点で何列も補間する場合は 1 度だけ作って
+    /// [`from_lagrange_basis`](Self::from_lagrange_basis) に渡す。
+    ///
+    /// 消失多項式 `Z(x) = Π (x − x_j)` を 1 度だけ展開し、分子は `Z(x) / (x − x_i)` を
+    /// 組立除法で、分母は [`FieldElement::batch_inverse`] でまとめて求める。
+    /// 計算量は全体で `O(n^2)`（[`lagrange_interpolation_at`](Self::lagrange_interpolation_at)
+    /// は分子を毎回掛け算で組み立てるので 1 回の補間で `O(n^3)`）。
+    ///
+    /// 同じ x 座標が 2 回現れると panic する。空の点列には空 vec を返す。
+    pub fn lagrange_basis(xs: &[FieldElement]) -> Vec<Polynomial> {
+        if xs.is_empty() {
+            return Vec::new();
+        }
+        for (i, xi) in xs.iter().enumerate() {
+            if xs[..i].contains(xi) {
+                panic!("補間点の x 座標が重複しています: {}", xi);
+            }
+        }
 
-/// 多項式の加算: 同じ次数の係数同士を加算する。
-impl<'b> Add<&'b Polynomial> for &Polynomial {
-    type Output = Polynomial;
+        let z = Polynomial::from_roots(xs, &xs[0].p);
+        let numerators: Vec<Polynomial> = xs.iter().map(|xi| z.div_by_linear(xi).0).collect();
+        // 分母 Π_{j≠i} (x_i − x_j) は分子 Z(x) / (x − x_i) の x_i での値
+        let denominators: Vec<FieldElement> = numerators
+            .iter()
+            .zip(xs)
+            .map(|(num, xi)| num.evaluate(xi))
+            .collect();
+        let inverses = FieldElement::batch_inverse(&denominators);
+        numerators
+            .iter()
+            .zip(&inverses)
+            .map(|(num, inv)| num.scale(inv))
+            .collect()
+    }
 
-    fn add(self, other: &'b Polynomial) -> Polynomial {
-        // 1. 両方とも空なら、空を返す
-        if self.coefficients.is_empty() && other.coefficients.is_empty() {
+    /// 基底 `basis = [L_0, ..., L_{n-1}]` を使って `Σ y_i · L_i(x)` を返す。
+    ///
+    /// `basis` は [`lagrange_basis`](Self::lagrange_basis) の返り値で、`y_values[i]` は
+    /// `i` 番目の補間点での値。`y_i = 0` の項は足さない。計算量は `O(非零の数 · n)`。
+    ///
+    /// # Panics
+    /// `basis` と `y_values` の長さが異なると panic する。
+    pub fn from_lagrange_basis(basis: &[Polynomial], y_values: &[FieldElement]) -> Polynomial {
+        assert_eq!(
+            basis.len(),
+            y_values.len(),
+            "基底の数と値の数が一致しません"
+        );
+        let Some(first) = y_values.first() else {
             return Polynomial::new(vec![]);
-        }
-
-        // 2. p を安全に取得する
-        // self が空なら other から取得する
-        let p = if !self.coefficients.is_empty() {
-            self.coefficients[0].p.clone()
-        } else {
-            other.coefficients[0].p.clone()
         };
 
-        let max_len = std::cmp::max(self.coefficients.len(), other.coefficients.len());
-        let mut res_coeffs = Vec::with_capacity(max_len);
-
-        for i in 0..max_len {
-            let zero = FieldElement::new(num_bigint::BigInt::from(0), p.clone());
-            let a = self.coefficients.get(i).unwrap_or(&zero);
-            let b = other.coefficients.get(i).unwrap_or(&zero);
-
-            // ここで参照同士の足し算
-            res_coeffs.push(a + b);
+        let mut total = Polynomial::new(vec![FieldElement::zero(&first.p)]);
+        for (l_i, y_i) in basis.iter().zip(y_values) {
+            if y_i.is_zero() {
+                continue;
+            }
+            total = &total + &l_i.scale(y_i);
         }
-
-        Polynomial::new(res_coeffs)
+        total
     }
-}
-
-/// 多項式の減算: 同じ次数の係数同士を減算する。
-impl<'b> Sub<&'b Polynomial> for &Polynomial {
-    type Output = Polynomial;
-
-    fn sub(self, other: &'b Polynomial) -> Polynomial {
-        let max_len = std::cmp::max(self.coefficients.len(), other.coefficients.len());
-        let mut res_coeffs = Vec::with_capacity(max_len);
-        let p = self.coefficients[0].p.clone();
 
-        for i in 0..max_len {
-            let zero = FieldElement::new(BigInt::from(0), p.clone());
-            let a = self.coefficients.get(i).unwrap_or(&zero);
-            let b = other.coefficients.get(i).unwrap_or(&zero);
-            res_coeffs.push(a - b);
+    /// ユークリッドの互除法で `self` と `other` の最大公約多項式を返す。
+    ///
+    /// `(a, b) ← (b, a mod b)` を `b` が 0 多項式になるまで繰り返し、残った `a` を
+    /// 最高次係数で割ってモニック（最高次係数 1）に正規化する。
+    /// 片方が 0 多項式なら、もう片方をモニック化したものを返す。
+    /// 両方 0 多項式なら 0 多項式を返す。
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while b.degree().is_some() {
+            let (_, r) = a.div_rem(&b);
+            a = b;
+            b = r;
         }
 
-        Polynomial::new(res_coeffs)
+        // a が 0 多項式（両方 0 だった）なら make_monic はそのまま返す
+        a.make_monic()
     }
-}
-
-/// 多項式の乗算: 各係数を畳み込んで `i + j` 次の項に集約する（計算量 `O(n*m)`）。
-impl<'b> Mul<&'b Polynomial> for &Polynomial {
-    type Output = Polynomial;
-
-    fn mul(self, other: &'b Polynomial) -> Polynomial {
-        let p = self.coefficients[0].p.clone();
-        // どちらの多項式にも含まれている 0次のオフセットを、重複して数えないように調整
-        let new_len = self.coefficients.len() + other.coefficients.len() - 1;
-        let mut res_coeffs = vec![FieldElement::new(BigInt::from(0), p.clone()); new_len];
 
-        for i in 0..self.coefficients.len() {
-            for j in 0..other.coefficients.len() {
-                let product = &self.coefficients[i] * &other.coefficients[j];
-                res_coeffs[i + j] = &res_coeffs[i + j] + &product;
+    /// 最高次係数で全係数を割り、モニック（最高次係数 1）にした多項式を返す。
+    ///
+    /// 定数倍しか変えないので根は変わらない。0 多項式はそのまま返す。
+    pub fn make_monic(&self) -> Polynomial {
+        match self.leading_coefficient() {
+            None => self.clone(),
+            Some(lc) => {
+                let leading_inv = lc
+                    .inverse()
+                    .expect("leading coefficient of a non-zero polynomial is non-zero");
+                self.scale(&leading_inv)
             }
         }
-
-        Polynomial::new(res_coeffs)
     }
-}
 
-impl<'b> Div<&'b Polynomial> for &Polynomial {
-    type Output = Polynomial;
-
-    fn div(self, other: &'b Polynomial) -> Polynomial {
-        let (q, _r) = self.div_rem(other);
-        q
+    /// 最高次係数が 1 かどうかを返す。0 多項式は `false`。
+    pub fn is_monic(&self) -> bool {
+        self.leading_coefficient().is_some_and(|lc| lc.is_one())
     }
-}
 
-impl std::fmt::Display for Polynomial {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.coefficients.is_empty() {
-            return write!(f, "0");
+    /// 形式微分 `P'(x)` を返す。
+    ///
+    /// `x^i` の係数 `a_i` を `x^{i-1}` の係数 `i · a_i` に移す（定数項は消える）。
+    /// `i` は体の元として掛けるので、`i ≡ 0 (mod p)` の項も消える。
+    /// 定数・0 多項式の微分は 0 多項式、空多項式の微分は空多項式。
+    pub fn derivative(&self) -> Polynomial {
+        let p = match self.coefficients.first() {
+            Some(c) => c.p.clone(),
+            None => return Polynomial::new(vec![]),
+        };
+        if self.coefficients.len() == 1 {
+            return Polynomial::new(vec![FieldElement::new(0, p)]);
         }
-
-        let s = self
+        let new_coeffs = self
             .coefficients
             .iter()
             .enumerate()
-            .rev()
-            .filter(|(_, coeff)| coeff.value != BigInt::from(0) || self.degree() == 0)
-            .map(|(i, coeff)| {
-                if i == 0 {
-                    format!("{}", coeff.value) // 定数項
-                } else if i == 1 {
-                    format!("{}x", coeff.value) // 1次の項
-                } else {
-                    format!("{}x^{}", coeff.value, i) // 2次以上の項
-                }
-            })
-            .collect::<Vec<_>>()
-            .join(" + ");
-
-        write!(f, "{}", if s.is_empty() { "0".to_string() } else { s })
+            .skip(1)
+            .map(|(i, c)| &FieldElement::new(i, p.clone()) * c)
+            .collect();
+        Polynomial::new(new_coeffs)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// 数論変換（NTT）で多項式の積を計算する。
+    ///
+    /// 積の係数長以上の 2 冪 `n = 2^k` を取り、位数 `n` の 1 の冪根 ω で両オペランドを
+    /// 評価形に変換（NTT）→ 点ごとに掛ける → 逆変換（INTT）する。計算量は `O(n log n)`。
+    /// `n | (p - 1)` でないと ω が存在しないので、その場合は筆算（`Mul`）にフォールバックする
+    /// （例: `p = 257` なら長さ 256 まで NTT、`p = 7` では長さ 2 まで）。
+    /// `p = 2` は `p - 1 = 1` で長さ 1 の NTT しかできないので、最初から筆算にする。
+    pub fn mul_ntt(&self, other: &Polynomial) -> Polynomial {
+        if self.coefficients.is_empty() || other.coefficients.is_empty() {
+            return Polynomial::new(vec![]);
+        }
+        let p = self.coefficients[0].p.clone();
+        if *p == BigInt::from(2) {
+            return self * other;
+        }
+        let result_len = self.coefficients.len() + other.coefficients.len() - 1;
+        let n = result_len.next_power_of_two();
 
-    const P: i64 = 7;
+        let omega = match two_adic_root_of_unity(n, &p) {
+            Some(w) => w,
+            None => return self * other,
+        };
 
-    fn fe(v: i64) -> FieldElement {
-        FieldElement::new(v, P)
+        let zero = FieldElement::new(0, p.clone());
+        let mut a = self.coefficients.clone();
+        let mut b = other.coefficients.clone();
+        a.resize(n, zero.clone());
+        b.resize(n, zero);
+
+        ntt_in_place(&mut a, &omega);
+        ntt_in_place(&mut b, &omega);
+        let mut c: Vec<FieldElement> = a.iter().zip(&b).map(|(x, y)| x * y).collect();
+
+        // 逆変換: ω^{-1} で NTT してから n^{-1} を掛ける
+        let omega_inv = omega.inverse().expect("root of unity is non-zero");
+        ntt_in_place(&mut c, &omega_inv);
+        let n_inv = FieldElement::new(n, p)
+            .inverse()
+            .expect("n divides p - 1, so n is invertible mod p");
+        let coeffs = c.iter().map(|x| x * &n_inv).collect();
+        Polynomial::new(coeffs)
     }
 
-    fn poly(coeffs: &[i64]) -> Polynomial {
-        Polynomial::new(coeffs.iter().map(|&c| fe(c)).collect())
+    /// Karatsuba 法で多項式の積を計算する。
+    ///
+    /// 係数列を上下半分 `a = a0 + a1·x^m` に分け、
+    /// `a·b = z0 + (z1 − z0 − z2)·x^m + z2·x^{2m}`
+    /// （`z0 = a0·b0`, `z2 = a1·b1`, `z1 = (a0+a1)(b0+b1)`）の 3 回の部分積で済ませる。
+    /// 計算量は `O(n^1.58)`。1 の冪根を要求しないので任意の素数で使える。
+    /// 長さが [`KARATSUBA_THRESHOLD`] 以下になったら筆算に切り替える。
+    /// 長さの違うオペランドは短い方を 0 で埋めて揃える。
+    pub fn mul_karatsuba(&self, other: &Polynomial) -> Polynomial {
+        if self.coefficients.is_empty() || other.coefficients.is_empty() {
+            return Polynomial::new(vec![]);
+        }
+        let zero = FieldElement::new(0, self.coefficients[0].p.clone());
+        let result_len = self.coefficients.len() + other.coefficients.len() - 1;
+        let mut coeffs = karatsuba(&self.coefficients, &other.coefficients, &zero);
+        coeffs.truncate(result_len);
+        Polynomial::new(coeffs)
     }
 
-    #[test]
+    /// 定数項に `c` を足した多項式 `P(x) + c` を返す。
+    ///
+    /// 空多項式に対しては定数多項式 `c` を返す。
+    pub fn add_scalar(&self, c: &FieldElement) -> Polynomial {
+        let mut coeffs = self.coefficients.clone();
+        match coeffs.first_mut() {
+            Some(c0) => *c0 = &*c0 + c,
+            None => coeffs.push(c.clone()),
+        }
+        Polynomial::new(coeffs)
+    }
+
+    /// 定数項から `c` を引いた多項式 `P(x) - c` を返す。
+    pub fn sub_scalar(&self, c: &FieldElement) -> Polynomial {
+        self.add_scalar(&-c)
+    }
+
+    /// `x^k` を掛けた多項式を返す（係数列の先頭に `k` 個の 0 を挿入する）。
+    ///
+    /// 0 多項式・空多項式はそのまま返す。
+    pub fn mul_by_x_power(&self, k: usize) -> Polynomial {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let zero = FieldElement::zero(&self.coefficients[0].p);
+        let mut coeffs = vec![zero; k];
+        coeffs.extend(self.coefficients.iter().cloned());
+        Polynomial::new(coeffs)
+    }
+
+    /// 全係数に `factor` を掛けたスカラー倍多項式を返す。
+    pub fn scale(&self, factor: &FieldElement) -> Polynomial {
+        let new_coeffs = self.coefficients.iter().map(|c| c * factor).collect();
+        Polynomial::new(new_coeffs)
+    }
+}
+
+/// [`Polynomial::mul_karatsuba`] が筆算に切り替える係数長。
+///
+/// 小さい長さでは分割と加減算のオーバーヘッドが勝つため。
+pub const KARATSUBA_THRESHOLD: usize = 16;
+
+/// 係数列どうしの筆算の積。結果の長さは `a.len() + b.len() - 1`。
+fn schoolbook_mul(
+    a: &[FieldElement],
+    b: &[FieldElement],
+    zero: &FieldElement,
+) -> Vec<FieldElement> {
+    let mut res = vec![zero.clone(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            res[i + j] = &res[i + j] + &(x * y);
+        }
+    }
+    res
+}
+
+/// Karatsuba 法の本体。`a`, `b` は空でない係数列。
+///
+/// 長さを揃えて（0 埋め）から分割するので、結果の長さは `2·max(len) − 1`
+/// （末尾に余分な 0 が付きうる。呼び出し側で切り詰める）。
+fn karatsuba(a: &[FieldElement], b: &[FieldElement], zero: &FieldElement) -> Vec<FieldElement> {
+    let n = a.len().max(b.len());
+    if n <= KARATSUBA_THRESHOLD {
+        return schoolbook_mul(a, b, zero);
+    }
+
+    // 長さを n に揃える
+    let pad = |v: &[FieldElement]| {
+        let mut v = v.to_vec();
+        v.resize(n, zero.clone());
+        v
+    };
+    let (a, b) = (pad(a), pad(b));
+
+    // a = a0 + a1·x^m, b = b0 + b1·x^m
+    let m = n / 2;
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+
+    let z0 = karatsuba(a0, b0, zero);
+    let z2 = karatsuba(a1, b1, zero);
+    // (a0 + a1), (b0 + b1)。a1 の方が長い（n - m >= m）ので a1 側に足し込む
+    let a_sum: Vec<FieldElement> = a1
+        .iter()
+        .enumerate()
+        .map(|(i, x)| a0.get(i).map_or(x.clone(), |y| x + y))
+        .collect();
+    let b_sum: Vec<FieldElement> = b1
+        .iter()
+        .enumerate()
+        .map(|(i, x)| b0.get(i).map_or(x.clone(), |y| x + y))
+        .collect();
+    let z1 = karatsuba(&a_sum, &b_sum, zero);
+
+    // 結果 = z0 + (z1 − z0 − z2)·x^m + z2·x^{2m}
+    let mut res = vec![zero.clone(); 2 * n - 1];
+    for (i, c) in z0.iter().enumerate() {
+        res[i] = &res[i] + c;
+        res[i + m] = &res[i + m] - c;
+    }
+    for (i, c) in z2.iter().enumerate() {
+        res[i + 2 * m] = &res[i + 2 * m] + c;
+        res[i + m] = &res[i + m] - c;
+    }
+    for (i, c) in z1.iter().enumerate() {
+        res[i + m] = &res[i + m] + c;
+    }
+    res
+}
+
+/// 位数がちょうど `n`（2 冪）の 1 の冪根を返す。`n ∤ (p - 1)` なら `None`。
+///
+/// `p - 1 = q · 2^s`（`q` は奇数）とし、平方非剰余 `z` から `c = z^q` を作ると
+/// `c` の位数はちょうど `2^s` になる（`z^((p-1)/2) = -1` のため）。
+/// これを `2^s / n` 乗すれば位数 `n` の元が得られる。`p - 1` の素因数分解は不要。
+fn two_adic_root_of_unity(n: usize, p: &BigInt) -> Option<FieldElement> {
+    debug_assert!(n.is_power_of_two());
+    let zero = BigInt::from(0);
+    let two = BigInt::from(2);
+
+    // p - 1 = q · 2^s
+    let mut q = p - BigInt::from(1);
+    let mut s = 0u32;
+    while &q % &two == zero {
+        q /= &two;
+        s += 1;
+    }
+    let k = n.trailing_zeros();
+    if k > s {
+        return None;
+    }
+    // 位数 1 の冪根は 1。F_2 には平方非剰余がないので、探索の前に返す
+    let one = FieldElement::new(1, p.clone());
+    if n == 1 {
+        return Some(one);
+    }
+
+    // 平方非剰余 z を 2, 3, ... から探す
+    let mut z = FieldElement::new(2, p.clone());
+    while z.legendre() != -1 {
+        z = &z + &one;
+    }
+
+    // c = z^q は位数 2^s。2^(s-k) 乗して位数 2^k = n にする
+    let mut root = z.pow_ref(&q);
+    for _ in 0..(s - k) {
+        root = root.square();
+    }
+    Some(root)
+}
+
+/// 長さ 2 冪の列 `values` を、位数 `values.len()` の 1 の冪根 `omega` で
+/// 反復型 Cooley-Tukey（radix-2）により NTT する（in-place）。
+///
+/// 出力 `values[j] = Σ_i values_in[i] · ω^{ij}`。`omega` に `ω^{-1}` を渡すと
+/// （`1/n` 倍を除いて）逆変換になる。
+fn ntt_in_place(values: &mut [FieldElement], omega: &FieldElement) {
+    let n = values.len();
+    // 長さ 1 の NTT は恒等写像（ビット反転のシフト量も 0 ビットで破綻する）
+    if n <= 1 {
+        return;
+    }
+    let log_n = n.trailing_zeros();
+
+    // 1. ビット反転順に並べ替える
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    // 2. バタフライ演算（長さ 2, 4, ..., n のブロックを順に合成）
+    let mut len = 2;
+    while len <= n {
+        // このブロック長で使う 1 の原始 len 乗根: ω^(n/len)
+        let w_len = omega.pow(n / len);
+        for start in (0..n).step_by(len) {
+            let mut w = FieldElement::new(1, omega.p.clone());
+            for k in 0..len / 2 {
+                let u = values[start + k].clone();
+                let v = &values[start + k + len / 2] * &w;
+                values[start + k] = &u + &v;
+                values[start + k + len / 2] = &u - &v;
+                w = &w * &w_len;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// 末尾の 0 係数を除いた係数列どうしで比較する。
+///
+/// モジュール内では `new()` を通らない値（`[1, 0]` など）も作れる。
+/// そうした表現の違いは無視し、同じ多項式を表すなら等しいとみなす
+/// （空多項式と `[0]` もどちらも 0 多項式として等しい）。
+impl PartialEq for Polynomial {
+    fn eq(&self, other: &Self) -> bool {
+        fn trimmed(coeffs: &[FieldElement]) -> &[FieldElement] {
+            let len = coeffs
+                .iter()
+                .rposition(|c| !c.is_zero())
+                .map_or(0, |i| i + 1);
+            &coeffs[..len]
+        }
+        trimmed(&self.coefficients) == trimmed(&other.coefficients)
+    }
+}
+
+/// 2 つの法が一致するか確認する。
+fn check_modulus(left: &BigInt, right: &BigInt) -> Result<(), PolyError> {
+    if left == right {
+        Ok(())
+    } else {
+        Err(PolyError::ModulusMismatch {
+            left: left.clone(),
+            right: right.clone(),
+        })
+    }
+}
+
+/// `[x^0, x^1, ..., x^{n-1}]` を返す（[`Polynomial::evaluate_with_powers`] 用）。
+///
+/// 次数 `d` 以下の多項式を評価するには `n = d + 1` 個必要。`n == 0` なら空 vec。
+pub fn powers(x: &FieldElement, n: usize) -> Vec<FieldElement> {
+    let mut result = Vec::with_capacity(n);
+    let mut current = FieldElement::one(&x.p);
+    for _ in 0..n {
+        let next = &current * x;
+        result.push(current);
+        current = next;
+    }
+    result
+}
+
+/// 多項式の加算: 同じ次数の係数同士を加算する。
+impl<'b> Add<&'b Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, other: &'b Polynomial) -> Polynomial {
+        // 1. 両方とも空なら、空を返す
+        if self.coefficients.is_empty() && other.coefficients.is_empty() {
+            return Polynomial::new(vec![]);
+        }
+
+        // 2. p を安全に取得する
+        // self が空なら other から取得する
+        let p = if !self.coefficients.is_empty() {
+            self.coefficients[0].p.clone()
+        } else {
+            other.coefficients[0].p.clone()
+        };
+
+        let max_len = std::cmp::max(self.coefficients.len(), other.coefficients.len());
+        let mut res_coeffs = Vec::with_capacity(max_len);
+
+        for i in 0..max_len {
+            let zero = FieldElement::new(num_bigint::BigInt::from(0), p.clone());
+            let a = self.coefficients.get(i).unwrap_or(&zero);
+            let b = other.coefficients.get(i).unwrap_or(&zero);
+
+            // ここで参照同士の足し算
+            res_coeffs.push(a + b);
+        }
+
+        Polynomial::new(res_coeffs)
+    }
+}
+
+/// 多項式の減算: 同じ次数の係数同士を減算する。
+impl<'b> Sub<&'b Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, other: &'b Polynomial) -> Polynomial {
+        // 両方とも空なら、空を返す
+        if self.coefficients.is_empty() && other.coefficients.is_empty() {
+            return Polynomial::new(vec![]);
+        }
+
+        let max_len = std::cmp::max(self.coefficients.len(), other.coefficients.len());
+        let mut res_coeffs = Vec::with_capacity(max_len);
+        // self が空なら other から p を取得する
+        let p = self
+            .coefficients
+            .first()
+            .or(other.coefficients.first())
+            .unwrap()
+            .p
+            .clone();
+
+        for i in 0..max_len {
+            let zero = FieldElement::new(BigInt::from(0), p.clone());
+            let a = self.coefficients.get(i).unwrap_or(&zero);
+            let b = other.coefficients.get(i).unwrap_or(&zero);
+            res_coeffs.push(a - b);
+        }
+
+        Polynomial::new(res_coeffs)
+    }
+}
+
+/// 多項式の符号反転: 各係数を加法逆元にする。
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Polynomial {
+        Polynomial::new(self.coefficients.iter().map(|c| -c).collect())
+    }
+}
+
+/// 多項式の乗算: [`Polynomial::try_mul`] に委譲する。法が異なる場合は panic する。
+///
+/// どちらかが空多項式なら空多項式を返す（法 `p` を取り出せず、長さ計算も
+/// `0 + m - 1` で破綻するため、`try_mul` はエラーにする）。
+impl<'b> Mul<&'b Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, other: &'b Polynomial) -> Polynomial {
+        match self.try_mul(other) {
+            Ok(product) => product,
+            Err(PolyError::EmptyOperand) => Polynomial::new(vec![]),
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+/// 多項式の商: [`Polynomial::try_div`] に委譲する。0 多項式で割ると panic する。
+impl<'b> Div<&'b Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn div(self, other: &'b Polynomial) -> Polynomial {
+        self.try_div(other).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+/// 多項式の剰余: [`Polynomial::div_rem`] の余りを返す（商は捨てる）。
+///
+/// QAP の割り切れ判定 `(P(x) % Z(x)).is_zero()` に使う。
+/// `other` が 0 多項式の場合は `div_rem` と同じく panic する。
+impl<'b> Rem<&'b Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn rem(self, other: &'b Polynomial) -> Polynomial {
+        let (_q, r) = self.div_rem(other);
+        r
+    }
+}
+
+/// `p %= q`: 剰余をその場で代入する。
+impl<'b> RemAssign<&'b Polynomial> for Polynomial {
+    fn rem_assign(&mut self, other: &'b Polynomial) {
+        *self = &*self % other;
+    }
+}
+
+/// 高次の項から `"2x^2 + x + 1"` の形式で表示する。
+///
+/// 0 係数の項は省き、非定数項の係数 1 は書かない（`1x` ではなく `x`）。
+/// 係数は `0..p` の代表元のまま出す（`x^2 - 1` は `x^2 + 16` になる）。
+/// 負の係数として読みたいときは [`Polynomial::to_signed_string`] を使う。
+impl std::fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format_terms(false))
+    }
+}
+
+impl Polynomial {
+    /// `p/2` を超える係数を負数として表示した文字列を返す（`16` ではなく `-1`）。
+    ///
+    /// `x^2 - 1` in F_17 は `"x^2 - 1"`、[`Display`](std::fmt::Display) では `"x^2 + 16"`。
+    pub fn to_signed_string(&self) -> String {
+        self.format_terms(true)
+    }
+
+    /// [`Display`](std::fmt::Display) と [`to_signed_string`](Self::to_signed_string) の共通部分。
+    ///
+    /// `signed` なら係数 `c > p/2` を `-(p - c)` として扱い、項の間を ` - ` でつなぐ。
+    fn format_terms(&self, signed: bool) -> String {
+        let mut s = String::new();
+        for (i, coeff) in self.coefficients.iter().enumerate().rev() {
+            if coeff.is_zero() {
+                continue;
+            }
+
+            // (負かどうか, 表示する絶対値)
+            let half = &*coeff.p / 2;
+            let (negative, abs) = if signed && coeff.value > half {
+                (true, &*coeff.p - &coeff.value)
+            } else {
+                (false, coeff.value.clone())
+            };
+
+            // 項の区切り（先頭の項は符号だけ付ける）
+            if s.is_empty() {
+                if negative {
+                    s.push('-');
+                }
+            } else {
+                s.push_str(if negative { " - " } else { " + " });
+            }
+
+            // 非定数項の係数 1 は省く
+            let coeff_str = if i > 0 && abs == BigInt::from(1) {
+                String::new()
+            } else {
+                abs.to_string()
+            };
+            match i {
+                0 => s.push_str(&coeff_str),                       // 定数項
+                1 => s.push_str(&format!("{}x", coeff_str)),       // 1次の項
+                _ => s.push_str(&format!("{}x^{}", coeff_str, i)), // 2次以上の項
+            }
+        }
+
+        if s.is_empty() {
+            "0".to_string()
+        } else {
+            s
+        }
+    }
+}
+
+/// 係数列（低次から）を [`FieldElement`] の配列としてシリアライズする。
+///
+/// 各係数は [`FieldElement`] の形式（10 進文字列）になる。
+/// 例（JSON）: `[{"value":"1","p":"7"},{"value":"2","p":"7"}]`
+#[cfg(feature = "serde")]
+impl serde::Serialize for Polynomial {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.coefficients.serialize(serializer)
+    }
+}
+
+/// [`Serialize`](serde::Serialize) の逆。読み込み後に [`Polynomial::new`] で正規化する。
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Polynomial {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<FieldElement>::deserialize(deserializer).map(Polynomial::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: i64 = 7;
+
+    fn fe(v: i64) -> FieldElement {
+        FieldElement::new(v, P)
+    }
+
+    fn poly(coeffs: &[i64]) -> Polynomial {
+        Polynomial::new(coeffs.iter().map(|&c| fe(c)).collect())
+    }
+
+    #[test]
     fn new_strips_trailing_zeros() {
         // [1, 2, 0, 0] -> [1, 2]
         let p = poly(&[1, 2, 0, 0]);
@@ -386,9 +1281,150 @@ mod tests {
 
     #[test]
     fn degree_basic_cases() {
-        assert_eq!(poly(&[5]).degree(), 0);
-        assert_eq!(poly(&[1, 2]).degree(), 1);
-        assert_eq!(poly(&[1, 0, 3]).degree(), 2);
+        assert_eq!(poly(&[5]).degree(), Some(0));
+        assert_eq!(poly(&[1, 2]).degree(), Some(1));
+        assert_eq!(poly(&[1, 0, 3]).degree(), Some(2));
+    }
+
+    #[test]
+    fn degree_of_zero_and_empty_polynomial_is_none() {
+        assert_eq!(Polynomial::new(vec![]).degree(), None);
+        assert_eq!(poly(&[0]).degree(), None);
+        assert_eq!(poly(&[0, 0, 0]).degree(), None);
+    }
+
+    #[test]
+    fn div_rem_zero_dividend() {
+        // 0 / (x + 1) → q = 0, r = 0
+        let (q, r) = poly(&[0]).div_rem(&poly(&[1, 1]));
+        assert!(q.is_zero());
+        assert!(r.is_zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "0多項式")]
+    fn div_rem_by_empty_polynomial_panics() {
+        let _ = poly(&[1, 1]).div_rem(&Polynomial::new(vec![]));
+    }
+
+    #[test]
+    fn try_div_rem_by_zero_returns_error() {
+        let dividend = poly(&[1, 1]);
+        assert_eq!(dividend.try_div_rem(&poly(&[0])), Err(PolyError::DivByZero));
+        assert_eq!(
+            dividend.try_div_rem(&Polynomial::new(vec![])),
+            Err(PolyError::DivByZero)
+        );
+        assert_eq!(dividend.try_div(&poly(&[0, 0])), Err(PolyError::DivByZero));
+    }
+
+    #[test]
+    fn try_mul_with_empty_operand_returns_error() {
+        let empty = Polynomial::new(vec![]);
+        assert_eq!(poly(&[1, 2]).try_mul(&empty), Err(PolyError::EmptyOperand));
+        assert_eq!(empty.try_mul(&poly(&[1, 2])), Err(PolyError::EmptyOperand));
+        // 演算子は従来どおり空多項式を返す
+        assert!((&poly(&[1, 2]) * &empty).coefficients.is_empty());
+    }
+
+    #[test]
+    fn try_ops_with_different_modulus_return_error() {
+        let f7 = poly(&[1, 2]);
+        let f17 = Polynomial::new(vec![FieldElement::new(3, 17), FieldElement::new(1, 17)]);
+        let err = PolyError::ModulusMismatch {
+            left: BigInt::from(7),
+            right: BigInt::from(17),
+        };
+        assert_eq!(f7.try_mul(&f17), Err(err.clone()));
+        assert_eq!(f7.try_div_rem(&f17), Err(err.clone()));
+        assert_eq!(f7.try_div(&f17), Err(err));
+    }
+
+    #[test]
+    fn try_ops_match_operators() {
+        let a = poly(&[3, 0, 5, 1]);
+        let b = poly(&[2, 1]);
+        assert_eq!(a.try_mul(&b), Ok(&a * &b));
+        assert_eq!(a.try_div(&b), Ok(&a / &b));
+        assert_eq!(a.try_div_rem(&b), Ok(a.div_rem(&b)));
+    }
+
+    #[test]
+    #[should_panic(expected = "異なる標数")]
+    fn mul_with_different_modulus_panics() {
+        let f17 = Polynomial::new(vec![FieldElement::new(3, 17)]);
+        let _ = &poly(&[1, 2]) * &f17;
+    }
+
+    #[test]
+    fn coefficient_past_degree_is_zero() {
+        let p = poly(&[1, 2, 3]);
+        assert_eq!(p.coefficient(1), fe(2));
+        assert_eq!(p.coefficient(3), fe(0));
+        assert_eq!(p.coefficient(100), fe(0));
+        assert_eq!(p.coefficients(), &[fe(1), fe(2), fe(3)]);
+    }
+
+    #[test]
+    fn set_coefficient_grows_and_retrims() {
+        let mut p = poly(&[1, 2]);
+        // x^5 の係数を立てると間が 0 で埋まり、次数が 5 になる
+        p.set_coefficient(5, fe(4));
+        assert_eq!(p.degree(), Some(5));
+        assert_eq!(
+            p.coefficients(),
+            &[fe(1), fe(2), fe(0), fe(0), fe(0), fe(4)]
+        );
+        assert_eq!(p.evaluate(&fe(1)), fe(0)); // 1 + 2 + 4 = 7 ≡ 0
+
+        // 最高次を 0 に戻すと末尾の 0 が取り除かれ、元の多項式に戻る
+        p.set_coefficient(5, fe(0));
+        assert_eq!(p.coefficients(), &[fe(1), fe(2)]);
+        assert_eq!(p.degree(), Some(1));
+
+        // 全係数を 0 にすると [0] になる
+        p.set_coefficient(1, fe(0));
+        p.set_coefficient(0, fe(0));
+        assert_eq!(p.coefficients(), &[fe(0)]);
+
+        // 空多項式にも設定できる
+        let mut empty = Polynomial::new(vec![]);
+        empty.set_coefficient(2, fe(3));
+        assert_eq!(empty.coefficients(), &[fe(0), fe(0), fe(3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "異なる標数")]
+    fn set_coefficient_with_different_modulus_panics() {
+        let mut p = poly(&[1, 2]);
+        p.set_coefficient(0, FieldElement::new(1, 17));
+    }
+
+    #[test]
+    fn div_by_linear_remainder_is_evaluation_and_reconstructs_input() {
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut rng = ark_std::test_rng();
+        let p17 = BigInt::from(17);
+        for degree in 0..8 {
+            let poly = Polynomial::random(&mut rng, degree, &p17);
+            for a in [0, 1, 5, 16] {
+                let a = f17(a);
+                let (q, r) = poly.div_by_linear(&a);
+                assert_eq!(r, poly.evaluate(&a));
+                // q(x)·(x − a) + r = self
+                let linear = Polynomial::new(vec![-&a, f17(1)]);
+                assert_eq!((&q * &linear).add_scalar(&r), poly);
+                // 一般の長除法と同じ商
+                assert_eq!(q, poly.div_rem(&linear).0);
+            }
+        }
+    }
+
+    #[test]
+    fn div_by_linear_of_constant_has_zero_quotient() {
+        let (q, r) = poly(&[4]).div_by_linear(&fe(3));
+        assert!(q.is_zero());
+        assert_eq!(r, fe(4));
     }
 
     #[test]
@@ -398,6 +1434,24 @@ mod tests {
         assert_eq!(p.evaluate(&fe(3)), fe(0));
     }
 
+    #[test]
+    fn evaluate_domain_matches_pointwise_evaluate() {
+        // P(x) = 3 + 2x + x^2
+        let p = poly(&[3, 2, 1]);
+        let values = p.evaluate_domain(5);
+        assert_eq!(values.len(), 5);
+        for (i, v) in values.iter().enumerate() {
+            assert_eq!(*v, p.evaluate(&fe(i as i64)));
+        }
+    }
+
+    #[test]
+    fn evaluate_batch_at_arbitrary_points() {
+        let p = poly(&[1, 2]); // 1 + 2x
+        let points = vec![fe(3), fe(5), fe(0)];
+        assert_eq!(p.evaluate_batch(&points), vec![fe(0), fe(4), fe(1)]);
+    }
+
     #[test]
     fn add_handles_different_lengths() {
         // (1 + 2x) + (3 + x^2) = 4 + 2x + x^2
@@ -422,6 +1476,23 @@ mod tests {
         assert_eq!((&a * &b).coefficients, vec![fe(1), fe(0), fe(6)]);
     }
 
+    #[test]
+    fn mul_by_empty_polynomial_returns_empty() {
+        let empty = Polynomial::new(vec![]);
+        let a = poly(&[1, 2]);
+        assert!((&a * &empty).coefficients.is_empty());
+        assert!((&empty * &a).coefficients.is_empty());
+        assert!((&empty * &empty).coefficients.is_empty());
+    }
+
+    #[test]
+    fn mul_by_zero_polynomial_returns_zero() {
+        let zero = poly(&[0]);
+        let a = poly(&[1, 2]);
+        assert!((&a * &zero).is_zero());
+        assert!((&zero * &a).is_zero());
+    }
+
     #[test]
     fn div_rem_exact_division() {
         // (x^2 - 1) / (x - 1) = x + 1, remainder 0
@@ -452,6 +1523,51 @@ mod tests {
         assert_eq!(r.coefficients, vec![fe(1), fe(1)]);
     }
 
+    /// 係数バッファをその場で更新する前の `div_rem`（毎回 `Polynomial` を作って引く）。
+    fn div_rem_by_subtraction(
+        dividend: &Polynomial,
+        divisor: &Polynomial,
+    ) -> (Polynomial, Polynomial) {
+        let deg_d = divisor.degree().unwrap();
+        let zero = Polynomial::new(vec![FieldElement::zero(&divisor.coefficients[0].p)]);
+        let deg_s = match dividend.degree() {
+            None => return (zero.clone(), zero),
+            Some(d) if d < deg_d => return (zero, dividend.clone()),
+            Some(d) => d,
+        };
+        let mut quotient_coeffs = vec![zero.coefficients[0].clone(); deg_s - deg_d + 1];
+        let leading_d = divisor.leading_coefficient().unwrap();
+        let mut remainder = dividend.clone();
+        while let Some(deg_r) = remainder.degree() {
+            if deg_r < deg_d {
+                break;
+            }
+            let ratio = remainder.leading_coefficient().unwrap() / leading_d;
+            quotient_coeffs[deg_r - deg_d] = ratio.clone();
+            remainder = &remainder - &divisor.scale(&ratio).mul_by_x_power(deg_r - deg_d);
+        }
+        (Polynomial::new(quotient_coeffs), remainder)
+    }
+
+    #[test]
+    fn div_rem_matches_subtraction_based_division_for_random_inputs() {
+        let mut rng = ark_std::test_rng();
+        let p = BigInt::from(17);
+        for deg_s in 0..10 {
+            for deg_d in 0..6 {
+                let dividend = Polynomial::random(&mut rng, deg_s, &p);
+                let divisor = Polynomial::random(&mut rng, deg_d, &p);
+                let (q, r) = dividend.div_rem(&divisor);
+                let (q_ref, r_ref) = div_rem_by_subtraction(&dividend, &divisor);
+                // 係数列まで（正規化の形も含めて）一致する
+                let case = format!("({}) / ({})", dividend, divisor);
+                assert_eq!(q.coefficients, q_ref.coefficients, "{}", case);
+                assert_eq!(r.coefficients, r_ref.coefficients, "{}", case);
+                assert_eq!(&(&divisor * &q) + &r, dividend);
+            }
+        }
+    }
+
     #[test]
     #[should_panic(expected = "0多項式")]
     fn div_rem_by_zero_polynomial_panics() {
@@ -460,6 +1576,41 @@ mod tests {
         let _ = dividend.div_rem(&divisor);
     }
 
+    #[test]
+    fn evaluate_with_powers_matches_evaluate_for_shared_point() {
+        let x = fe(3);
+        let polys = [
+            poly(&[5]),
+            poly(&[1, 2]),
+            poly(&[6, 0, 4, 1]),
+            poly(&[0, 0, 0, 0, 0, 2]),
+            Polynomial::new(vec![]),
+        ];
+        // 最大次数 5 に合わせて 1 回だけ冪を作り、全多項式で使い回す
+        let xs = powers(&x, 6);
+        assert_eq!(xs, vec![fe(1), fe(3), fe(2), fe(6), fe(4), fe(5)]);
+        for p in &polys {
+            assert_eq!(p.evaluate_with_powers(&xs), p.evaluate(&x), "P = {}", p);
+        }
+        // 次数ちょうどの長さでもよい
+        assert_eq!(
+            polys[2].evaluate_with_powers(&xs[..4]),
+            polys[2].evaluate(&x)
+        );
+    }
+
+    #[test]
+    fn powers_of_zero_length_is_empty() {
+        assert!(powers(&fe(3), 0).is_empty());
+        assert_eq!(powers(&fe(0), 3), vec![fe(1), fe(0), fe(0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "冪の数が足りません")]
+    fn evaluate_with_powers_rejects_too_few_powers() {
+        let _ = poly(&[1, 2, 3]).evaluate_with_powers(&powers(&fe(2), 2));
+    }
+
     #[test]
     fn lagrange_interpolation_recovers_known_points() {
         // y_i = (i + 1)^2 mod 7 → [1, 4, 2]
@@ -478,6 +1629,258 @@ mod tests {
         assert_eq!(p.evaluate(&fe(99)), fe(5));
     }
 
+    #[test]
+    fn lagrange_interpolation_at_passes_through_given_points() {
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let points = [(f17(2), f17(5)), (f17(7), f17(3)), (f17(11), f17(9))];
+        let p = Polynomial::lagrange_interpolation_at(&points);
+        assert_eq!(p.degree(), Some(2));
+        for (x, y) in &points {
+            assert_eq!(&p.evaluate(x), y);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "x 座標が重複")]
+    fn lagrange_interpolation_at_rejects_duplicate_x() {
+        let _ = Polynomial::lagrange_interpolation_at(&[(fe(1), fe(2)), (fe(1), fe(3))]);
+    }
+
+    #[test]
+    fn lagrange_basis_is_kronecker_delta_on_nodes() {
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let xs = [f17(2), f17(7), f17(11), f17(0)];
+        let basis = Polynomial::lagrange_basis(&xs);
+        assert_eq!(basis.len(), xs.len());
+        for (i, l_i) in basis.iter().enumerate() {
+            assert_eq!(l_i.degree(), Some(xs.len() - 1));
+            for (j, xj) in xs.iter().enumerate() {
+                let expected = if i == j { f17(1) } else { f17(0) };
+                assert_eq!(l_i.evaluate(xj), expected, "L_{}(x_{})", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn from_lagrange_basis_matches_lagrange_interpolation() {
+        // 同じ基底を使い回して、列ごとの lagrange_interpolation と同じ多項式になる
+        let xs: Vec<FieldElement> = (0..5).map(fe).collect();
+        let basis = Polynomial::lagrange_basis(&xs);
+        for ys in [
+            [1, 4, 2, 0, 6],
+            [0, 0, 0, 0, 0],
+            [0, 3, 0, 0, 0],
+            [6, 6, 6, 6, 6],
+        ] {
+            let ys: Vec<FieldElement> = ys.iter().map(|&y| fe(y)).collect();
+            assert_eq!(
+                Polynomial::from_lagrange_basis(&basis, &ys),
+                Polynomial::lagrange_interpolation(&ys)
+            );
+        }
+    }
+
+    #[test]
+    fn lagrange_interpolation_sparse_matches_dense_path() {
+        // 40 点のうち 4 点だけ非零（うち 1 点は 2 項に分かれて足し合わされる）
+        let f101 = |v: i64| FieldElement::new(v, 101);
+        let n = 40;
+        let nonzero = [
+            (0, f101(5)),
+            (7, f101(99)),
+            (39, f101(1)),
+            (7, f101(3)),
+            (20, f101(0)),
+        ];
+        let mut dense = vec![f101(0); n];
+        for (i, y) in &nonzero {
+            dense[*i] = &dense[*i] + y;
+        }
+        let nodes: Vec<FieldElement> = (0..n as i64).map(f101).collect();
+        let z = Polynomial::from_roots(&nodes, &BigInt::from(101));
+        let sparse = Polynomial::lagrange_interpolation_sparse(&nonzero, &z);
+        assert_eq!(sparse, Polynomial::lagrange_interpolation(&dense));
+        assert_eq!(sparse.evaluate(&f101(7)), f101(1)); // 99 + 3 = 102 ≡ 1
+        assert_eq!(sparse.evaluate(&f101(20)), f101(0));
+    }
+
+    #[test]
+    fn lagrange_interpolation_sparse_of_empty_is_zero() {
+        let z =
+            |n: i64| Polynomial::from_roots(&(0..n).map(fe).collect::<Vec<_>>(), &BigInt::from(P));
+        assert!(Polynomial::lagrange_interpolation_sparse(&[], &z(10)).is_zero());
+        assert!(Polynomial::lagrange_interpolation_sparse(&[(3, fe(0))], &z(5)).is_zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "範囲外")]
+    fn lagrange_interpolation_sparse_rejects_out_of_range_index() {
+        let z = Polynomial::from_roots(&(0..5).map(fe).collect::<Vec<_>>(), &BigInt::from(P));
+        let _ = Polynomial::lagrange_interpolation_sparse(&[(5, fe(1))], &z);
+    }
+
+    #[test]
+    #[should_panic(expected = "x 座標が重複")]
+    fn lagrange_basis_rejects_duplicate_x() {
+        let _ = Polynomial::lagrange_basis(&[fe(3), fe(1), fe(3)]);
+    }
+
+    #[test]
+    fn from_roots_vanishes_at_each_root_and_is_monic() {
+        let roots = [fe(0), fe(1), fe(2), fe(5)];
+        let z = Polynomial::from_roots(&roots, &BigInt::from(P));
+        assert_eq!(z.degree(), Some(4));
+        assert_eq!(z.coefficients.last(), Some(&fe(1)));
+        for r in &roots {
+            assert!(z.evaluate(r).is_zero());
+        }
+        assert!(!z.evaluate(&fe(3)).is_zero());
+    }
+
+    #[test]
+    fn roots_of_product_of_linear_factors_are_exactly_those_points() {
+        let p17 = BigInt::from(17);
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        // (x − 1)(x − 2)(x − 3) over F_17
+        let z = Polynomial::from_roots(&[f17(1), f17(2), f17(3)], &p17);
+        assert_eq!(z.roots(&p17), vec![f17(1), f17(2), f17(3)]);
+        // 重根は 1 回だけ、x^2 + 1 は F_7 に根を持たない（7 ≡ 3 mod 4）
+        let double = Polynomial::from_roots(&[f17(4), f17(4)], &p17);
+        assert_eq!(double.roots(&p17), vec![f17(4)]);
+        assert!(poly(&[1, 0, 1]).roots(&BigInt::from(P)).is_empty());
+    }
+
+    #[test]
+    fn from_roots_empty_is_one() {
+        assert_eq!(Polynomial::from_roots(&[], &BigInt::from(P)), poly(&[1]));
+    }
+
+    #[test]
+    fn compose_substitutes_inner_polynomial() {
+        // (x^2 + 1).compose(2x) = 4x^2 + 1 in F_17
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let outer = Polynomial::new(vec![f17(1), f17(0), f17(1)]);
+        let inner = Polynomial::new(vec![f17(0), f17(2)]);
+        assert_eq!(
+            outer.compose(&inner),
+            Polynomial::new(vec![f17(1), f17(0), f17(4)])
+        );
+    }
+
+    #[test]
+    fn compose_with_constant_equals_evaluate() {
+        let outer = poly(&[3, 1, 4, 1]);
+        let composed = outer.compose(&poly(&[5]));
+        assert_eq!(composed.degree(), Some(0));
+        assert_eq!(composed.coefficients[0], outer.evaluate(&fe(5)));
+    }
+
+    #[test]
+    fn rem_returns_division_remainder() {
+        // (x^2 - 1) % (x - 1) = 0
+        let x_minus_one = poly(&[-1, 1]);
+        assert!((&poly(&[-1, 0, 1]) % &x_minus_one).is_zero());
+        // x^2 % (x - 1) = 1
+        assert_eq!(&poly(&[0, 0, 1]) % &x_minus_one, poly(&[1]));
+    }
+
+    #[test]
+    fn rem_assign_matches_rem() {
+        let divisor = poly(&[2, 1]);
+        let mut a = poly(&[3, 1, 4, 1]);
+        let expected = &a % &divisor;
+        a %= &divisor;
+        assert_eq!(a, expected);
+    }
+
+    #[test]
+    fn neg_negates_each_coefficient() {
+        let a = poly(&[1, 2, 3]);
+        assert_eq!(-&a, poly(&[6, 5, 4]));
+        assert_eq!(-&(-&a), a);
+        assert!((&a + &(-&a)).is_zero());
+    }
+
+    #[test]
+    fn add_scalar_bumps_only_constant_term() {
+        let a = poly(&[1, 2, 3]);
+        assert_eq!(a.add_scalar(&fe(4)), poly(&[5, 2, 3]));
+        assert_eq!(a.sub_scalar(&fe(4)), poly(&[4, 2, 3]));
+        assert_eq!(Polynomial::new(vec![]).add_scalar(&fe(3)), poly(&[3]));
+    }
+
+    #[test]
+    fn mul_by_x_power_shifts_coefficients() {
+        // (3 + x) · x^2 = 3x^2 + x^3
+        assert_eq!(poly(&[3, 1]).mul_by_x_power(2), poly(&[0, 0, 3, 1]));
+        assert_eq!(poly(&[3, 1]).mul_by_x_power(0), poly(&[3, 1]));
+        assert!(poly(&[0]).mul_by_x_power(3).is_zero());
+    }
+
+    #[test]
+    fn evaluations_round_trip_over_f17() {
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let p = Polynomial::new(vec![f17(3), f17(0), f17(11), f17(5)]);
+        // domain.len() = 5 > degree = 3
+        let domain: Vec<FieldElement> = [1, 4, 6, 9, 13].iter().map(|&v| f17(v)).collect();
+        let evals = p.to_evaluations(&domain);
+        assert_eq!(evals.len(), domain.len());
+        assert_eq!(Polynomial::from_evaluations(&evals, &domain), p);
+    }
+
+    #[test]
+    #[should_panic(expected = "個数が一致しません")]
+    fn from_evaluations_rejects_length_mismatch() {
+        let _ = Polynomial::from_evaluations(&[fe(1)], &[fe(0), fe(1)]);
+    }
+
+    #[test]
+    fn make_monic_keeps_roots() {
+        // 3x^2 + 6 over F_17: 3(x^2 + 2) なので x^2 = -2 = 15 の根 (x = 7, 10)
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let p = Polynomial::new(vec![f17(6), f17(0), f17(3)]);
+        assert!(!p.is_monic());
+        let m = p.make_monic();
+        assert!(m.is_monic());
+        assert_eq!(m, Polynomial::new(vec![f17(2), f17(0), f17(1)]));
+        for x in 0..17 {
+            assert_eq!(p.evaluate(&f17(x)).is_zero(), m.evaluate(&f17(x)).is_zero());
+        }
+        assert!(m.evaluate(&f17(7)).is_zero());
+    }
+
+    #[test]
+    fn make_monic_of_zero_is_zero() {
+        assert!(poly(&[0]).make_monic().is_zero());
+        assert!(!poly(&[0]).is_monic());
+    }
+
+    #[test]
+    fn random_has_exact_degree() {
+        // 小さな体 F_7 だと最高次係数が 0 になる確率も高いので引き直しが効く
+        let p = BigInt::from(P);
+        let mut rng = ark_std::test_rng();
+        for degree in 0..20 {
+            let r = Polynomial::random(&mut rng, degree, &p);
+            assert_eq!(r.degree(), Some(degree));
+        }
+    }
+
+    #[test]
+    fn eq_ignores_trailing_zero_coefficients() {
+        let untrimmed = Polynomial {
+            coefficients: vec![fe(1), fe(0)],
+        };
+        assert_eq!(untrimmed, Polynomial::new(vec![fe(1)]));
+        assert_ne!(untrimmed, poly(&[1, 1]));
+        // 0 多項式の表現（空 / [0] / [0, 0]）はすべて等しい
+        let zeros = Polynomial {
+            coefficients: vec![fe(0), fe(0)],
+        };
+        assert_eq!(zeros, poly(&[0]));
+        assert_eq!(Polynomial::new(vec![]), poly(&[0]));
+    }
+
     #[test]
     fn scale_multiplies_each_coefficient() {
         // (1 + 2x).scale(3) = 3 + 6x
@@ -486,6 +1889,119 @@ mod tests {
         assert_eq!(scaled.coefficients, vec![fe(3), fe(6)]);
     }
 
+    #[test]
+    fn sub_from_empty_polynomial() {
+        // [] - (1 + 2x) = -1 - 2x ≡ 6 + 5x (mod 7)
+        let empty = Polynomial::new(vec![]);
+        assert_eq!((&empty - &poly(&[1, 2])).coefficients, vec![fe(6), fe(5)]);
+    }
+
+    #[test]
+    fn gcd_of_polynomials_sharing_one_root() {
+        // F_17: gcd((x-1)(x-2), (x-1)(x-3)) == x - 1
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let linear = |r: i64| Polynomial::new(vec![f17(-r), f17(1)]);
+        let a = &linear(1) * &linear(2);
+        let b = &linear(1) * &linear(3);
+        assert_eq!(a.gcd(&b), linear(1));
+        // スカラー倍しても結果はモニックで変わらない
+        assert_eq!(a.scale(&f17(5)).gcd(&b), linear(1));
+    }
+
+    #[test]
+    fn gcd_with_zero_polynomial() {
+        // gcd(2 + 2x, 0) = 1 + x（モニック化）
+        let a = poly(&[2, 2]);
+        assert_eq!(a.gcd(&poly(&[0])), poly(&[1, 1]));
+        assert_eq!(poly(&[0]).gcd(&a), poly(&[1, 1]));
+        assert!(poly(&[0]).gcd(&poly(&[0])).is_zero());
+    }
+
+    #[test]
+    fn mul_ntt_matches_schoolbook_for_random_polynomials() {
+        // p = 257: p - 1 = 2^8 なので長さ 256 までの NTT が使える
+        let p = BigInt::from(257);
+        let mut rng = ark_std::test_rng();
+        let mut random_poly = |deg: usize| {
+            Polynomial::new(
+                (0..=deg)
+                    .map(|_| FieldElement::random(&mut rng, &p))
+                    .collect(),
+            )
+        };
+        for _ in 0..10 {
+            let a = random_poly(8);
+            let b = random_poly(8);
+            assert_eq!(a.mul_ntt(&b), &a * &b);
+        }
+    }
+
+    #[test]
+    fn mul_ntt_falls_back_without_root_of_unity() {
+        // p = 7: p - 1 = 6 = 3·2 なので長さ 3 以上の積は筆算にフォールバック
+        let a = poly(&[1, 2, 3]);
+        let b = poly(&[4, 5]);
+        assert_eq!(a.mul_ntt(&b), &a * &b);
+        // 定数どうし（NTT 長 1）
+        assert_eq!(poly(&[3]).mul_ntt(&poly(&[5])), poly(&[1]));
+    }
+
+    #[test]
+    fn mul_ntt_over_f2_terminates_and_matches_schoolbook() {
+        // p = 2 には平方非剰余がないので、冪根の探索に入ると止まらない
+        let f2 = |coeffs: &[i64]| {
+            Polynomial::new(coeffs.iter().map(|&c| FieldElement::new(c, 2)).collect())
+        };
+        assert_eq!(
+            two_adic_root_of_unity(1, &BigInt::from(2)),
+            Some(FieldElement::new(1, 2))
+        );
+        assert_eq!(two_adic_root_of_unity(2, &BigInt::from(2)), None);
+        // (1 + x)(1 + x) = 1 + x^2 over F_2
+        assert_eq!(f2(&[1, 1]).mul_ntt(&f2(&[1, 1])), f2(&[1, 0, 1]));
+        assert_eq!(f2(&[1]).mul_ntt(&f2(&[1])), f2(&[1]));
+    }
+
+    #[test]
+    fn mul_karatsuba_matches_schoolbook_for_random_polynomials() {
+        // 閾値を超える長さ・長さ違い・閾値以下の組み合わせを試す
+        let p = BigInt::from(17);
+        let mut rng = ark_std::test_rng();
+        let mut random_poly = |deg: usize| {
+            Polynomial::new(
+                (0..=deg)
+                    .map(|_| FieldElement::random(&mut rng, &p))
+                    .collect(),
+            )
+        };
+        for (da, db) in [(40, 40), (50, 17), (3, 70), (5, 5), (33, 0)] {
+            let a = random_poly(da);
+            let b = random_poly(db);
+            assert_eq!(a.mul_karatsuba(&b), &a * &b, "deg {} x {}", da, db);
+        }
+    }
+
+    #[test]
+    fn derivative_over_f17() {
+        // d/dx(3x^2 + 5x + 7) = 6x + 5
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let p = Polynomial::new(vec![f17(7), f17(5), f17(3)]);
+        assert_eq!(p.derivative().coefficients, vec![f17(5), f17(6)]);
+    }
+
+    #[test]
+    fn derivative_of_constant_is_zero() {
+        assert!(poly(&[5]).derivative().is_zero());
+        assert!(poly(&[0]).derivative().is_zero());
+    }
+
+    #[test]
+    fn derivative_drops_terms_with_index_multiple_of_p() {
+        // F_7: d/dx(x^7) = 7x^6 ≡ 0
+        let p = poly(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        assert!(p.derivative().is_zero());
+    }
+
     #[test]
     fn display_formats_polynomial() {
         // 1 + 0x + 2x^2 → "2x^2 + 1"
@@ -493,6 +2009,29 @@ mod tests {
         assert_eq!(format!("{}", p), "2x^2 + 1");
     }
 
+    #[test]
+    fn display_omits_unit_coefficients() {
+        // 1 + x + x^3 → "x^3 + x + 1"（定数項の 1 は残す）
+        assert_eq!(format!("{}", poly(&[1, 1, 0, 1])), "x^3 + x + 1");
+    }
+
+    #[test]
+    fn display_x_squared_minus_one_over_f17() {
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let p = Polynomial::new(vec![f17(-1), f17(0), f17(1)]);
+        assert_eq!(format!("{}", p), "x^2 + 16");
+        assert_eq!(p.to_signed_string(), "x^2 - 1");
+    }
+
+    #[test]
+    fn signed_string_handles_negative_leading_term() {
+        // -x^2 + 3x - 2 in F_7 → 6x^2 + 3x + 5
+        let p = poly(&[-2, 3, -1]);
+        assert_eq!(format!("{}", p), "6x^2 + 3x + 5");
+        assert_eq!(p.to_signed_string(), "-x^2 + 3x - 2");
+        assert_eq!(poly(&[0]).to_signed_string(), "0");
+    }
+
     #[test]
     fn display_zero_polynomial() {
         assert_eq!(format!("{}", poly(&[0])), "0");
@@ -504,6 +2043,21 @@ mod tests {
         assert!(poly(&[0, 0, 0]).is_zero()); // new() にて [0] に正規化される
     }
     
+    #[test]
+    fn is_zero_treats_empty_polynomial_as_zero() {
+        assert!(Polynomial::new(vec![]).is_zero());
+    }
+
+    #[test]
+    fn leading_coefficient_cases() {
+        assert_eq!(Polynomial::new(vec![]).leading_coefficient(), None);
+        assert_eq!(poly(&[0]).leading_coefficient(), None);
+        // [0, 0, 5] は new() で末尾が残り 5x^2
+        assert_eq!(poly(&[0, 0, 5]).leading_coefficient(), Some(&fe(5)));
+        // [3, 5, 0, 0] は new() で 3 + 5x に切り詰められる
+        assert_eq!(poly(&[3, 5, 0, 0]).leading_coefficient(), Some(&fe(5)));
+    }
+
     #[test]
     fn is_zero_returns_false_for_nonzero_polynomial() {
         assert!(!poly(&[1]).is_zero());     // 定数 1
@@ -5,14 +5,72 @@
 //!
 //! ## 主要型
 //! - [`Groth16Proof`]: Groth16 の証明 `(A, B, C)`（ランダム化込み）
+//! - [`Prover`]: QAP と witness から `h(x)` を求める前段（自作体の上での多項式計算）
+//! - [`QapProof`]: [`Prover::prove`] の結果（合成多項式 `A, B, C` と商 `h`）
 //!
 //! ## 主要関数
 //! - [`prove`]: Groth16 の証明生成（ランダム r, s 込み）
 
 use ark_bn254::{Fr, G1Projective, G2Projective};
+use num_bigint::BigInt;
 
+use crate::field::FieldElement;
+use crate::polynomial::Polynomial;
+use crate::qap::Qap;
 use crate::setup::{ProvingKey, QapFr};
 
+/// QAP の段階での証明: witness で合成した多項式と商 `h(x)`。
+///
+/// `A(x)·B(x) − C(x) = h(x)·Z(x)` を満たす。`h` の係数を Fr に変換して
+/// [`prove`] に渡すと Groth16 の証明になる。
+#[derive(Debug, Clone)]
+pub struct QapProof {
+    /// `A(x) = Σ_i w_i · a_i(x)`
+    pub a: Polynomial,
+    /// `B(x) = Σ_i w_i · b_i(x)`
+    pub b: Polynomial,
+    /// `C(x) = Σ_i w_i · c_i(x)`
+    pub c: Polynomial,
+    /// `h(x) = (A(x)·B(x) − C(x)) / Z(x)`
+    pub h: Polynomial,
+}
+
+/// QAP と消失多項式 `Z(x)` を持ち、witness から [`QapProof`] を作る。
+///
+/// ペアリングを使う前の、自作 [`FieldElement`] / [`Polynomial`] 上の計算を受け持つ。
+#[derive(Debug, Clone)]
+pub struct Prover {
+    pub qap: Qap,
+    /// 消失多項式 `Z(x) = (x − 0)(x − 1)...(x − (n − 1))`
+    pub z: Polynomial,
+}
+
+impl Prover {
+    /// QAP と制約数 `num_constraints`（= 補間点の数）から Prover を作る。
+    pub fn new(qap: Qap, num_constraints: usize, p: &BigInt) -> Self {
+        let z = qap.target_polynomial(num_constraints, p);
+        Prover { qap, z }
+    }
+
+    /// `P(x) = A(x)·B(x) − C(x)` を `Z(x)` で割り、[`QapProof`] を返す。
+    ///
+    /// witness が制約を満たさず `P(x)` が割り切れなければ panic する。
+    /// 事前に確かめるには [`verify_divisibility`](Self::verify_divisibility) を使う。
+    pub fn prove(&self, witness: &[FieldElement]) -> QapProof {
+        let (a, b, c) = self.qap.compose(witness);
+        let p_poly = &(&a * &b) - &c;
+        let (h, remainder) = p_poly.div_rem(&self.z);
+        assert!(remainder.is_zero(), "P(x) is not divisible by Z(x)");
+        QapProof { a, b, c, h }
+    }
+
+    /// witness で合成した `P(x) = A(x)·B(x) − C(x)` が `Z(x)` で割り切れるかを返す。
+    pub fn verify_divisibility(&self, witness: &[FieldElement]) -> bool {
+        let (a, b, c) = self.qap.compose(witness);
+        (&(&(&a * &b) - &c) % &self.z).is_zero()
+    }
+}
+
 /// 本式 Groth16 の証明（楕円曲線上の 3 点）。
 ///
 /// 各点 `(A, B, C)` に α/β/γ/δ とランダム値 r, s が織り込まれており、
@@ -119,14 +177,10 @@ pub fn prove(
 mod tests {
     use super::*;
     use crate::adapter::{field_element_to_fr, polynomial_to_fr_vec, polys_to_fr_vecs};
-    use crate::field::FieldElement;
-    use crate::polynomial::Polynomial;
-    use crate::qap::Qap;
-    use crate::r1cs::{ConstraintSystem, LinearCombination, CS_ONE};
+    use crate::r1cs::x3_plus5_circuit;
     use crate::setup::{generate_groth16_keys, ToxicWaste, VerifyingKey};
     use crate::verifier::verify;
     use ark_ec::PrimeGroup; // generator() のため
-    use num_bigint::BigInt;
 
     #[test]
     fn test_evaluate_on_g1_linear() {
@@ -166,6 +220,43 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    // === QAP 段階の Prover ===
+
+    /// y = x³ + 5（x = 3, y = 32）の Prover と witness。
+    fn build_x3_plus5_prover() -> (Prover, Vec<FieldElement>) {
+        let p = BigInt::from(97);
+        let cs = x3_plus5_circuit(&p);
+        let prover = Prover::new(Qap::from_r1cs(&cs), cs.num_constraints(), &p);
+        (prover, cs.generate_witness())
+    }
+
+    #[test]
+    fn test_prover_demo_witness_is_divisible() {
+        let (prover, witness) = build_x3_plus5_prover();
+        assert!(prover.verify_divisibility(&witness));
+
+        // A·B − C = h·Z が多項式として成り立つ
+        let proof = prover.prove(&witness);
+        let lhs = &(&proof.a * &proof.b) - &proof.c;
+        assert_eq!(lhs, &proof.h * &prover.z);
+    }
+
+    #[test]
+    fn test_prover_tampered_witness_is_not_divisible() {
+        let (prover, mut witness) = build_x3_plus5_prover();
+        // y = 32 を 33 に改ざん
+        witness[1] = &witness[1] + &FieldElement::one(&witness[1].p);
+        assert!(!prover.verify_divisibility(&witness));
+    }
+
+    #[test]
+    #[should_panic(expected = "P(x) is not divisible by Z(x)")]
+    fn test_prover_prove_panics_on_tampered_witness() {
+        let (prover, mut witness) = build_x3_plus5_prover();
+        witness[1] = &witness[1] + &FieldElement::one(&witness[1].p);
+        prover.prove(&witness);
+    }
+
     // === 本式 Groth16 の E2E / soundness / ZK テスト ===
 
     /// x³ + 5 = y（y を public 出力）の本式 Groth16 一式。x = 3, y = 32。
@@ -184,30 +275,11 @@ mod tests {
             10,
         )
         .unwrap();
-        let fe = |v: u64| FieldElement::new(BigInt::from(v), p.clone());
-        let one = fe(1);
-        let zero = fe(0);
 
         // === R1CS（public: CS_ONE, y / private: x, v1, v2）===
-        let mut cs = ConstraintSystem::new();
-        cs.init_one(one.clone());
-        let y = cs.alloc_public_input(); // public 出力を前方固め
-        cs.assign(y, fe(32)); // 3³ + 5 = 32
-        let x = cs.alloc_variable(); // private 入力
-        cs.assign(x, fe(3));
-        let v1 = cs.mul(x, x); // 9
-        let v2 = cs.mul(v1, x); // 27
-        // 制約: (v2 + 5)·1 = y
-        let mut lc_a = LinearCombination::new();
-        lc_a.add_term(v2, one.clone());
-        lc_a.add_term(CS_ONE, fe(5));
-        let mut lc_b = LinearCombination::new();
-        lc_b.add_term(CS_ONE, one.clone());
-        let mut lc_c = LinearCombination::new();
-        lc_c.add_term(y, one.clone());
-        cs.enforce(lc_a, lc_b, lc_c);
-
-        let num_constraints = cs.constraints.len(); // 3
+        let cs = x3_plus5_circuit(&p);
+
+        let num_constraints = cs.num_constraints(); // 3
         let num_public = cs.num_public_variables; // 2 (CS_ONE + y)
 
         // === QAP → Fr ===
@@ -223,26 +295,8 @@ mod tests {
         let witness: Vec<Fr> = witness_fe.iter().map(field_element_to_fr).collect();
 
         // === h(x) = (A·B − C) / Z ===
-        let mut a_poly = Polynomial::new(vec![zero.clone()]);
-        let mut b_poly = Polynomial::new(vec![zero.clone()]);
-        let mut c_poly = Polynomial::new(vec![zero.clone()]);
-        for (i, w_val) in witness_fe.iter().enumerate() {
-            a_poly = &a_poly + &qap.a_polys[i].scale(w_val);
-            b_poly = &b_poly + &qap.b_polys[i].scale(w_val);
-            c_poly = &c_poly + &qap.c_polys[i].scale(w_val);
-        }
-        let ab = &a_poly * &b_poly;
-        let minus_one = &zero - &one;
-        let neg_c = c_poly.scale(&minus_one);
-        let p_poly = &ab + &neg_c;
-        let mut z_poly = Polynomial::new(vec![one.clone()]);
-        for i in 0..num_constraints {
-            let neg_i = &zero - &fe(i as u64);
-            z_poly = &z_poly * &Polynomial::new(vec![neg_i, one.clone()]);
-        }
-        let (h_poly, remainder) = p_poly.div_rem(&z_poly);
-        assert!(remainder.is_zero(), "P(x) が Z(x) で割り切れません");
-        let h_coeffs = polynomial_to_fr_vec(&h_poly);
+        let qap_proof = Prover::new(qap, num_constraints, &p).prove(&witness_fe);
+        let h_coeffs = polynomial_to_fr_vec(&qap_proof.h);
 
         // === 鍵生成 ===
         let toxic = ToxicWaste {
@@ -255,7 +309,11 @@ mod tests {
         let (pk, vk) = generate_groth16_keys(&qap_fr, num_constraints, num_public, &toxic);
 
         // public_inputs = a_1..a_ℓ = [y]
-        let public_inputs = vec![witness[y.0]];
+        let public_inputs: Vec<Fr> = cs
+            .public_inputs(&witness_fe)
+            .iter()
+            .map(field_element_to_fr)
+            .collect();
 
         Groth16Fixture {
             pk,
@@ -6,14 +6,21 @@
 //! ## 主要型
 //! - [`Qap`]: 各変数 i に対する `u_i(x), v_i(x), w_i(x)` の組
 //!   （`a_polys`, `b_polys`, `c_polys`）
+//! - [`QapPolys`]: [`Qap::split_public_private`] が返す、変数範囲ごとの多項式列の参照
 //!
 //! ## 補間点
 //! 制約 i 番目を `x = i` の点に対応させる（補間点列は 0, 1, ..., n-1）。
+//! [`Qap::from_r1cs_on_domain`] では、代わりに 1 の n 乗根の冪 `ω^0, ω^1, ..., ω^{n-1}`
+//! を補間点に使う（FFT 向き）。このとき消失多項式は `x^n − 1` になる。
+//!
+//! ## 保存
+//! `serde` feature 有効時は `Qap::save` / `Qap::load` で JSON ファイルに
+//! 書き出し・読み込みできる（構築コストの高い QAP を実行間で使い回すため）。
 
 use num_bigint::BigInt;
 
-use crate::field::FieldElement;
-use crate::polynomial::Polynomial;
+use crate::field::{FieldElement, IntoModulus};
+use crate::polynomial::{powers, Polynomial};
 use crate::r1cs::ConstraintSystem;
 
 /// R1CS から変換した Quadratic Arithmetic Program (QAP)。
@@ -35,6 +42,11 @@ pub struct Qap {
     pub b_polys: Vec<Polynomial>,
     /// C 行列由来の多項式列。インデックス規約は `a_polys` と同じ。
     pub c_polys: Vec<Polynomial>,
+    /// public 変数の数（[`CS_ONE`](crate::r1cs::CS_ONE) を含む）。
+    /// 先頭 `num_public` 個の多項式が public、残りが private。
+    pub num_public: usize,
+    /// 補間点列。`None` なら整数点 `0, 1, ..., n-1`、`Some` なら 1 の n 乗根の冪。
+    pub domain: Option<Vec<FieldElement>>,
 }
 
 impl Qap {
@@ -43,29 +55,78 @@ impl Qap {
     /// 各 (行列, 変数) ペアの列を「制約 index → 係数」の点列とみなし、
     /// 補間点列 `0, 1, ..., num_constraints − 1` でラグランジュ補間する。
     ///
-    /// 計算量は `O(num_vars · num_constraints^2)`
-    /// （変数ごとに `O(num_constraints^2)` の補間を 3 行列分）。
+    /// 補間点は全列で共通なので、ラグランジュ基底 `L_j(x)` は
+    /// [`Polynomial::lagrange_basis`] で 1 度だけ作り（`O(num_constraints^2)`）、
+    /// 各列は `Σ_j y_j · L_j(x)` として組み立てる（列ごとに `O(非零の数 · num_constraints)`）。
     /// 制約系は `init_one` 済みであることが前提（法 `p` を取り出すため
     /// `assignments[0]` を参照する）。
+    ///
+    /// 制約が 1 つもない場合は、全変数の多項式を零多項式 `0` とする
+    /// （消失多項式は `1` なので、どの witness も自明に充足する）。
     pub fn from_r1cs(cs: &ConstraintSystem) -> Self {
-        let num_vars = cs.next_var_index;
-        let num_constraints = cs.constraints.len();
-        let p = cs
+        Self::build(cs, None)
+    }
+
+    /// 乗法部分群 `domain = [ω^0, ω^1, ..., ω^{n-1}]`（ω は 1 の原始 n 乗根）を
+    /// 補間点列として QAP を構築する。
+    ///
+    /// 制約 j 番目を `x = ω^j` に対応させ、`domain` 上のラグランジュ基底で各列を多項式化する。
+    /// 制約数が `n` に満たない分は全係数 0 の行として扱う（`0 · 0 = 0` で常に充足）。
+    /// 消失多項式は `x^n − 1` になる（[`target_polynomial`](Self::target_polynomial)）。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    ///
+    /// # Panics
+    /// `domain.len() < cs.num_constraints()` のとき、または `domain` が
+    /// `[ω^0, ω^1, ..., ω^{n-1}]`（ω は 1 の原始 n 乗根）の形でないとき panic。
+    #[allow(dead_code)]
+    pub fn from_r1cs_on_domain(cs: &ConstraintSystem, domain: &[FieldElement]) -> Self {
+        assert!(
+            domain.len() >= cs.num_constraints(),
+            "評価点の数が制約数より少なくなっています"
+        );
+        assert!(
+            is_subgroup_domain(domain),
+            "domain が 1 の原始 n 乗根の冪 [ω^0, ..., ω^(n-1)] になっていません"
+        );
+        Self::build(cs, Some(domain.to_vec()))
+    }
+
+    /// [`from_r1cs`](Self::from_r1cs) / [`from_r1cs_on_domain`](Self::from_r1cs_on_domain) の本体。
+    fn build(cs: &ConstraintSystem, domain: Option<Vec<FieldElement>>) -> Self {
+        let num_vars = cs.num_variables();
+        let num_points = domain.as_ref().map_or(cs.num_constraints(), Vec::len);
+        // 法は CS_ONE の値から借用し、補間点・零多項式の元はすべてこれを共有する
+        let p = &cs
             .assignments
             .first()
             .expect("CS未初期化")
             .as_ref()
             .unwrap()
-            .p
-            .clone();
+            .p;
+
+        // 補間点は全列で共通なので、基底 L_j(x) は 3 行列 × 全変数で使い回す
+        let nodes: Vec<FieldElement> = match &domain {
+            None => (0..num_points)
+                .map(|j| FieldElement::from_u64(j as u64, p))
+                .collect(),
+            Some(xs) => xs.clone(),
+        };
+        let basis = Polynomial::lagrange_basis(&nodes);
 
         // 指定行列の各変数列を Lagrange 補間で多項式化する
         let interpolate_column = |matrix: Matrix| -> Vec<Polynomial> {
             (0..num_vars)
                 .map(|i| {
-                    let points = extract_column(cs, i, matrix);
-                    let dense = to_dense_vector(points, num_constraints, &p);
-                    Polynomial::lagrange_interpolation(&dense)
+                    if num_points == 0 {
+                        // 制約なし: 補間点がないので各列は零多項式とする
+                        return Polynomial::new(vec![FieldElement::zero(p)]);
+                    }
+                    // 0 埋めした密ベクトルは作らず、非零の行の基底だけを足し込む
+                    let zero = Polynomial::new(vec![FieldElement::zero(p)]);
+                    extract_column(cs, i, matrix)
+                        .iter()
+                        .fold(zero, |acc, (row, val)| &acc + &basis[*row].scale(val))
                 })
                 .collect()
         };
@@ -74,29 +135,126 @@ impl Qap {
             a_polys: interpolate_column(Matrix::A),
             b_polys: interpolate_column(Matrix::B),
             c_polys: interpolate_column(Matrix::C),
+            num_public: cs.num_public_variables,
+            domain,
         }
     }
-}
 
-/// スパースな点列 `[(row, value), ...]` を、長さ `num_constraints` の
-/// 密ベクトルに展開する（欠けた行は 0 で埋める）。
-///
-/// `Polynomial::lagrange_interpolation` が補間点 `x = 0, 1, ..., n-1` の
-/// `y` 値列を要求するための前処理。
-fn to_dense_vector(
-    sparse_points: Vec<(usize, FieldElement)>,
-    num_constraints: usize,
-    p: &BigInt,
-) -> Vec<FieldElement> {
-    let zero = FieldElement::new(0, p.clone());
-    let mut dense = vec![zero; num_constraints];
-
-    for (row_idx, val) in sparse_points {
-        if row_idx < num_constraints {
-            dense[row_idx] = val;
+    /// 多項式列を public 変数（先頭 `num_public` 個）と private 変数に分けて返す。
+    ///
+    /// 検証者は public 側だけを公開入力と組み合わせ、private 側は証明者が扱う。
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn split_public_private(&self) -> (QapPolys<'_>, QapPolys<'_>) {
+        let k = self.num_public;
+        let public = QapPolys {
+            a_polys: &self.a_polys[..k],
+            b_polys: &self.b_polys[..k],
+            c_polys: &self.c_polys[..k],
+        };
+        let private = QapPolys {
+            a_polys: &self.a_polys[k..],
+            b_polys: &self.b_polys[k..],
+            c_polys: &self.c_polys[k..],
+        };
+        (public, private)
+    }
+
+    /// 消失多項式（target polynomial）`Z(x) = (x − 0)(x − 1)...(x − (n − 1))` を返す。
+    ///
+    /// `n = num_constraints` は補間点の数。各補間点で 0 になり、次数は `n`。
+    /// `num_constraints == 0` のときは定数 `1`（空積）。
+    ///
+    /// [`from_r1cs_on_domain`](Self::from_r1cs_on_domain) で構築した QAP では、
+    /// `x^n − 1`（`n = domain.len()`）を返す。制約数に満たない分の補間点も根に含む。
+    ///
+    /// # Panics
+    /// domain 付きの QAP で `num_constraints > domain.len()` のとき panic。
+    pub fn target_polynomial(&self, num_constraints: usize, p: &BigInt) -> Polynomial {
+        if let Some(domain) = &self.domain {
+            assert!(
+                num_constraints <= domain.len(),
+                "制約数 {} が domain のサイズ {} を超えています",
+                num_constraints,
+                domain.len()
+            );
+            // Π_j (x − ω^j) = x^n − 1
+            let one = FieldElement::one(p);
+            return Polynomial::new(vec![one.clone()])
+                .mul_by_x_power(domain.len())
+                .sub_scalar(&one);
         }
+        // 法は 1 度だけ包み、各根で共有する
+        let p = p.into_modulus();
+        let roots: Vec<FieldElement> = (0..num_constraints)
+            .map(|i| FieldElement::new(i, &p))
+            .collect();
+        Polynomial::from_roots(&roots, &p)
     }
-    dense
+
+    /// witness で `A(x), B(x), C(x)` を合成する。
+    ///
+    /// `A(x) = Σ_i w_i · a_i(x)`（`B`, `C` も同様）。
+    /// witness は `CS_ONE` を含む全変数の割り当て（長さ = 変数の数）。
+    pub fn compose(&self, witness: &[FieldElement]) -> (Polynomial, Polynomial, Polynomial) {
+        let zero = Polynomial::new(vec![FieldElement::zero(&witness[0].p)]);
+        let (mut a, mut b, mut c) = (zero.clone(), zero.clone(), zero);
+        for (i, w) in witness.iter().enumerate() {
+            a = &a + &self.a_polys[i].scale(w);
+            b = &b + &self.b_polys[i].scale(w);
+            c = &c + &self.c_polys[i].scale(w);
+        }
+        (a, b, c)
+    }
+
+    /// 各変数の多項式を点 `tau` で評価し、`(a_i(τ), b_i(τ), c_i(τ))` の列を返す。
+    ///
+    /// setup が秘密点 τ での値に「コミット」するための前段。witness `w` との内積
+    /// `Σ w_i · a_i(τ)` は合成多項式の値 `A(τ)` に一致する（`B`, `C` も同様）。
+    pub fn evaluate_all_at(
+        &self,
+        tau: &FieldElement,
+    ) -> (Vec<FieldElement>, Vec<FieldElement>, Vec<FieldElement>) {
+        // τ の冪は全多項式で共通なので 1 度だけ作る
+        let max_len = [&self.a_polys, &self.b_polys, &self.c_polys]
+            .iter()
+            .flat_map(|polys| polys.iter().map(|poly| poly.coefficients().len()))
+            .max()
+            .unwrap_or(0);
+        let tau_powers = powers(tau, max_len.max(1));
+        let eval = |polys: &[Polynomial]| {
+            polys
+                .iter()
+                .map(|poly| poly.evaluate_with_powers(&tau_powers))
+                .collect()
+        };
+        (
+            eval(&self.a_polys),
+            eval(&self.b_polys),
+            eval(&self.c_polys),
+        )
+    }
+
+    /// witness が QAP を満たすか（`P(x) = A(x)·B(x) − C(x)` が `Z(x)` で割り切れるか）を返す。
+    ///
+    /// 合成 → `P(x)` → `Z(x) | P(x)` の確認までを一度に行う。
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn is_satisfied(&self, witness: &[FieldElement], num_constraints: usize) -> bool {
+        let (a, b, c) = self.compose(witness);
+        let z = self.target_polynomial(num_constraints, &witness[0].p);
+        (&(&(&a * &b) - &c) % &z).is_zero()
+    }
+}
+
+/// [`Qap`] の多項式列のうち、連続した変数範囲の参照。
+/// 現在は unit test からのみ読まれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct QapPolys<'a> {
+    pub a_polys: &'a [Polynomial],
+    pub b_polys: &'a [Polynomial],
+    pub c_polys: &'a [Polynomial],
 }
 
 /// `extract_column` が見る行列を指定するセレクタ。
@@ -110,7 +268,7 @@ enum Matrix {
 /// 指定行列の指定変数列に出てくる係数を、`(制約 index, 係数)` のスパース列で返す。
 ///
 /// 同一制約内に同じ変数が複数項として登録されている場合、それぞれ別エントリで返す
-/// （`LinearCombination::add_term` がマージしない仕様に対応）。
+/// （`LinearCombination::add_term` がマージしない仕様に対応）。補間側で足し合わせる。
 fn extract_column(
     cs: &ConstraintSystem,
     var_idx: usize,
@@ -131,15 +289,119 @@ fn extract_column(
                 points.push((i, coeff.clone()));
             }
         }
-        // エントリなければ 0 だが、スパース表現として詰めない（to_dense_vector で 0 埋め）
+        // エントリなければ 0 だが、スパース表現として詰めない（補間側で 0 とみなす）
     }
     points
 }
 
+/// `domain` が `[ω^0, ω^1, ..., ω^{n-1}]`（ω は 1 の原始 n 乗根）の形かを判定する。
+///
+/// 先頭が 1、隣り合う点の比がすべて `ω = domain[1]` で、`ω^n = 1` かつ
+/// `ω^j ≠ 1 (0 < j < n)` なら、`domain` は位数 `n` の乗法部分群そのものになる。
+fn is_subgroup_domain(domain: &[FieldElement]) -> bool {
+    let Some(first) = domain.first() else {
+        return false;
+    };
+    if !first.is_one() {
+        return false;
+    }
+    let omega = domain.get(1).unwrap_or(first);
+    domain
+        .windows(2)
+        .all(|w| w[1] == &w[0] * omega && !w[1].is_one())
+        && (&domain[domain.len() - 1] * omega).is_one()
+}
+
+#[cfg(feature = "serde")]
+impl Qap {
+    /// QAP を JSON として `path` に書き出す。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string(self)?;
+        std::fs::write(path, json)
+    }
+
+    /// [`save`](Self::save) で書き出した JSON を `path` から読み込む。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// 全フィールドを同名のキーで持つ構造体形式でシリアライズする。
+///
+/// 多項式の係数は [`FieldElement`] の形式（10 進文字列）になる。
+#[cfg(feature = "serde")]
+impl serde::Serialize for Qap {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut st = serializer.serialize_struct("Qap", 5)?;
+        st.serialize_field("a_polys", &self.a_polys)?;
+        st.serialize_field("b_polys", &self.b_polys)?;
+        st.serialize_field("c_polys", &self.c_polys)?;
+        st.serialize_field("num_public", &self.num_public)?;
+        st.serialize_field("domain", &self.domain)?;
+        st.end()
+    }
+}
+
+/// [`Serialize`](serde::Serialize) の逆。
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Qap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{self, MapAccess, Visitor};
+        use std::fmt;
+
+        const FIELDS: &[&str] = &["a_polys", "b_polys", "c_polys", "num_public", "domain"];
+
+        struct QapVisitor;
+
+        impl<'de> Visitor<'de> for QapVisitor {
+            type Value = Qap;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a Qap { a_polys, b_polys, c_polys, num_public, domain }")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Qap, A::Error> {
+                let mut a_polys: Option<Vec<Polynomial>> = None;
+                let mut b_polys: Option<Vec<Polynomial>> = None;
+                let mut c_polys: Option<Vec<Polynomial>> = None;
+                let mut num_public: Option<usize> = None;
+                let mut domain: Option<Option<Vec<FieldElement>>> = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "a_polys" => a_polys = Some(map.next_value()?),
+                        "b_polys" => b_polys = Some(map.next_value()?),
+                        "c_polys" => c_polys = Some(map.next_value()?),
+                        "num_public" => num_public = Some(map.next_value()?),
+                        "domain" => domain = Some(map.next_value()?),
+                        _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+                    }
+                }
+                Ok(Qap {
+                    a_polys: a_polys.ok_or_else(|| de::Error::missing_field("a_polys"))?,
+                    b_polys: b_polys.ok_or_else(|| de::Error::missing_field("b_polys"))?,
+                    c_polys: c_polys.ok_or_else(|| de::Error::missing_field("c_polys"))?,
+                    num_public: num_public.ok_or_else(|| de::Error::missing_field("num_public"))?,
+                    domain: domain.unwrap_or(None),
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("Qap", FIELDS, QapVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::r1cs::{ConstraintSystem, CS_ONE};
+    use crate::r1cs::{x3_plus5_circuit, LinearCombination, CS_ONE};
 
     const P: i64 = 7;
 
@@ -229,4 +491,316 @@ mod tests {
         assert_eq!(qap.a_polys[CS_ONE.0].evaluate(&fe(0)), fe(2));
         assert_eq!(qap.a_polys[CS_ONE.0].evaluate(&fe(1)), fe(0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn target_polynomial_vanishes_at_each_constraint_index() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(2));
+        let v1 = cs.mul(x, x);
+        let _v2 = cs.mul(v1, x);
+        let qap = Qap::from_r1cs(&cs);
+
+        let z = qap.target_polynomial(cs.num_constraints(), &BigInt::from(P));
+        assert_eq!(z.degree(), Some(2));
+        for i in 0..cs.num_constraints() {
+            assert_eq!(z.evaluate(&fe(i as i64)), fe(0));
+        }
+        assert_ne!(z.evaluate(&fe(2)), fe(0));
+    }
+
+    #[test]
+    fn target_polynomial_with_no_constraints_is_one() {
+        let qap = Qap::from_r1cs(&build_x_squared_cs());
+        let z = qap.target_polynomial(0, &BigInt::from(P));
+        assert_eq!(z, Polynomial::new(vec![fe(1)]));
+    }
+
+    #[test]
+    fn is_satisfied_accepts_valid_witness() {
+        let cs = build_x_squared_cs();
+        let qap = Qap::from_r1cs(&cs);
+        assert!(qap.is_satisfied(&cs.generate_witness(), cs.num_constraints()));
+    }
+
+    #[test]
+    fn is_satisfied_rejects_broken_witness() {
+        let cs = build_x_squared_cs();
+        let qap = Qap::from_r1cs(&cs);
+        let mut witness = cs.generate_witness();
+        witness[2] = fe(3); // y = 9 ≡ 2 を 3 に改ざん
+        assert!(!qap.is_satisfied(&witness, cs.num_constraints()));
+    }
+
+    #[test]
+    fn from_r1cs_on_domain_agrees_with_integer_nodes() {
+        // F_17 の乗法群は位数 16。2^4 = 16 ≡ −1 なので 2 は 1 の原始 8 乗根。
+        let p = BigInt::from(17);
+        let fe17 = |v: i64| FieldElement::new(v, p.clone());
+        let omega = fe17(2);
+        let domain: Vec<FieldElement> = (0..8).map(|j| omega.pow(j)).collect();
+
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe17(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe17(3));
+        let v1 = cs.mul(x, x);
+        let _v2 = cs.mul(v1, x);
+        let n = cs.num_constraints();
+
+        let integer_qap = Qap::from_r1cs(&cs);
+        let domain_qap = Qap::from_r1cs_on_domain(&cs, &domain);
+
+        // 消失多項式 x^8 − 1 は domain 上で 0
+        let z = domain_qap.target_polynomial(n, &p);
+        assert_eq!(z.degree(), Some(8));
+        for w in &domain {
+            assert!(z.evaluate(w).is_zero());
+        }
+
+        // 制約 j 番目の係数が x = ω^j に載っている
+        assert_eq!(domain_qap.a_polys[1].evaluate(&domain[0]), fe17(1)); // x
+        assert_eq!(domain_qap.a_polys[2].evaluate(&domain[1]), fe17(1)); // v1
+
+        let witness = cs.generate_witness();
+        assert!(integer_qap.is_satisfied(&witness, n));
+        assert!(domain_qap.is_satisfied(&witness, n));
+
+        let mut broken = witness.clone();
+        broken[3] = &broken[3] + &fe17(1); // v2 を改ざん
+        assert!(!integer_qap.is_satisfied(&broken, n));
+        assert!(!domain_qap.is_satisfied(&broken, n));
+    }
+
+    #[test]
+    fn split_public_private_matches_public_prefix() {
+        // デモ回路 y = x³ + 5（public: CS_ONE, y / private: x, v1, v2）
+        let cs = x3_plus5_circuit(P);
+
+        let qap = Qap::from_r1cs(&cs);
+        assert_eq!(qap.num_public, 2);
+
+        let (public, private) = qap.split_public_private();
+        assert_eq!(public.a_polys, &qap.a_polys[..2]);
+        assert_eq!(public.c_polys, &qap.c_polys[..2]);
+        assert_eq!(private.b_polys, &qap.b_polys[2..]);
+        assert_eq!(private.a_polys.len(), 3);
+
+        // y（public の 2 番目）は最後の制約の C 側にだけ現れる
+        let last = fe(cs.num_constraints() as i64 - 1);
+        assert_eq!(public.c_polys[1].evaluate(&last), fe(1));
+    }
+
+    #[test]
+    fn from_r1cs_with_no_constraints_yields_zero_polynomials() {
+        // init_one のみ: 変数は CS_ONE だけ、制約は 0 個
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+
+        let qap = Qap::from_r1cs(&cs);
+        assert_eq!(qap.a_polys.len(), 1);
+        assert_eq!(qap.b_polys.len(), 1);
+        assert_eq!(qap.c_polys.len(), 1);
+        assert!(qap.a_polys[0].is_zero());
+        assert_eq!(qap.a_polys[0].evaluate(&fe(3)), fe(0));
+        assert!(qap.is_satisfied(&cs.generate_witness(), 0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn save_and_load_round_trip() {
+        let cs = build_x_squared_cs();
+        let qap = Qap::from_r1cs(&cs);
+
+        let path = std::env::temp_dir().join(format!("qap_round_trip_{}.json", std::process::id()));
+        qap.save(&path).unwrap();
+        let loaded = Qap::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.a_polys, qap.a_polys);
+        assert_eq!(loaded.b_polys, qap.b_polys);
+        assert_eq!(loaded.c_polys, qap.c_polys);
+        assert_eq!(loaded.num_public, qap.num_public);
+        assert_eq!(loaded.domain, qap.domain);
+    }
+
+    #[test]
+    fn evaluate_all_at_recombines_to_composed_polynomials() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(2));
+        let z = cs.add_const(x, fe(2));
+        let _w = cs.mul(z, x);
+        let qap = Qap::from_r1cs(&cs);
+        let witness = cs.generate_witness();
+
+        let tau = fe(5);
+        let (a_evals, b_evals, c_evals) = qap.evaluate_all_at(&tau);
+        assert_eq!(a_evals.len(), cs.num_variables());
+
+        let inner = |evals: &[FieldElement]| {
+            evals
+                .iter()
+                .zip(&witness)
+                .fold(fe(0), |acc, (e, w)| &acc + &(e * w))
+        };
+        let (a, b, c) = qap.compose(&witness);
+        assert_eq!(inner(&a_evals), a.evaluate(&tau));
+        assert_eq!(inner(&b_evals), b.evaluate(&tau));
+        assert_eq!(inner(&c_evals), c.evaluate(&tau));
+    }
+
+    /// 基底を使い回す前の構築方法: 列を 0 埋めの密ベクトルにして `lagrange_interpolation_at` を呼ぶ。
+    fn per_column_reference(cs: &ConstraintSystem, nodes: &[FieldElement]) -> [Vec<Polynomial>; 3] {
+        let p = nodes[0].p.clone();
+        [Matrix::A, Matrix::B, Matrix::C].map(|matrix| {
+            (0..cs.num_variables())
+                .map(|i| {
+                    let mut dense = vec![FieldElement::zero(&p); nodes.len()];
+                    for (row, val) in extract_column(cs, i, matrix) {
+                        dense[row] = &dense[row] + &val;
+                    }
+                    let points: Vec<(FieldElement, FieldElement)> =
+                        nodes.iter().cloned().zip(dense).collect();
+                    Polynomial::lagrange_interpolation_at(&points)
+                })
+                .collect()
+        })
+    }
+
+    /// `x^k` を `k − 1` 回の乗算で作り、定数を足して `x` を掛ける回路（制約数 `k + 1`）。
+    fn build_chain_cs(p: &BigInt, k: u64) -> ConstraintSystem {
+        let f = |v: u64| FieldElement::from_u64(v, p);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f(1));
+        let y = cs.alloc_public_input();
+        let x = cs.alloc_variable();
+        cs.assign(x, f(3));
+        let mut x_k = x;
+        for _ in 1..k {
+            x_k = cs.mul(x_k, x);
+        }
+        let shifted = cs.add_const(x_k, f(5));
+        let out = cs.mul(shifted, x);
+        cs.assign(y, cs.assignments[out.0].clone().unwrap());
+        cs
+    }
+
+    #[test]
+    fn from_r1cs_sums_duplicate_terms_in_one_constraint() {
+        // 制約 0: (x + 2x) * 1 = z  → A[x] は 1 + 2 = 3
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(2));
+        let z = cs.alloc_variable();
+        cs.assign(z, fe(6));
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(x, fe(1));
+        lc_a.add_term(x, fe(2));
+        cs.enforce(
+            lc_a,
+            LinearCombination::from((CS_ONE, fe(1))),
+            LinearCombination::from((z, fe(1))),
+        );
+
+        let qap = Qap::from_r1cs(&cs);
+        assert_eq!(qap.a_polys[x.0].evaluate(&fe(0)), fe(3));
+        assert!(qap.is_satisfied(&cs.generate_witness(), cs.num_constraints()));
+    }
+
+    #[test]
+    #[should_panic(expected = "原始 n 乗根")]
+    fn from_r1cs_on_domain_rejects_non_subgroup() {
+        let p = BigInt::from(17);
+        let cs = build_chain_cs(&p, 3);
+        // 整数点 1, 2, 3, 4 は乗法部分群ではない
+        let domain: Vec<FieldElement> = (1..=4).map(|j| FieldElement::from_u64(j, &p)).collect();
+        Qap::from_r1cs_on_domain(&cs, &domain);
+    }
+
+    #[test]
+    #[should_panic(expected = "原始 n 乗根")]
+    fn from_r1cs_on_domain_rejects_non_primitive_root() {
+        // ω = 4 は 1 の 4 乗根（4^4 = 256 ≡ 1）なので、長さ 8 の冪列は 1 を 2 回含む
+        let p = BigInt::from(17);
+        let cs = build_chain_cs(&p, 3);
+        let omega = FieldElement::from_u64(4, &p);
+        let domain: Vec<FieldElement> = (0..8).map(|j| omega.pow(j)).collect();
+        Qap::from_r1cs_on_domain(&cs, &domain);
+    }
+
+    #[test]
+    #[should_panic(expected = "domain のサイズ")]
+    fn target_polynomial_rejects_more_constraints_than_domain() {
+        let p = BigInt::from(17);
+        let cs = build_chain_cs(&p, 3);
+        let omega = FieldElement::from_u64(2, &p);
+        let domain: Vec<FieldElement> = (0..8).map(|j| omega.pow(j)).collect();
+        let qap = Qap::from_r1cs_on_domain(&cs, &domain);
+        qap.target_polynomial(9, &p);
+    }
+
+    #[test]
+    fn from_r1cs_matches_per_column_interpolation() {
+        // 整数点 0..n
+        let p = BigInt::from(101);
+        let cs = build_chain_cs(&p, 9);
+        let nodes: Vec<FieldElement> = (0..cs.num_constraints())
+            .map(|j| FieldElement::from_u64(j as u64, &p))
+            .collect();
+        let qap = Qap::from_r1cs(&cs);
+        let [a, b, c] = per_column_reference(&cs, &nodes);
+        assert_eq!(qap.a_polys, a);
+        assert_eq!(qap.b_polys, b);
+        assert_eq!(qap.c_polys, c);
+
+        // 1 の 8 乗根の冪（F_17 で ω = 2）
+        let p = BigInt::from(17);
+        let cs = build_chain_cs(&p, 5);
+        let omega = FieldElement::from_u64(2, &p);
+        let domain: Vec<FieldElement> = (0..8).map(|j| omega.pow(j)).collect();
+        let qap = Qap::from_r1cs_on_domain(&cs, &domain);
+        let [a, b, c] = per_column_reference(&cs, &domain);
+        assert_eq!(qap.a_polys, a);
+        assert_eq!(qap.b_polys, b);
+        assert_eq!(qap.c_polys, c);
+    }
+
+    /// `cargo test --release -- --ignored --nocapture bench_qap` で実行する。
+    #[test]
+    #[ignore]
+    fn bench_qap_build_shared_basis_vs_per_column() {
+        use std::time::Instant;
+
+        let p = BigInt::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            10,
+        )
+        .unwrap();
+        let cs = build_chain_cs(&p, 48);
+        let nodes: Vec<FieldElement> = (0..cs.num_constraints())
+            .map(|j| FieldElement::from_u64(j as u64, &p))
+            .collect();
+
+        let start = Instant::now();
+        let reference = per_column_reference(&cs, &nodes);
+        let per_column = start.elapsed();
+
+        let start = Instant::now();
+        let qap = Qap::from_r1cs(&cs);
+        let shared = start.elapsed();
+
+        assert_eq!(qap.a_polys, reference[0]);
+        eprintln!(
+            "{} constraints, {} variables: per-column {:?}, shared basis {:?} ({:.1}x)",
+            cs.num_constraints(),
+            cs.num_variables(),
+            per_column,
+            shared,
+            per_column.as_secs_f64() / shared.as_secs_f64()
+        );
+    }
+}
@@ -11,16 +11,106 @@
 //!
 //! ## 回路構築 API
 //! - [`ConstraintSystem::mul`][]: 掛け算ゲート
+//! - [`ConstraintSystem::square`][]: 2 乗ゲート
 //! - [`ConstraintSystem::add`][]: 足し算ゲート
+//! - [`ConstraintSystem::sum`][]: 多入力の足し算ゲート（制約 1 本）
+//! - [`ConstraintSystem::linear_combination`][]: 重み付き和 `Σ k_i · v_i + k` のゲート（制約 1 本）
 //! - [`ConstraintSystem::add_const`][]: 定数加算ゲート
+//! - [`ConstraintSystem::sub`][]: 引き算ゲート
+//! - [`ConstraintSystem::mul_const`][]: 定数倍ゲート
+//! - [`ConstraintSystem::pow`][]: 定数乗ゲート（square-and-multiply で掛け算を連鎖）
+//! - [`ConstraintSystem::inner_product`][]: 内積 `Σ a_i · b_i`
+//! - [`ConstraintSystem::div`][]: 割り算ゲート
+//! - [`ConstraintSystem::inverse`][]: 逆元ゲート
+//! - [`ConstraintSystem::select`][]: 条件選択ゲート `cond ? a : b`
+//! - [`ConstraintSystem::enforce_boolean`][]: 変数が 0 または 1 であることの制約
+//! - [`ConstraintSystem::assert_nonzero`][]: 変数が 0 でないことの制約（逆元を hint にする）
+//! - [`ConstraintSystem::to_bits`][]: ビット分解（範囲チェック）
+//! - [`ConstraintSystem::less_than`][]: 大小比較 `a < b`（`n` ビットに収まる値どうし）
+//! - [`ConstraintSystem::mimc`][]: MiMC 置換（ラウンドごとに `x ↦ (x + k + c_i)^3`）
+//! - [`ConstraintSystem::and`][] / [`ConstraintSystem::or`][] / [`ConstraintSystem::xor`][]: ビット演算ゲート
+//! - [`ConstraintSystem::enforce_equal`][] / [`ConstraintSystem::enforce_equal_const`][]: 等値制約
+//! - [`ConstraintSystem::alloc_const`][]: 定数値に固定した変数の確保
+//!
+//! ## 遅延 witness
+//! - [`ConstraintSystem::alloc_lazy`][] / [`ConstraintSystem::assign_with`][]: 値の計算方法（クロージャ）を登録
+//! - [`ConstraintSystem::solve_witness`][]: 登録順にクロージャを実行して値を埋める
+//!
+//! ## デバッグ表示
+//! - [`ConstraintSystem::alloc_named`][]: 名前付きで変数を確保
+//! - [`ConstraintSystem::format_constraints`][] / [`ConstraintSystem::print_constraints`][]:
+//!   制約を `(x) * (x) = x2` のように変数名で表示
+//!
+//! ## 充足判定
+//! - [`ConstraintSystem::is_satisfied`][]: witness が全制約を満たすか
+//! - [`ConstraintSystem::evaluate_lc`][]: 線形結合を witness で評価
+//!
+//! ## 行列表現
+//! - [`ConstraintSystem::to_matrices`][]: `A`, `B`, `C` を密行列として取り出す
+//!
+//! ## 最適化
+//! - [`ConstraintSystem::eliminate_unused`][]: どの制約にも現れない変数を取り除いて詰め直す
+//! - [`ConstraintSystem::deduplicate_constraints`][]: 同一の制約を 1 本にまとめる
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::ops::Add;
 
 use crate::field::FieldElement;
+#[cfg(test)]
+use crate::field::IntoModulus;
+
+/// Witness 生成で起こりうるエラー。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitnessError {
+    /// 値が代入されていない変数があった。
+    Unassigned(Variable),
+    /// 変数の値が `bits` ビットに収まらない（[`ConstraintSystem::to_bits`]）。
+    OutOfRange { var: Variable, bits: usize },
+}
+
+impl fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WitnessError::Unassigned(var) => {
+                write!(f, "witness contains an unassigned variable: {}", var.0)
+            }
+            WitnessError::OutOfRange { var, bits } => {
+                write!(f, "variable {} does not fit in {} bits", var.0, bits)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
+/// ゲート・ガジェットの引数が不正なときのエラー。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    /// 同じ長さであるべき変数列の長さが異なる。
+    LengthMismatch { left: usize, right: usize },
+    /// 空の変数列が渡された。
+    EmptyInput,
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CircuitError::LengthMismatch { left, right } => {
+                write!(f, "slice lengths differ: {} != {}", left, right)
+            }
+            CircuitError::EmptyInput => write!(f, "empty input"),
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
 
 /// 制約系内の変数を識別するインデックス。
 ///
 /// `Variable(0)` は定数 1 に予約済み（[`CS_ONE`]）。通常の変数は
 /// [`ConstraintSystem::alloc_variable`] で発行される。
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Variable(pub usize);
 
 /// 定数 1 を表す予約変数。`assignments[0]` に値 1 が入っていることが前提。
@@ -39,20 +129,139 @@ pub struct LinearCombination {
     // (変数のインデックス, その係数) のリスト
     // 3x + 2y + 5 は [(Variable(1), 3), (Variable(2), 2), (Variable(0), 5)] となる
     pub terms: Vec<(Variable, FieldElement)>,
+    // `From<Variable>` で係数 1 として足したが、法が分からず係数をまだ作れていない変数。
+    // 法を持つ係数と出会ったとき（add_term・Add・scale）か enforce で terms に移す。
+    // 不変条件: unit_terms が空でなければ terms は空
+    unit_terms: Vec<Variable>,
 }
 
 impl LinearCombination {
     /// 空の線形結合を生成する。
     pub fn new() -> Self {
-        Self { terms: Vec::new() }
+        Self {
+            terms: Vec::new(),
+            unit_terms: Vec::new(),
+        }
     }
 
     /// 項 `coeff · var` を末尾に追加する。
     ///
     /// 既存の同変数項とはマージせず、別エントリとして保持する。
     pub fn add_term(&mut self, var: Variable, coeff: FieldElement) {
+        self.resolve_units(&FieldElement::one(&coeff.p));
         self.terms.push((var, coeff));
     }
+
+    /// 係数 1 のまま保留していた項を、`one` の法の係数で `terms` に移す。
+    fn resolve_units(&mut self, one: &FieldElement) {
+        if self.unit_terms.is_empty() {
+            return;
+        }
+        let units = std::mem::take(&mut self.unit_terms);
+        self.terms = merge_terms(units.into_iter().map(|var| (var, one.clone())));
+    }
+}
+
+impl LinearCombination {
+    /// 全項の係数に `c` を掛けた線形結合を返す。
+    pub fn scale(&self, c: &FieldElement) -> LinearCombination {
+        let mut lc = self.clone();
+        lc.resolve_units(&FieldElement::one(&c.p));
+        lc.terms = lc
+            .terms
+            .iter()
+            .map(|(var, coeff)| (*var, coeff * c))
+            .collect();
+        lc
+    }
+
+    /// 比較・書き出し用の正規形（変数順・同じ変数はまとめる・係数 0 は除く）を返す。
+    ///
+    /// `(変数インデックス, 係数)` の列。項の並びや重複だけが違う線形結合は同じ結果になる。
+    pub(crate) fn normalized_terms(&self) -> Vec<(usize, FieldElement)> {
+        debug_assert!(
+            self.unit_terms.is_empty(),
+            "係数 1 の項は enforce などで法が決まってから正規化する"
+        );
+        let mut merged: BTreeMap<usize, FieldElement> = BTreeMap::new();
+        for (var, coeff) in &self.terms {
+            merged
+                .entry(var.0)
+                .and_modify(|c| *c = &*c + coeff)
+                .or_insert_with(|| coeff.clone());
+        }
+        merged.into_iter().filter(|(_, c)| !c.is_zero()).collect()
+    }
+}
+
+/// 単一の項 `coeff · var` からなる線形結合。
+///
+/// [`Variable`] は法 `p` を持たないので、係数 1 も呼び出し側が渡す
+/// （`(x, one.clone()).into()` の形で使う）。
+impl From<(Variable, FieldElement)> for LinearCombination {
+    fn from((var, coeff): (Variable, FieldElement)) -> Self {
+        let mut lc = LinearCombination::new();
+        lc.add_term(var, coeff);
+        lc
+    }
+}
+
+/// 単一の項 `1 · var` からなる線形結合。
+///
+/// [`Variable`] は法 `p` を持たないので、係数 1 は法が分かるまで保留する。
+/// 法を持つ項と足したとき・[`scale`](LinearCombination::scale) したとき、
+/// または [`ConstraintSystem::enforce`] に渡したときに確定する
+/// （`x.into()` のまま `terms` を直接読んでも係数 1 の項は見えない）。
+impl From<Variable> for LinearCombination {
+    fn from(var: Variable) -> Self {
+        let mut lc = LinearCombination::new();
+        lc.unit_terms.push(var);
+        lc
+    }
+}
+
+/// 線形結合の和。同じ変数の項は係数を足して 1 項にまとめる。
+///
+/// [`LinearCombination::add_term`] はマージしないが、こちらは結合後の項を
+/// 変数ごとに 1 つにする（右辺側の重複もまとめる）。項の並びは初出順。
+impl Add for LinearCombination {
+    type Output = LinearCombination;
+
+    fn add(mut self, mut other: LinearCombination) -> LinearCombination {
+        // どちらかが係数を持っていれば法が分かるので、保留中の係数 1 を確定させる
+        let one = self
+            .terms
+            .first()
+            .or(other.terms.first())
+            .map(|(_, c)| FieldElement::one(&c.p));
+        match one {
+            Some(one) => {
+                self.resolve_units(&one);
+                other.resolve_units(&one);
+            }
+            None => {
+                self.unit_terms.extend(other.unit_terms);
+                return self;
+            }
+        }
+        let mut lc = LinearCombination::new();
+        lc.terms = merge_terms(self.terms.into_iter().chain(other.terms));
+        lc
+    }
+}
+
+/// 同じ変数の項の係数を足して 1 項にまとめる（並びは初出順）。
+fn merge_terms(
+    terms: impl Iterator<Item = (Variable, FieldElement)>,
+) -> Vec<(Variable, FieldElement)> {
+    let mut merged: Vec<(Variable, FieldElement)> = Vec::new();
+    for (var, coeff) in terms {
+        match merged.iter_mut().find(|(v, _)| *v == var) {
+            Some((_, existing)) => *existing = &*existing + &coeff,
+            None => merged.push((var, coeff)),
+        }
+    }
+    merged
 }
 
 impl Default for LinearCombination {
@@ -72,6 +281,14 @@ pub struct Constraint {
     pub c: LinearCombination,
 }
 
+/// 遅延 witness の計算関数。それまでの代入値（未代入は `None`）から変数の値を計算する。
+///
+/// [`ConstraintSystem::alloc_lazy`] / [`ConstraintSystem::assign_with`] で登録する。
+pub type WitnessFn = Box<dyn Fn(&[Option<FieldElement>]) -> FieldElement>;
+
+/// 係数の密行列。`m[j][i]` が制約 `j`・変数 `i` の係数（[`ConstraintSystem::to_matrices`]）。
+pub type DenseMatrix = Vec<Vec<FieldElement>>;
+
 /// 算術回路全体を保持する制約系。
 ///
 /// 制約のリストと、各変数の現在値（Witness 候補）を持つ。
@@ -104,6 +321,10 @@ pub struct ConstraintSystem {
     // 先頭から数えた public 変数の数（CS_ONE 含む = l+1）。
     // 不変条件: public 変数は常にインデックス 0..num_public_variables
     pub num_public_variables: usize,
+    // 各変数の表示名（alloc_named で付ける。None は v{index} で表示）。assignments と同じ長さ
+    pub names: Vec<Option<String>>,
+    // 遅延 witness の (変数, 計算関数)。solve_witness が登録順に実行する
+    witness_fns: Vec<(Variable, WitnessFn)>,
 }
 
 impl ConstraintSystem {
@@ -119,9 +340,21 @@ impl ConstraintSystem {
             assignments: Vec::new(),
             // init_one で CS_ONE を public として 1 に設定する
             num_public_variables: 0,
+            names: Vec::new(),
+            witness_fns: Vec::new(),
         }
     }
 
+    /// 追加済みの制約の数を返す。QAP の補間点の数になる。
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// 確保済みの変数の数（[`CS_ONE`] を含む）を返す。Witness ベクトルの長さになる。
+    pub fn num_variables(&self) -> usize {
+        self.next_var_index
+    }
+
     /// 変数 `var` に値 `value` を代入する。
     ///
     /// `var` が [`alloc_variable`](Self::alloc_variable) 未発行のときは panic する。
@@ -151,14 +384,21 @@ impl ConstraintSystem {
     /// 全変数の現在値を Witness ベクトルとして取り出す。
     ///
     /// 未代入の変数（`None`）が残っていれば panic する。
+    /// panic させたくない場合は [`try_generate_witness`](Self::try_generate_witness) を使う。
     pub fn generate_witness(&self) -> Vec<FieldElement> {
+        self.try_generate_witness()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// 全変数の現在値を Witness ベクトルとして取り出す。
+    ///
+    /// 未代入の変数があれば、最初に見つかったものを
+    /// [`WitnessError::Unassigned`] で返す。
+    pub fn try_generate_witness(&self) -> Result<Vec<FieldElement>, WitnessError> {
         self.assignments
             .iter()
-            .map(|val| {
-                val.as_ref()
-                    .expect("witness contains an unassigned variable")
-                    .clone()
-            })
+            .enumerate()
+            .map(|(i, val)| val.clone().ok_or(WitnessError::Unassigned(Variable(i))))
             .collect()
     }
 
@@ -169,6 +409,61 @@ impl ConstraintSystem {
         let var = Variable(self.next_var_index);
         self.next_var_index += 1;
         self.assignments.push(None);
+        self.names.push(None);
+        var
+    }
+
+    /// 値を後から計算する変数を発行する。
+    ///
+    /// `f` は [`solve_witness`](Self::solve_witness) のたびに、それまでの代入値
+    /// （`assignments` 全体）を受け取って変数の値を返す。回路は一度だけ組み、
+    /// 入力を [`assign`](Self::assign) し直して `solve_witness` を呼べば別の入力で使い回せる。
+    /// `mul` などのゲートは構築時に値を計算するので、遅延させたい変数は
+    /// こちらで確保して [`enforce`](Self::enforce) で制約を張る。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn alloc_lazy(&mut self, f: WitnessFn) -> Variable {
+        let var = self.alloc_variable();
+        self.assign_with(var, f);
+        var
+    }
+
+    /// 確保済みの変数 `var` に、値の計算関数 `f` を登録する。
+    ///
+    /// 公開入力のように [`alloc_lazy`](Self::alloc_lazy) で確保できない変数に使う。
+    /// 関数は登録順に実行されるので、`f` が読む変数の関数より後に登録すること。
+    pub fn assign_with(&mut self, var: Variable, f: WitnessFn) {
+        assert!(
+            var.0 < self.assignments.len(),
+            "variable {} is out of bounds; alloc it first",
+            var.0
+        );
+        self.witness_fns.push((var, f));
+    }
+
+    /// 登録済みの計算関数を登録順に実行し、遅延変数の値を埋める。
+    ///
+    /// 変数の確保順ではなく登録順に実行するのは、公開入力のように前方に確保した変数が
+    /// 後ろの中間変数に依存することがあるため。登録は依存先より後に行う前提なので、
+    /// 登録順がそのまま依存順になる。何度呼んでもよく、そのつど値を上書きする。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn solve_witness(&mut self) {
+        for (var, f) in &self.witness_fns {
+            let value = f(&self.assignments);
+            self.assignments[var.0] = Some(value);
+        }
+    }
+
+    /// 表示名 `name` 付きで新しい変数を発行する。
+    ///
+    /// [`alloc_variable`](Self::alloc_variable) と同じく秘密/中間変数で、値は未代入。
+    /// 名前は [`format_constraints`](Self::format_constraints) の表示にだけ使う。
+    pub fn alloc_named(&mut self, name: &str) -> Variable {
+        let var = self.alloc_variable();
+        self.names[var.0] = Some(name.to_string());
         var
     }
 
@@ -197,13 +492,291 @@ impl ConstraintSystem {
         var
     }
 
+    /// 定数 `value` に固定した変数を発行し、その [`Variable`] ハンドルを返す。
+    ///
+    /// 変数を確保して `value` を代入し、制約 `(var) · 1 = (value · 1)` を追加する。
+    /// 定数は普段 [`CS_ONE`] の係数として線形結合に入るが、こうして配線にしておけば
+    /// ガジェットは定数も通常の変数と同じに扱える（制約 1 本と変数 1 つが増える）。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn alloc_const(&mut self, value: FieldElement) -> Variable {
+        let one = self.one();
+        let var = self.alloc_variable();
+        self.assign(var, value.clone());
+
+        // 制約： (var) * 1 = value * 1
+        let lc_a = LinearCombination::from((var, one.clone()));
+        let lc_b = LinearCombination::from((CS_ONE, one));
+        let lc_c = LinearCombination::from((CS_ONE, value));
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        var
+    }
+
+    /// Witness ベクトルから公開入力 `a_1, ..., a_ℓ` の部分を切り出す。
+    ///
+    /// public 変数は前方 `0..num_public_variables` に固められているので、
+    /// そこから [`CS_ONE`]（`a_0 = 1`）を除いた範囲を返す。
+    /// verifier の `public_inputs` 引数と同じ並び（長さ ℓ）になる。
+    ///
+    /// `witness` は [`generate_witness`](Self::generate_witness) の戻り値を想定し、
+    /// 長さが public 変数の数に満たなければ panic する。
+    pub fn public_inputs<'a>(&self, witness: &'a [FieldElement]) -> &'a [FieldElement] {
+        assert!(
+            witness.len() >= self.num_public_variables,
+            "witness is shorter than the public variables"
+        );
+        &witness[CS_ONE.0 + 1..self.num_public_variables]
+    }
+
     /// 制約 `A · B = C` を制約系に直接追加する。
     ///
     /// 通常は `mul` / `add` / `add_const` 経由で間接的に呼ばれる。
-    pub fn enforce(&mut self, a: LinearCombination, b: LinearCombination, c: LinearCombination) {
+    pub fn enforce(
+        &mut self,
+        mut a: LinearCombination,
+        mut b: LinearCombination,
+        mut c: LinearCombination,
+    ) {
+        // `From<Variable>` で保留された係数 1 をこの制約系の法で確定させる
+        let one = self.one();
+        for lc in [&mut a, &mut b, &mut c] {
+            lc.resolve_units(&one);
+        }
         self.constraints.push(Constraint { a, b, c });
     }
 
+    /// 整数係数で書いた制約 `A · B = C` を追加する。
+    ///
+    /// 各辺は `(項の列, 定数)` の組で、`(&[(x, 1), (y, -2)], 5)` は `x - 2y + 5` を表す。
+    /// 係数と定数は法 `p` で簡約され（負数も可）、定数は [`CS_ONE`] の項として末尾に置く
+    /// （0 なら項を作らない）。`CS_ONE` の項を手で足す手間と書き間違いを減らすための糖衣。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn enforce_lc(
+        &mut self,
+        a: (&[(Variable, i64)], i64),
+        b: (&[(Variable, i64)], i64),
+        c: (&[(Variable, i64)], i64),
+    ) {
+        let lc_a = self.lc_from_ints(a);
+        let lc_b = self.lc_from_ints(b);
+        let lc_c = self.lc_from_ints(c);
+        self.enforce(lc_a, lc_b, lc_c);
+    }
+
+    /// [`enforce_lc`](Self::enforce_lc) の 1 辺を [`LinearCombination`] に変換する。
+    fn lc_from_ints(&self, (terms, constant): (&[(Variable, i64)], i64)) -> LinearCombination {
+        let p = &self.one().p;
+        let mut lc = LinearCombination::new();
+        for &(var, coeff) in terms {
+            lc.add_term(var, FieldElement::new(coeff, p.clone()));
+        }
+        if constant != 0 {
+            lc.add_term(CS_ONE, FieldElement::new(constant, p.clone()));
+        }
+        lc
+    }
+
+    /// 線形結合 `lc` を `witness` で評価した値 `Σ c_i · witness[x_i]` を返す。
+    ///
+    /// 法 `p` は `witness[0]` ではなく項の係数から取る（空の witness でも安全）。
+    /// 項が 1 つもない線形結合は 0 で、その法は [`CS_ONE`] の代入値から取る。
+    ///
+    /// `lc` が `witness` の範囲外の変数を参照していれば panic する。
+    pub fn evaluate_lc(&self, lc: &LinearCombination, witness: &[FieldElement]) -> FieldElement {
+        let resolved;
+        let lc = if lc.unit_terms.is_empty() {
+            lc
+        } else {
+            let mut copy = lc.clone();
+            copy.resolve_units(&self.one());
+            resolved = copy;
+            &resolved
+        };
+        let mut acc: Option<FieldElement> = None;
+        for (var, coeff) in &lc.terms {
+            let value = witness
+                .get(var.0)
+                .unwrap_or_else(|| panic!("variable {} is out of the witness", var.0));
+            let term = coeff * value;
+            acc = Some(match acc {
+                Some(sum) => &sum + &term,
+                None => term,
+            });
+        }
+        acc.unwrap_or_else(|| FieldElement::zero(&self.one().p))
+    }
+
+    /// 全制約 `A · B = C` が `witness` で成り立つかを返す。
+    ///
+    /// `witness` の長さが変数の数と一致しなければ（空を含む）`false`。
+    pub fn is_satisfied(&self, witness: &[FieldElement]) -> bool {
+        if witness.len() != self.next_var_index {
+            return false;
+        }
+        self.constraints.iter().all(|con| {
+            let a = self.evaluate_lc(&con.a, witness);
+            let b = self.evaluate_lc(&con.b, witness);
+            &a * &b == self.evaluate_lc(&con.c, witness)
+        })
+    }
+
+    /// 制約系を `A`, `B`, `C` の密行列（`num_constraints × num_variables`）として返す。
+    ///
+    /// 行 `j` が制約 `j`、列 `i` が変数 `Variable(i)` に対応する。
+    /// 同じ制約内に同じ変数の項が複数あれば係数を足し合わせ、項のない位置は 0 になる。
+    /// R1CS → QAP の対応を目で確かめるためのデバッグ・学習用
+    /// （QAP は各列を補間して変数ごとの多項式を作る）。
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn to_matrices(&self) -> (DenseMatrix, DenseMatrix, DenseMatrix) {
+        let zero = FieldElement::zero(&self.one().p);
+        let dense = |lc: &LinearCombination| {
+            let mut row = vec![zero.clone(); self.num_variables()];
+            for (var, coeff) in &lc.terms {
+                row[var.0] = &row[var.0] + coeff;
+            }
+            row
+        };
+        let a = self.constraints.iter().map(|con| dense(&con.a)).collect();
+        let b = self.constraints.iter().map(|con| dense(&con.b)).collect();
+        let c = self.constraints.iter().map(|con| dense(&con.c)).collect();
+        (a, b, c)
+    }
+
+    /// どの制約にも現れない変数を取り除き、残りのインデックスを前から詰め直す。
+    ///
+    /// 全制約の `A`, `B`, `C` を走査して参照される変数を集め、参照されない変数を落とす。
+    /// [`CS_ONE`] と公開入力は参照がなくても残す（公開入力の並びは検証者との約束なので）。
+    /// 残った変数は元の順序のまま `0, 1, 2, ...` に振り直し、線形結合と代入値も書き換える。
+    /// 戻り値は残った変数の「旧 → 新」の対応（落とした変数はキーに含まない）。
+    ///
+    /// 変数が減るぶん QAP の多項式（変数ごとに 3 本）も減る。
+    /// 遅延 witness の計算関数は旧インデックスを読むので破棄する（先に
+    /// [`solve_witness`](Self::solve_witness) を済ませておくこと）。
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn eliminate_unused(&mut self) -> HashMap<Variable, Variable> {
+        let mut used = vec![false; self.num_variables()];
+        for flag in used.iter_mut().take(self.num_public_variables) {
+            *flag = true;
+        }
+        for con in &self.constraints {
+            for lc in [&con.a, &con.b, &con.c] {
+                for (var, _) in &lc.terms {
+                    used[var.0] = true;
+                }
+            }
+        }
+
+        let mut mapping = HashMap::new();
+        let mut assignments = Vec::new();
+        let mut names = Vec::new();
+        for (old, (value, name)) in self
+            .assignments
+            .drain(..)
+            .zip(self.names.drain(..))
+            .enumerate()
+        {
+            if used[old] {
+                mapping.insert(Variable(old), Variable(assignments.len()));
+                assignments.push(value);
+                names.push(name);
+            }
+        }
+        self.assignments = assignments;
+        self.names = names;
+        self.witness_fns.clear();
+        self.next_var_index = self.assignments.len();
+
+        for con in &mut self.constraints {
+            for lc in [&mut con.a, &mut con.b, &mut con.c] {
+                for (var, _) in &mut lc.terms {
+                    *var = mapping[var];
+                }
+            }
+        }
+        mapping
+    }
+
+    /// 全制約を 1 行 1 本の `(A) * (B) = C` 形式の文字列にする。
+    ///
+    /// 変数は [`alloc_named`](Self::alloc_named) の名前、名前がなければ `v{index}` で表す。
+    /// [`CS_ONE`] の項は係数だけ（`5`）、係数 1 の項は変数名だけ、それ以外は `3·x` と書く。
+    /// 係数は `[0, p)` の代表元で表示するので、`−1` は `p − 1` になる。
+    /// 空の線形結合は `0`。例: `(x + 5) * (1) = y`。
+    pub fn format_constraints(&self) -> String {
+        self.constraints
+            .iter()
+            .map(|con| {
+                format!(
+                    "({}) * ({}) = {}\n",
+                    self.format_lc(&con.a),
+                    self.format_lc(&con.b),
+                    self.format_lc(&con.c)
+                )
+            })
+            .collect()
+    }
+
+    /// [`format_constraints`](Self::format_constraints) の結果を標準出力に書く。
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn print_constraints(&self) {
+        print!("{}", self.format_constraints());
+    }
+
+    /// 線形結合 1 本を `x + 3·y + 5` の形にする（[`format_constraints`](Self::format_constraints) 用）。
+    fn format_lc(&self, lc: &LinearCombination) -> String {
+        if lc.terms.is_empty() {
+            return "0".to_string();
+        }
+        lc.terms
+            .iter()
+            .map(|(var, coeff)| {
+                if *var == CS_ONE {
+                    coeff.value.to_string()
+                } else if coeff.is_one() {
+                    self.variable_label(*var)
+                } else {
+                    format!("{}·{}", coeff.value, self.variable_label(*var))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    /// 変数の表示名。名前がなければ `v{index}`。
+    fn variable_label(&self, var: Variable) -> String {
+        match self.names.get(var.0) {
+            Some(Some(name)) => name.clone(),
+            _ => format!("v{}", var.0),
+        }
+    }
+
+    /// 同一の制約（`A`, `B`, `C` がすべて等しい）を最初の 1 本だけ残して取り除く。
+    ///
+    /// 線形結合は項の並びや重複に左右されないよう、変数ごとに係数を足し合わせ、
+    /// 係数 0 の項を落とし、変数インデックス順に並べた形で比較する。
+    /// 同じ制約は何本あっても満たす witness の集合を変えないので、QAP も同値のまま
+    /// 補間点（= 制約）の数だけ減る。残す制約の順序は元のまま。
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn deduplicate_constraints(&mut self) {
+        let mut seen = HashSet::new();
+        self.constraints.retain(|con| {
+            seen.insert((
+                con.a.normalized_terms(),
+                con.b.normalized_terms(),
+                con.c.normalized_terms(),
+            ))
+        });
+    }
+
     /// 掛け算ゲートを追加する。
     ///
     /// 新変数 `c` を確保して `c = a * b` を計算し、制約 `(a) · (b) = (c)` を追加する。
@@ -234,19 +807,67 @@ impl ConstraintSystem {
         c
     }
 
+    /// 2 乗ゲートを追加する。
+    ///
+    /// 新変数 `c` を確保して `c = a^2` を計算し、制約 `(a) · (a) = (c)` を追加する。
+    /// 制約は `mul(a, a)` と同じだが、2 乗であることを明示する。戻り値は `c`。
+    pub fn square(&mut self, a: Variable) -> Variable {
+        let c = self.alloc_variable();
+
+        // 値の計算（Witness 生成）
+        let val_a = self.assignments[a.0]
+            .as_ref()
+            .expect("variable a is unassigned");
+        self.assign(c, val_a.square());
+
+        // 制約: (a) * (a) = (c)
+        let lc_a = LinearCombination::from((a, self.one()));
+        let lc_b = LinearCombination::from((a, self.one()));
+        let lc_c = LinearCombination::from((c, self.one()));
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+
+    /// 定数乗ゲートを追加する: `base^exponent` を表す変数を返す。
+    ///
+    /// 指数を上位ビットから走査する square-and-multiply で [`square`](Self::square) と
+    /// [`mul`](Self::mul) を連鎖する。
+    /// 制約数は `(ビット長 - 1) + (1 のビット数 - 1)` で、素朴な連鎖の `exponent - 1` より少ない
+    /// （例: `x^16` は 4 本、`x^3` は 2 本）。
+    ///
+    /// `exponent == 0` なら [`CS_ONE`]、`exponent == 1` なら `base` をそのまま返し、
+    /// どちらも制約を追加しない。
+    pub fn pow(&mut self, base: Variable, exponent: u64) -> Variable {
+        if exponent == 0 {
+            return CS_ONE;
+        }
+
+        // 最上位ビットは base そのもの。残りのビットで 2 乗（と 1 なら base 倍）を繰り返す
+        let bits = u64::BITS - exponent.leading_zeros();
+        let mut result = base;
+        for i in (0..bits - 1).rev() {
+            result = self.square(result);
+            if (exponent >> i) & 1 == 1 {
+                result = self.mul(result, base);
+            }
+        }
+        result
+    }
+
     /// 法 `p` のもとでの `FieldElement` 1 を返す。
     ///
     /// `assignments[0]` ([`CS_ONE`]) から法を取り出すため、`init_one` 済み前提。
     fn one(&self) -> FieldElement {
-        let p = self
+        let p = &self
             .assignments
             .first()
             .expect("constraint system not initialized; call init_one() first")
             .as_ref()
             .expect("CS_ONE is unassigned")
-            .p
-            .clone();
-        FieldElement::new(1, p)
+            .p;
+        FieldElement::one(p)
     }
 
     /// 足し算ゲートを追加する。
@@ -285,27 +906,105 @@ impl ConstraintSystem {
         c
     }
 
-    /// 定数加算ゲートを追加する。
+    /// 多入力の足し算ゲートを追加する: `c = v_0 + v_1 + ... + v_{n-1}`。
     ///
-    /// 新変数 `c` を確保して `c = a + k` を計算し、
-    /// 制約 `(a + k · 1) · 1 = (c)` を追加する。戻り値は `c`。
+    /// 新変数 `c` を 1 つだけ確保し、制約 `(v_0 + ... + v_{n-1}) · 1 = (c)` を 1 本追加する。
+    /// [`add`](Self::add) を連鎖させると制約と中間変数が `n - 1` 個ずつ増えるのに対し、
+    /// 線形結合は何項でも制約 1 本に収まる。戻り値は `c`。
     ///
-    /// 現在は unit test からのみ呼ばれる（main の x³+5 デモは `enforce` で
-    /// 公開出力に接続するため）。回路で使われ始めたら attribute を外す。
+    /// 空の `vars` では A 側が空の線形結合（= 0）になり、`c` は定数 0 に固定される。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
     #[allow(dead_code)]
-    pub fn add_const(&mut self, a: Variable, constant: FieldElement) -> Variable {
+    pub fn sum(&mut self, vars: &[Variable]) -> Variable {
+        let one = self.one();
+
+        // 値の計算
+        let mut val_c = FieldElement::zero(&one.p);
+        for v in vars {
+            let val = self.assignments[v.0]
+                .as_ref()
+                .expect("summed variable is unassigned");
+            val_c = &val_c + val;
+        }
+        let c = self.alloc_variable();
+        self.assign(c, val_c);
+
+        // 制約： (v_0 + ... + v_{n-1}) * 1 = c
+        let mut lc_a = LinearCombination::new();
+        for &v in vars {
+            lc_a.add_term(v, one.clone());
+        }
+        let lc_b = LinearCombination::from((CS_ONE, one.clone()));
+        let lc_c = LinearCombination::from((c, one));
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+
+    /// 重み付き和のゲートを追加する: `c = Σ k_i · v_i + constant`。
+    ///
+    /// [`add_const`](Self::add_const) と [`sum`](Self::sum) の一般形。新変数 `c` を確保して
+    /// 値を計算し、制約 `(Σ k_i · v_i + constant · 1) · 1 = (c)` を 1 本追加する。
+    /// `constant` が 0 なら [`CS_ONE`] の項は作らない。戻り値は `c`。
+    pub fn linear_combination(
+        &mut self,
+        terms: &[(Variable, FieldElement)],
+        constant: FieldElement,
+    ) -> Variable {
+        let one = self.one();
+
+        // 値の計算
+        let mut val_c = constant.clone();
+        for (v, k) in terms {
+            let val = self.assignments[v.0]
+                .as_ref()
+                .expect("variable in linear combination is unassigned");
+            val_c = &val_c + &(k * val);
+        }
+        let c = self.alloc_variable();
+        self.assign(c, val_c);
+
+        // 制約： (Σ k_i * v_i + k * 1) * 1 = c
+        let mut lc_a = LinearCombination::new();
+        for (v, k) in terms {
+            lc_a.add_term(*v, k.clone());
+        }
+        if !constant.is_zero() {
+            lc_a.add_term(CS_ONE, constant);
+        }
+        let lc_b = LinearCombination::from((CS_ONE, one.clone()));
+        let lc_c = LinearCombination::from((c, one));
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+
+    /// 引き算ゲートを追加する。
+    ///
+    /// 新変数 `c` を確保して `c = a - b` を計算し、
+    /// 制約 `(a + (-1) · b) · 1 = (c)` を追加する。戻り値は `c`。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn sub(&mut self, a: Variable, b: Variable) -> Variable {
         let c = self.alloc_variable();
 
         // 値の計算
         let val_a = self.assignments[a.0]
             .as_ref()
             .expect("variable a is unassigned");
-        self.assign(c, val_a + &constant);
+        let val_b = self.assignments[b.0]
+            .as_ref()
+            .expect("variable b is unassigned");
+        self.assign(c, val_a - val_b);
 
-        // 制約： (a + 1 * k) * 1 = c
+        // 制約： (a - b) * 1 = c
         let mut lc_a = LinearCombination::new();
         lc_a.add_term(a, self.one());
-        lc_a.add_term(CS_ONE, constant);
+        lc_a.add_term(b, -self.one());
 
         let mut lc_b = LinearCombination::new();
         lc_b.add_term(CS_ONE, self.one());
@@ -317,135 +1016,1433 @@ impl ConstraintSystem {
 
         c
     }
-}
 
-impl Default for ConstraintSystem {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+    /// 定数倍ゲートを追加する。
+    ///
+    /// 新変数 `c` を確保して `c = k · a` を計算し、制約 `(k · a) · 1 = (c)` を追加する。
+    /// `k` は A 側の係数に入るだけなので、掛け算ゲートと違い変数同士の積を使わない。
+    /// 戻り値は `c`。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn mul_const(&mut self, a: Variable, k: FieldElement) -> Variable {
+        let c = self.alloc_variable();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // 値の計算
+        let val_a = self.assignments[a.0]
+            .as_ref()
+            .expect("variable a is unassigned");
+        self.assign(c, &k * val_a);
 
-    const P: i64 = 7;
+        // 制約： (k * a) * 1 = c
+        let lc_a = LinearCombination::from((a, self.one())).scale(&k);
+        let lc_b = LinearCombination::from((CS_ONE, self.one()));
+        let lc_c = LinearCombination::from((c, self.one()));
 
-    fn fe(v: i64) -> FieldElement {
-        FieldElement::new(v, P)
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+
+    /// 内積ガジェットを追加する: `Σ a_i · b_i` を表す変数を返す。
+    ///
+    /// ペアごとに [`mul`](Self::mul) を 1 本、積の和を [`add`](Self::add) の連鎖で作る。
+    /// 制約数は `2n - 1`。
+    ///
+    /// `a` と `b` の長さが異なれば [`CircuitError::LengthMismatch`]、
+    /// 空なら [`CircuitError::EmptyInput`] を返し、そのときは何も追加しない。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn inner_product(
+        &mut self,
+        a: &[Variable],
+        b: &[Variable],
+    ) -> Result<Variable, CircuitError> {
+        if a.len() != b.len() {
+            return Err(CircuitError::LengthMismatch {
+                left: a.len(),
+                right: b.len(),
+            });
+        }
+        if a.is_empty() {
+            return Err(CircuitError::EmptyInput);
+        }
+
+        let mut acc = self.mul(a[0], b[0]);
+        for (&x, &y) in a.iter().zip(b).skip(1) {
+            let product = self.mul(x, y);
+            acc = self.add(acc, product);
+        }
+        Ok(acc)
+    }
+
+    /// 割り算ゲートを追加する。
+    ///
+    /// 新変数 `c` を確保して `c = a · b^{-1}` を計算し、制約 `(c) · (b) = (a)` を追加する。
+    /// 逆元は witness 計算にだけ使い、制約は掛け算 1 本で `c` を固定する。戻り値は `c`。
+    ///
+    /// `b` の値が 0 のときは panic する（`c · 0 = a` を満たす `c` は一意に決まらない）。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn div(&mut self, a: Variable, b: Variable) -> Variable {
+        // 値の計算（b = 0 なら変数を確保する前に panic する）
+        let val_a = self.assignments[a.0]
+            .as_ref()
+            .expect("variable a is unassigned");
+        let val_b = self.assignments[b.0]
+            .as_ref()
+            .expect("variable b is unassigned");
+        let val_c = val_a.try_div(val_b).expect("division by zero in div gate");
+
+        let c = self.alloc_variable();
+        self.assign(c, val_c);
+
+        // 制約： (c) * (b) = (a)
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(c, self.one());
+        let mut lc_b = LinearCombination::new();
+        lc_b.add_term(b, self.one());
+        let mut lc_c = LinearCombination::new();
+        lc_c.add_term(a, self.one());
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+
+    /// 逆元ゲートを追加する。
+    ///
+    /// 新変数 `inv` を確保して `inv = b^{-1}` を計算し、制約 `(inv) · (b) = (1)` を追加する。
+    /// 戻り値は `inv`。`b` の値が 0 のときは panic する。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn inverse(&mut self, b: Variable) -> Variable {
+        // 値の計算（b = 0 なら変数を確保する前に panic する）
+        let val_b = self.assignments[b.0]
+            .as_ref()
+            .expect("variable b is unassigned");
+        let val_inv = val_b.inverse().expect("inverse of zero in inverse gate");
+
+        let inv = self.alloc_variable();
+        self.assign(inv, val_inv);
+
+        // 制約： (inv) * (b) = 1
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(inv, self.one());
+        let mut lc_b = LinearCombination::new();
+        lc_b.add_term(b, self.one());
+        let mut lc_c = LinearCombination::new();
+        lc_c.add_term(CS_ONE, self.one());
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        inv
+    }
+
+    /// 変数 `v` が 0 でないことを強制する。
+    ///
+    /// hint 変数 `inv` を確保して `v^{-1}` を代入し、制約 `(v) · (inv) = (1)` を追加する。
+    /// 0 には逆元がないので、`v = 0` ならこの制約を満たす `inv` は存在しない。
+    ///
+    /// [`inverse`](Self::inverse) と違い `v = 0` でも panic しない。そのときは `inv` を
+    /// 未代入のまま残すので、[`try_generate_witness`](Self::try_generate_witness) が
+    /// [`WitnessError::Unassigned`] で witness 生成を失敗させる。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn assert_nonzero(&mut self, v: Variable) {
+        let val_inv = self.assignments[v.0]
+            .as_ref()
+            .expect("variable v is unassigned")
+            .inverse();
+
+        let inv = self.alloc_variable();
+        if let Some(val_inv) = val_inv {
+            self.assign(inv, val_inv);
+        }
+
+        // 制約： (v) * (inv) = 1
+        let lc_a = LinearCombination::from((v, self.one()));
+        let lc_b = LinearCombination::from((inv, self.one()));
+        let lc_c = LinearCombination::from((CS_ONE, self.one()));
+
+        self.enforce(lc_a, lc_b, lc_c);
+    }
+
+    /// 変数 `b` が 0 または 1 であることを強制する。
+    ///
+    /// 制約 `(b) · (b - 1) = 0` を追加する（C 側は空の線形結合 = 0）。
+    /// 新しい変数は確保しない。範囲チェックや選択ゲートの前提に使う。
+    pub fn enforce_boolean(&mut self, b: Variable) {
+        // 制約： (b) * (b - 1) = 0
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(b, self.one());
+
+        let mut lc_b = LinearCombination::new();
+        lc_b.add_term(b, self.one());
+        lc_b.add_term(CS_ONE, -self.one());
+
+        let lc_c = LinearCombination::new();
+
+        self.enforce(lc_a, lc_b, lc_c);
+    }
+
+    /// 条件選択ゲートを追加する: `out = cond ? a : b`。
+    ///
+    /// `cond` に [`enforce_boolean`](Self::enforce_boolean) を掛けたうえで、
+    /// `out = cond · a + (1 - cond) · b` を変形した制約 `(cond) · (a - b) = (out - b)`
+    /// を掛け算 1 本で追加する。戻り値は `out`。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn select(&mut self, cond: Variable, a: Variable, b: Variable) -> Variable {
+        self.enforce_boolean(cond);
+
+        let out = self.alloc_variable();
+
+        // 値の計算（cond が 1 なら a、それ以外なら b）
+        let val_cond = self.assignments[cond.0]
+            .as_ref()
+            .expect("variable cond is unassigned");
+        let selected = if val_cond.is_one() { a } else { b };
+        let val_out = self.assignments[selected.0]
+            .clone()
+            .expect("selected variable is unassigned");
+        self.assign(out, val_out);
+
+        // 制約： (cond) * (a - b) = (out - b)
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(cond, self.one());
+
+        let mut lc_b = LinearCombination::new();
+        lc_b.add_term(a, self.one());
+        lc_b.add_term(b, -self.one());
+
+        let mut lc_c = LinearCombination::new();
+        lc_c.add_term(out, self.one());
+        lc_c.add_term(b, -self.one());
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        out
+    }
+
+    /// 変数 `v` を下位から `n` 個のビット変数に分解し、`v < 2^n` を強制する。
+    ///
+    /// 各ビット `bit_i` を確保して [`enforce_boolean`](Self::enforce_boolean) を掛け、
+    /// 制約 `(Σ 2^i · bit_i) · 1 = (v)` を追加する。戻り値は `[bit_0, ..., bit_{n-1}]`。
+    /// 制約数は `n + 1`。
+    ///
+    /// 範囲チェックとして健全なのは `2^n < p` のときだけ（和が法で回り込まないため）。
+    ///
+    /// `v` が未代入なら [`WitnessError::Unassigned`]、値が `2^n` 以上なら
+    /// [`WitnessError::OutOfRange`] を返し、そのときは変数も制約も追加しない。
+    #[allow(clippy::wrong_self_convention)] // `to_*` だが回路に変数を追加するので &mut self
+    pub fn to_bits(&mut self, v: Variable, n: usize) -> Result<Vec<Variable>, WitnessError> {
+        let value = self.assignments[v.0]
+            .as_ref()
+            .ok_or(WitnessError::Unassigned(v))?
+            .value
+            .clone();
+        if value.bits() > n as u64 {
+            return Err(WitnessError::OutOfRange { var: v, bits: n });
+        }
+
+        let one = self.one();
+        let mut bits = Vec::with_capacity(n);
+        // 制約： (Σ 2^i · bit_i) * 1 = v
+        let mut lc_a = LinearCombination::new();
+        let zero = FieldElement::zero(&one.p);
+        let mut weight = one.clone();
+        for i in 0..n {
+            let bit = self.alloc_variable();
+            let bit_value = if value.bit(i as u64) {
+                one.clone()
+            } else {
+                zero.clone()
+            };
+            self.assign(bit, bit_value);
+            self.enforce_boolean(bit);
+
+            lc_a.add_term(bit, weight.clone());
+            weight = weight.double();
+            bits.push(bit);
+        }
+
+        let mut lc_b = LinearCombination::new();
+        lc_b.add_term(CS_ONE, one.clone());
+
+        let mut lc_c = LinearCombination::new();
+        lc_c.add_term(v, one);
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        Ok(bits)
+    }
+
+    /// 大小比較ガジェットを追加する: `a < b` なら 1、そうでなければ 0 の変数を返す。
+    ///
+    /// [`linear_combination`](Self::linear_combination) で `d = b − a − 1 + 2^n` を作り、
+    /// [`to_bits`](Self::to_bits) で `n + 1` ビットに分解して最上位ビット `bit_n` を返す。
+    /// `a, b < 2^n` なら `0 ≤ d < 2^{n+1}` で、`bit_n = 1 ⇔ d ≥ 2^n ⇔ b − a − 1 ≥ 0 ⇔ a < b`。
+    /// （`−1` を入れないと `a ≤ b` の判定になる。）制約数は `n + 3`。
+    ///
+    /// 健全性の前提: `a` と `b` がともに `n` ビットに収まり、かつ `2^{n+1} < p` であること。
+    /// このガジェットは `a`, `b` 自体の範囲は検査しないので、外部入力なら事前に
+    /// `to_bits(a, n)` などで制約しておくこと（範囲外だと `d` が法で回り込み、結果を偽れる）。
+    ///
+    /// `a` か `b` が未代入なら [`WitnessError::Unassigned`]、値が `n` ビットに収まらなければ
+    /// [`WitnessError::OutOfRange`] を返し、そのときは変数も制約も追加しない。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn less_than(
+        &mut self,
+        a: Variable,
+        b: Variable,
+        n_bits: usize,
+    ) -> Result<Variable, WitnessError> {
+        // 変数を確保する前に入力の範囲を確かめる（途中で失敗して制約系を中途半端に残さない）
+        for var in [a, b] {
+            let value = self.assignments[var.0]
+                .as_ref()
+                .ok_or(WitnessError::Unassigned(var))?;
+            if value.value.bits() > n_bits as u64 {
+                return Err(WitnessError::OutOfRange { var, bits: n_bits });
+            }
+        }
+
+        let one = self.one();
+        // 2^n - 1
+        let offset = &one.double().pow(n_bits as u64) - &one;
+        let d = self.linear_combination(&[(b, one.clone()), (a, -one)], offset);
+        // a, b < 2^n なら 0 ≤ d < 2^{n+1} なので分解は失敗しない
+        let bits = self.to_bits(d, n_bits + 1)?;
+        Ok(bits[n_bits])
+    }
+
+    /// MiMC 置換のガジェットを追加する: ラウンド `i` ごとに `x ↦ (x + k + c_i)^3`。
+    ///
+    /// ラウンド数は `round_constants.len()`。各ラウンドは
+    /// [`linear_combination`](Self::linear_combination) で `t = x + k + c_i` を作り
+    /// （制約 1 本）、[`pow`](Self::pow) で `t^3` を取る（制約 2 本）。戻り値は最終ラウンドの出力で、
+    /// 制約数は `3 · rounds`。ラウンド数 0 なら `input` をそのまま返す。
+    ///
+    /// `x ↦ x^3` が置換になるのは `gcd(3, p − 1) = 1` のときだけなので、そういう `p` で使うこと。
+    /// MiMC 暗号としては最後に鍵 `k` をもう一度足すが、ここでは置換部分だけを組む。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn mimc(
+        &mut self,
+        input: Variable,
+        key: Variable,
+        round_constants: &[FieldElement],
+    ) -> Variable {
+        let one = self.one();
+        let mut x = input;
+        for c in round_constants {
+            let t = self.linear_combination(&[(x, one.clone()), (key, one.clone())], c.clone());
+            x = self.pow(t, 3);
+        }
+        x
+    }
+
+    /// 論理積ゲートを追加する: `c = a AND b`。
+    ///
+    /// `a`, `b`, `c` に [`enforce_boolean`](Self::enforce_boolean) を掛け、
+    /// 制約 `(a) · (b) = (c)` を追加する。戻り値は `c`。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn and(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.alloc_bit_output(a, b, |x, y| x * y);
+
+        // 制約： (a) * (b) = (c)
+        let lc_a = LinearCombination::from((a, self.one()));
+        let lc_b = LinearCombination::from((b, self.one()));
+        let lc_c = LinearCombination::from((c, self.one()));
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+
+    /// 論理和ゲートを追加する: `c = a OR b = a + b - a·b`。
+    ///
+    /// `a`, `b`, `c` に boolean 制約を掛け、制約 `(a) · (b) = (a + b - c)` を追加する。
+    /// 戻り値は `c`。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn or(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.alloc_bit_output(a, b, |x, y| &(x + y) - &(x * y));
+
+        // 制約： (a) * (b) = (a + b - c)
+        let lc_a = LinearCombination::from((a, self.one()));
+        let lc_b = LinearCombination::from((b, self.one()));
+        let lc_c = LinearCombination::from((a, self.one()))
+            + (b, self.one()).into()
+            + (c, -self.one()).into();
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+
+    /// 排他的論理和ゲートを追加する: `c = a XOR b = a + b - 2·a·b`。
+    ///
+    /// `a`, `b`, `c` に boolean 制約を掛け、制約 `(2a) · (b) = (a + b - c)` を追加する。
+    /// 戻り値は `c`。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn xor(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.alloc_bit_output(a, b, |x, y| &(x + y) - &(x * y).double());
+
+        // 制約： (2a) * (b) = (a + b - c)
+        let lc_a = LinearCombination::from((a, self.one().double()));
+        let lc_b = LinearCombination::from((b, self.one()));
+        let lc_c = LinearCombination::from((a, self.one()))
+            + (b, self.one()).into()
+            + (c, -self.one()).into();
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+
+    /// ビット演算ゲートの共通部分。
+    ///
+    /// 入力 `a`, `b` に boolean 制約を掛け、出力 `c = op(a, b)` を確保・代入して
+    /// `c` にも boolean 制約を掛ける。ゲート本体の制約は呼び出し側が追加する。
+    fn alloc_bit_output(
+        &mut self,
+        a: Variable,
+        b: Variable,
+        op: impl Fn(&FieldElement, &FieldElement) -> FieldElement,
+    ) -> Variable {
+        self.enforce_boolean(a);
+        self.enforce_boolean(b);
+
+        let val_a = self.assignments[a.0]
+            .as_ref()
+            .expect("variable a is unassigned");
+        let val_b = self.assignments[b.0]
+            .as_ref()
+            .expect("variable b is unassigned");
+        let val_c = op(val_a, val_b);
+
+        let c = self.alloc_variable();
+        self.assign(c, val_c);
+        self.enforce_boolean(c);
+        c
+    }
+
+    /// 変数 `a` と `b` が等しいことを強制する。
+    ///
+    /// 制約 `(a - b) · 1 = 0` を追加する。新しい変数は確保しない。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn enforce_equal(&mut self, a: Variable, b: Variable) {
+        // 制約： (a - b) * 1 = 0
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(a, self.one());
+        lc_a.add_term(b, -self.one());
+
+        let mut lc_b = LinearCombination::new();
+        lc_b.add_term(CS_ONE, self.one());
+
+        self.enforce(lc_a, lc_b, LinearCombination::new());
+    }
+
+    /// 変数 `a` が定数 `constant` に等しいことを強制する。
+    ///
+    /// 制約 `(a - k · 1) · 1 = 0` を追加する。新しい変数は確保しない。
+    ///
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn enforce_equal_const(&mut self, a: Variable, constant: FieldElement) {
+        // 制約： (a - 1 * k) * 1 = 0
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(a, self.one());
+        lc_a.add_term(CS_ONE, -constant);
+
+        let mut lc_b = LinearCombination::new();
+        lc_b.add_term(CS_ONE, self.one());
+
+        self.enforce(lc_a, lc_b, LinearCombination::new());
+    }
+
+    /// 定数加算ゲートを追加する。
+    ///
+    /// 新変数 `c` を確保して `c = a + k` を計算し、
+    /// 制約 `(a + k · 1) · 1 = (c)` を追加する。戻り値は `c`。
+    ///
+    /// 現在は unit test からのみ呼ばれる（main の x³+5 デモは `enforce` で
+    /// 公開出力に接続するため）。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn add_const(&mut self, a: Variable, constant: FieldElement) -> Variable {
+        let c = self.alloc_variable();
+
+        // 値の計算
+        let val_a = self.assignments[a.0]
+            .as_ref()
+            .expect("variable a is unassigned");
+        self.assign(c, val_a + &constant);
+
+        // 制約： (a + 1 * k) * 1 = c
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(a, self.one());
+        lc_a.add_term(CS_ONE, constant);
+
+        let mut lc_b = LinearCombination::new();
+        lc_b.add_term(CS_ONE, self.one());
+
+        let mut lc_c = LinearCombination::new();
+        lc_c.add_term(c, self.one());
+
+        self.enforce(lc_a, lc_b, lc_c);
+
+        c
+    }
+}
+
+impl Default for ConstraintSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// main のデモと同じ `y = x^3 + 5` 回路を法 `p` の上で組む（各モジュールのテスト用）。
+///
+/// 変数は `CS_ONE, y, x, x^2, x^3` の 5 つ（`y` が公開入力、`x` は表示名 `"x"` の秘密入力）、
+/// 制約は `x·x = x^2`, `x^2·x = x^3`, `(x^3 + 5)·1 = y` の 3 本。
+/// `x = 3, y = 32` を代入済みなので、そのまま witness を取り出せる。
+#[cfg(test)]
+pub(crate) fn x3_plus5_circuit(p: impl IntoModulus) -> ConstraintSystem {
+    let one = FieldElement::one(p);
+    let fe = |v: u64| FieldElement::from_u64(v, &one.p);
+
+    let mut cs = ConstraintSystem::new();
+    cs.init_one(one.clone());
+    let y = cs.alloc_public_input();
+    cs.assign(y, fe(32));
+    let x = cs.alloc_named("x");
+    cs.assign(x, fe(3));
+    let x_cubed = cs.pow(x, 3);
+    let lc_a = LinearCombination::from((x_cubed, one.clone())) + (CS_ONE, fe(5)).into();
+    cs.enforce(
+        lc_a,
+        LinearCombination::from((CS_ONE, one.clone())),
+        LinearCombination::from((y, one)),
+    );
+    cs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: i64 = 7;
+
+    fn fe(v: i64) -> FieldElement {
+        FieldElement::new(v, P)
+    }
+
+    #[test]
+    fn alloc_variable_assigns_sequential_indices() {
+        let mut cs = ConstraintSystem::new();
+        let v0 = cs.alloc_variable();
+        let v1 = cs.alloc_variable();
+        let v2 = cs.alloc_variable();
+        assert_eq!(v0, Variable(0));
+        assert_eq!(v1, Variable(1));
+        assert_eq!(v2, Variable(2));
+        assert_eq!(cs.next_var_index, 3);
+        assert_eq!(cs.assignments.len(), 3);
+        assert!(cs.assignments.iter().all(|a| a.is_none()));
+    }
+
+    #[test]
+    fn init_one_sets_cs_one_to_one() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        assert_eq!(cs.assignments[CS_ONE.0], Some(fe(1)));
+        assert_eq!(cs.next_var_index, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn assign_out_of_bounds_variable_panics() {
+        let mut cs = ConstraintSystem::new();
+        cs.assign(Variable(5), fe(3));
+    }
+
+    #[test]
+    fn generate_witness_returns_assigned_values() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let a = cs.alloc_variable();
+        let b = cs.alloc_variable();
+        cs.assign(a, fe(2));
+        cs.assign(b, fe(3));
+        assert_eq!(cs.generate_witness(), vec![fe(1), fe(2), fe(3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "unassigned")]
+    fn generate_witness_panics_on_unassigned() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let _ = cs.alloc_variable(); // 未 assign のまま
+        cs.generate_witness();
+    }
+
+    #[test]
+    fn try_generate_witness_reports_unassigned_variable() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let a = cs.alloc_variable();
+        cs.assign(a, fe(2));
+        let missing = cs.alloc_variable(); // 未 assign のまま
+        let _also_missing = cs.alloc_variable();
+
+        // 最初の未代入変数が報告される
+        assert_eq!(
+            cs.try_generate_witness(),
+            Err(WitnessError::Unassigned(missing))
+        );
+        assert_eq!(missing, Variable(2));
+    }
+
+    #[test]
+    fn try_generate_witness_ok_when_fully_assigned() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let a = cs.alloc_variable();
+        cs.assign(a, fe(4));
+        assert_eq!(cs.try_generate_witness(), Ok(vec![fe(1), fe(4)]));
+    }
+
+    #[test]
+    fn accessors_match_demo_circuit() {
+        // main のデモと同じ y = x^3 + 5 回路: CS_ONE, y, x, x^2, x^3 の 5 変数・3 制約
+        let cs = x3_plus5_circuit(P);
+        assert_eq!(cs.num_constraints(), 3);
+        assert_eq!(cs.num_variables(), 5);
+        assert_eq!(cs.num_variables(), cs.generate_witness().len());
+    }
+
+    #[test]
+    fn mul_computes_value_and_adds_constraint() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let a = cs.alloc_variable();
+        let b = cs.alloc_variable();
+        cs.assign(a, fe(2));
+        cs.assign(b, fe(3));
+
+        let c = cs.mul(a, b);
+
+        // 2 * 3 ≡ 6 (mod 7)
+        assert_eq!(cs.assignments[c.0], Some(fe(6)));
+        assert_eq!(cs.constraints.len(), 1);
+
+        // 制約形: (a) * (b) = (c)
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms, vec![(a, fe(1))]);
+        assert_eq!(con.b.terms, vec![(b, fe(1))]);
+        assert_eq!(con.c.terms, vec![(c, fe(1))]);
+    }
+
+    #[test]
+    fn square_computes_value_and_adds_constraint() {
+        // F_17 で 4^2 = 16
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f17(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, f17(4));
+
+        let c = cs.square(x);
+
+        assert_eq!(cs.assignments[c.0], Some(f17(16)));
+        // 制約形: (x) * (x) = c
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms, vec![(x, f17(1))]);
+        assert_eq!(con.b.terms, vec![(x, f17(1))]);
+        assert_eq!(con.c.terms, vec![(c, f17(1))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn pow_computes_power_with_square_and_multiply() {
+        // F_17 で 3^3 = 27 ≡ 10
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f17(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, f17(3));
+
+        let y = cs.pow(x, 3);
+
+        assert_eq!(cs.assignments[y.0], Some(f17(10)));
+        assert_eq!(cs.constraints.len(), 2);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn pow_uses_fewer_constraints_than_linear_chain() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+
+        let y = cs.pow(x, 100);
+
+        // 100 = 0b1100100: 2 乗 6 回 + base 倍 2 回 = 8 本（素朴な連鎖なら 99 本）
+        assert_eq!(cs.constraints.len(), 8);
+        assert_eq!(cs.assignments[y.0], Some(fe(3).pow(100)));
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn pow_with_small_exponents_adds_no_constraints() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+
+        assert_eq!(cs.pow(x, 0), CS_ONE);
+        assert_eq!(cs.pow(x, 1), x);
+        assert!(cs.constraints.is_empty());
+    }
+
+    #[test]
+    fn add_computes_value_and_adds_constraint() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let a = cs.alloc_variable();
+        let b = cs.alloc_variable();
+        cs.assign(a, fe(5));
+        cs.assign(b, fe(4));
+
+        let c = cs.add(a, b);
+
+        // 5 + 4 = 9 ≡ 2 (mod 7)
+        assert_eq!(cs.assignments[c.0], Some(fe(2)));
+        assert_eq!(cs.constraints.len(), 1);
+
+        // 制約形: (a + b) * 1 = c
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms, vec![(a, fe(1)), (b, fe(1))]);
+        assert_eq!(con.b.terms, vec![(CS_ONE, fe(1))]);
+        assert_eq!(con.c.terms, vec![(c, fe(1))]);
+    }
+
+    #[test]
+    fn add_const_computes_value_and_adds_constraint() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let a = cs.alloc_variable();
+        cs.assign(a, fe(3));
+
+        let c = cs.add_const(a, fe(5));
+
+        // 3 + 5 = 8 ≡ 1 (mod 7)
+        assert_eq!(cs.assignments[c.0], Some(fe(1)));
+        assert_eq!(cs.constraints.len(), 1);
+
+        // 制約形: (a + 1·k) * 1 = c
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms, vec![(a, fe(1)), (CS_ONE, fe(5))]);
+        assert_eq!(con.b.terms, vec![(CS_ONE, fe(1))]);
+        assert_eq!(con.c.terms, vec![(c, fe(1))]);
+    }
+
+    #[test]
+    fn sub_computes_value_and_adds_constraint() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        let y = cs.alloc_variable();
+        cs.assign(x, fe(2));
+        cs.assign(y, fe(5));
+
+        let c = cs.sub(x, y);
+
+        // 2 - 5 = -3 ≡ 4 (mod 7)
+        assert_eq!(cs.assignments[c.0], Some(fe(4)));
+        assert_eq!(cs.constraints.len(), 1);
+
+        // 制約形: (x - y) * 1 = c
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms, vec![(x, fe(1)), (y, fe(-1))]);
+        assert_eq!(con.b.terms, vec![(CS_ONE, fe(1))]);
+        assert_eq!(con.c.terms, vec![(c, fe(1))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+
+        // c を書き換えると制約を満たさなくなる
+        cs.assign(c, fe(3));
+        assert!(!cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn mul_const_scales_variable_by_constant() {
+        // F_17 で 3 · x, x = 7 → 21 ≡ 4
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f17(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, f17(7));
+
+        let c = cs.mul_const(x, f17(3));
+
+        assert_eq!(cs.assignments[c.0], Some(f17(4)));
+        // 制約形: (3·x) * 1 = c
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms, vec![(x, f17(3))]);
+        assert_eq!(con.b.terms, vec![(CS_ONE, f17(1))]);
+        assert_eq!(con.c.terms, vec![(c, f17(1))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn inner_product_sums_pairwise_products() {
+        // F_17 で [1, 2, 3]·[4, 5, 6] = 32 ≡ 15
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f17(1));
+        let mut alloc = |v: i64| {
+            let var = cs.alloc_variable();
+            cs.assign(var, f17(v));
+            var
+        };
+        let a = [alloc(1), alloc(2), alloc(3)];
+        let b = [alloc(4), alloc(5), alloc(6)];
+
+        let c = cs.inner_product(&a, &b).unwrap();
+
+        assert_eq!(cs.assignments[c.0], Some(f17(15)));
+        // mul 3 本 + add 2 本
+        assert_eq!(cs.num_constraints(), 5);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn inner_product_rejects_mismatched_or_empty_slices() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(2));
+
+        assert_eq!(
+            cs.inner_product(&[x, x], &[x]),
+            Err(CircuitError::LengthMismatch { left: 2, right: 1 })
+        );
+        assert_eq!(cs.inner_product(&[], &[]), Err(CircuitError::EmptyInput));
+        assert_eq!(cs.num_constraints(), 0);
+    }
+
+    #[test]
+    fn sum_adds_many_variables_in_one_constraint() {
+        // F_17 で 1 + 2 + 3 + 4 = 10
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f17(1));
+        let vars: Vec<Variable> = (1..=4)
+            .map(|v| {
+                let var = cs.alloc_variable();
+                cs.assign(var, f17(v));
+                var
+            })
+            .collect();
+        let num_vars = cs.num_variables();
+
+        let c = cs.sum(&vars);
+
+        assert_eq!(cs.assignments[c.0], Some(f17(10)));
+        assert_eq!(cs.num_constraints(), 1);
+        assert_eq!(cs.num_variables(), num_vars + 1);
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms.len(), 4);
+        assert_eq!(con.b.terms, vec![(CS_ONE, f17(1))]);
+        assert_eq!(con.c.terms, vec![(c, f17(1))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn sum_of_empty_slice_is_pinned_to_zero() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+
+        let c = cs.sum(&[]);
+
+        assert_eq!(cs.assignments[c.0], Some(fe(0)));
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+        cs.assign(c, fe(1));
+        assert!(!cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn linear_combination_builds_weighted_sum() {
+        // F_17 で 2x + 3y + 1, x = 4, y = 5 → 8 + 15 + 1 = 24 ≡ 7
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f17(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, f17(4));
+        let y = cs.alloc_variable();
+        cs.assign(y, f17(5));
+
+        let c = cs.linear_combination(&[(x, f17(2)), (y, f17(3))], f17(1));
+
+        assert_eq!(cs.assignments[c.0], Some(f17(7)));
+        assert_eq!(cs.num_constraints(), 1);
+        // 制約形: (2x + 3y + 1·1) * 1 = c
+        let con = &cs.constraints[0];
+        assert_eq!(
+            con.a.terms,
+            vec![(x, f17(2)), (y, f17(3)), (CS_ONE, f17(1))]
+        );
+        assert_eq!(con.b.terms, vec![(CS_ONE, f17(1))]);
+        assert_eq!(con.c.terms, vec![(c, f17(1))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+
+        // c を書き換えると制約を満たさなくなる
+        cs.assign(c, f17(8));
+        assert!(!cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn alloc_const_pins_value_with_constraint() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+
+        let k = cs.alloc_const(fe(5));
+
+        assert_eq!(cs.assignments[k.0], Some(fe(5)));
+        assert_eq!(cs.num_constraints(), 1);
+        // 制約形: (k) * 1 = 5·1
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms, vec![(k, fe(1))]);
+        assert_eq!(con.b.terms, vec![(CS_ONE, fe(1))]);
+        assert_eq!(con.c.terms, vec![(CS_ONE, fe(5))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+
+        // 定数の配線は通常の変数としてゲートに渡せる
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        let y = cs.mul(x, k);
+        assert_eq!(cs.assignments[y.0], Some(fe(1))); // 15 ≡ 1 (mod 7)
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+
+        // 値を書き換えると制約を満たさなくなる
+        cs.assign(k, fe(6));
+        assert!(!cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn assert_nonzero_accepts_nonzero_witness() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let v = cs.alloc_variable();
+        cs.assign(v, fe(3));
+
+        cs.assert_nonzero(v);
+
+        // hint は 3^{-1} = 5 (mod 7)
+        assert_eq!(cs.assignments[v.0 + 1], Some(fe(5)));
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
     }
 
     #[test]
-    fn alloc_variable_assigns_sequential_indices() {
+    fn assert_nonzero_rejects_zero_during_witness_generation() {
         let mut cs = ConstraintSystem::new();
-        let v0 = cs.alloc_variable();
-        let v1 = cs.alloc_variable();
-        let v2 = cs.alloc_variable();
-        assert_eq!(v0, Variable(0));
-        assert_eq!(v1, Variable(1));
-        assert_eq!(v2, Variable(2));
-        assert_eq!(cs.next_var_index, 3);
-        assert_eq!(cs.assignments.len(), 3);
-        assert!(cs.assignments.iter().all(|a| a.is_none()));
+        cs.init_one(fe(1));
+        let v = cs.alloc_variable();
+        cs.assign(v, fe(0));
+
+        cs.assert_nonzero(v);
+
+        let hint = Variable(v.0 + 1);
+        assert_eq!(
+            cs.try_generate_witness(),
+            Err(WitnessError::Unassigned(hint))
+        );
+        // hint にどんな値を入れても v · inv = 1 は満たせない
+        for k in 0..P {
+            cs.assign(hint, fe(k));
+            assert!(!cs.is_satisfied(&cs.generate_witness()));
+        }
+    }
+
+    /// F_97 上で `a < b` を 4 ビット比較し、充足を確かめてから結果の値を返す。
+    fn less_than_result(a: i64, b: i64) -> FieldElement {
+        let f97 = |v: i64| FieldElement::new(v, 97);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f97(1));
+        let va = cs.alloc_variable();
+        cs.assign(va, f97(a));
+        let vb = cs.alloc_variable();
+        cs.assign(vb, f97(b));
+
+        let lt = cs.less_than(va, vb, 4).unwrap();
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+        cs.assignments[lt.0].clone().unwrap()
     }
 
     #[test]
-    fn init_one_sets_cs_one_to_one() {
+    fn less_than_compares_bounded_values() {
+        let f97 = |v: i64| FieldElement::new(v, 97);
+        assert_eq!(less_than_result(3, 5), f97(1));
+        assert_eq!(less_than_result(5, 3), f97(0));
+        assert_eq!(less_than_result(4, 4), f97(0));
+        assert_eq!(less_than_result(0, 15), f97(1));
+        assert_eq!(less_than_result(15, 0), f97(0));
+    }
+
+    #[test]
+    fn less_than_rejects_out_of_range_inputs() {
+        // a = 40 は 4 ビットに収まらない（そのままだと d = 0 − 40 − 1 + 16 が法で回り込む）
+        let f97 = |v: i64| FieldElement::new(v, 97);
         let mut cs = ConstraintSystem::new();
-        cs.init_one(fe(1));
-        assert_eq!(cs.assignments[CS_ONE.0], Some(fe(1)));
-        assert_eq!(cs.next_var_index, 1);
+        cs.init_one(f97(1));
+        let a = cs.alloc_variable();
+        cs.assign(a, f97(40));
+        let b = cs.alloc_variable();
+        cs.assign(b, f97(0));
+        let (num_variables, num_constraints) = (cs.num_variables(), cs.num_constraints());
+
+        assert_eq!(
+            cs.less_than(a, b, 4),
+            Err(WitnessError::OutOfRange { var: a, bits: 4 })
+        );
+        assert_eq!(
+            cs.less_than(b, a, 4),
+            Err(WitnessError::OutOfRange { var: a, bits: 4 })
+        );
+        // 失敗したときは変数も制約も増えない
+        assert_eq!(cs.num_variables(), num_variables);
+        assert_eq!(cs.num_constraints(), num_constraints);
+    }
+
+    /// 回路を使わない MiMC 置換の参照実装。
+    fn mimc_reference(
+        input: &FieldElement,
+        key: &FieldElement,
+        round_constants: &[FieldElement],
+    ) -> FieldElement {
+        round_constants.iter().fold(input.clone(), |x, c| {
+            let t = &(&x + key) + c;
+            &(&t * &t) * &t
+        })
     }
 
     #[test]
-    #[should_panic(expected = "out of bounds")]
-    fn assign_out_of_bounds_variable_panics() {
+    fn mimc_matches_reference_implementation() {
+        // p = 101 は gcd(3, 100) = 1 なので x^3 が置換になる
+        let f101 = |v: i64| FieldElement::new(v, 101);
+        let round_constants: Vec<FieldElement> = [0, 7, 42, 13, 99, 58].map(f101).to_vec();
+
         let mut cs = ConstraintSystem::new();
-        cs.assign(Variable(5), fe(3));
+        cs.init_one(f101(1));
+        let input = cs.alloc_variable();
+        cs.assign(input, f101(17));
+        let key = cs.alloc_variable();
+        cs.assign(key, f101(5));
+
+        let out = cs.mimc(input, key, &round_constants);
+
+        let expected = mimc_reference(&f101(17), &f101(5), &round_constants);
+        assert_eq!(cs.assignments[out.0], Some(expected));
+        assert_eq!(cs.num_constraints(), 3 * round_constants.len());
+        assert!(cs.is_satisfied(&cs.generate_witness()));
     }
 
     #[test]
-    fn generate_witness_returns_assigned_values() {
+    fn mimc_with_no_rounds_returns_input() {
         let mut cs = ConstraintSystem::new();
         cs.init_one(fe(1));
+        let input = cs.alloc_variable();
+        cs.assign(input, fe(3));
+        let key = cs.alloc_variable();
+        cs.assign(key, fe(4));
+
+        assert_eq!(cs.mimc(input, key, &[]), input);
+        assert_eq!(cs.num_constraints(), 0);
+    }
+
+    #[test]
+    fn div_computes_quotient_and_pins_it_with_constraint() {
+        // F_17 で 6 / 3 = 2
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f17(1));
         let a = cs.alloc_variable();
         let b = cs.alloc_variable();
-        cs.assign(a, fe(2));
-        cs.assign(b, fe(3));
-        assert_eq!(cs.generate_witness(), vec![fe(1), fe(2), fe(3)]);
+        cs.assign(a, f17(6));
+        cs.assign(b, f17(3));
+
+        let c = cs.div(a, b);
+
+        assert_eq!(cs.assignments[c.0], Some(f17(2)));
+        // 制約形: (c) * (b) = (a)
+        let con = &cs.constraints[0];
+        assert_eq!(con.a.terms, vec![(c, f17(1))]);
+        assert_eq!(con.b.terms, vec![(b, f17(1))]);
+        assert_eq!(con.c.terms, vec![(a, f17(1))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+
+        // 改ざんした c は制約で弾かれる
+        cs.assign(c, f17(5));
+        assert!(!cs.is_satisfied(&cs.generate_witness()));
     }
 
     #[test]
-    #[should_panic(expected = "unassigned")]
-    fn generate_witness_panics_on_unassigned() {
+    #[should_panic(expected = "division by zero")]
+    fn div_by_zero_witness_panics() {
         let mut cs = ConstraintSystem::new();
         cs.init_one(fe(1));
-        let _ = cs.alloc_variable(); // 未 assign のまま
-        cs.generate_witness();
+        let a = cs.alloc_variable();
+        let b = cs.alloc_variable();
+        cs.assign(a, fe(3));
+        cs.assign(b, fe(0));
+        cs.div(a, b);
     }
 
     #[test]
-    fn mul_computes_value_and_adds_constraint() {
+    fn inverse_assigns_field_inverse_and_satisfies_constraint() {
         let mut cs = ConstraintSystem::new();
         cs.init_one(fe(1));
-        let a = cs.alloc_variable();
         let b = cs.alloc_variable();
-        cs.assign(a, fe(2));
         cs.assign(b, fe(3));
 
-        let c = cs.mul(a, b);
-
-        // 2 * 3 ≡ 6 (mod 7)
-        assert_eq!(cs.assignments[c.0], Some(fe(6)));
-        assert_eq!(cs.constraints.len(), 1);
+        let inv = cs.inverse(b);
 
-        // 制約形: (a) * (b) = (c)
+        // 3 · 5 = 15 ≡ 1 (mod 7)
+        assert_eq!(cs.assignments[inv.0], fe(3).inverse());
+        assert_eq!(cs.assignments[inv.0], Some(fe(5)));
+        // 制約形: (inv) * (b) = 1
         let con = &cs.constraints[0];
-        assert_eq!(con.a.terms, vec![(a, fe(1))]);
+        assert_eq!(con.a.terms, vec![(inv, fe(1))]);
         assert_eq!(con.b.terms, vec![(b, fe(1))]);
-        assert_eq!(con.c.terms, vec![(c, fe(1))]);
+        assert_eq!(con.c.terms, vec![(CS_ONE, fe(1))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
     }
 
     #[test]
-    fn add_computes_value_and_adds_constraint() {
+    #[should_panic(expected = "inverse of zero")]
+    fn inverse_of_zero_witness_panics() {
         let mut cs = ConstraintSystem::new();
         cs.init_one(fe(1));
-        let a = cs.alloc_variable();
         let b = cs.alloc_variable();
-        cs.assign(a, fe(5));
-        cs.assign(b, fe(4));
+        cs.assign(b, fe(0));
+        cs.inverse(b);
+    }
 
-        let c = cs.add(a, b);
+    #[test]
+    fn enforce_boolean_accepts_only_zero_and_one() {
+        for (value, expected) in [(0, true), (1, true), (2, false)] {
+            let mut cs = ConstraintSystem::new();
+            cs.init_one(fe(1));
+            let b = cs.alloc_variable();
+            cs.assign(b, fe(value));
+
+            cs.enforce_boolean(b);
+
+            // 変数は増えず、制約が 1 本だけ増える
+            assert_eq!(cs.next_var_index, 2);
+            assert_eq!(cs.constraints.len(), 1);
+            let witness = cs.generate_witness();
+            assert_eq!(cs.is_satisfied(&witness), expected, "b = {}", value);
+        }
+    }
 
-        // 5 + 4 = 9 ≡ 2 (mod 7)
-        assert_eq!(cs.assignments[c.0], Some(fe(2)));
-        assert_eq!(cs.constraints.len(), 1);
+    #[test]
+    fn select_picks_a_or_b_by_condition() {
+        for (cond_val, expected) in [(1, 4), (0, 6)] {
+            let mut cs = ConstraintSystem::new();
+            cs.init_one(fe(1));
+            let cond = cs.alloc_variable();
+            let a = cs.alloc_variable();
+            let b = cs.alloc_variable();
+            cs.assign(cond, fe(cond_val));
+            cs.assign(a, fe(4));
+            cs.assign(b, fe(6));
+
+            let out = cs.select(cond, a, b);
+
+            assert_eq!(cs.assignments[out.0], Some(fe(expected)));
+            // enforce_boolean + 選択の 2 本
+            assert_eq!(cs.constraints.len(), 2);
+            assert!(cs.is_satisfied(&cs.generate_witness()));
+
+            // 選ばれなかった側の値にすり替えると失敗する
+            let mut witness = cs.generate_witness();
+            witness[out.0] = fe(if cond_val == 1 { 6 } else { 4 });
+            assert!(!cs.is_satisfied(&witness));
+        }
+    }
 
-        // 制約形: (a + b) * 1 = c
-        let con = &cs.constraints[0];
-        assert_eq!(con.a.terms, vec![(a, fe(1)), (b, fe(1))]);
-        assert_eq!(con.b.terms, vec![(CS_ONE, fe(1))]);
-        assert_eq!(con.c.terms, vec![(c, fe(1))]);
+    #[test]
+    fn to_bits_decomposes_value_and_satisfies() {
+        // 2^4 < 97 なので 4 ビットの範囲チェックとして健全
+        let f97 = |v: i64| FieldElement::new(v, 97);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f97(1));
+        let v = cs.alloc_variable();
+        cs.assign(v, f97(13));
+
+        let bits = cs.to_bits(v, 4).unwrap();
+
+        // 13 = 0b1101（下位から 1, 0, 1, 1）
+        let values: Vec<_> = bits.iter().map(|b| cs.assignments[b.0].clone()).collect();
+        assert_eq!(
+            values,
+            vec![Some(f97(1)), Some(f97(0)), Some(f97(1)), Some(f97(1))]
+        );
+        // boolean 4 本 + 和 1 本
+        assert_eq!(cs.constraints.len(), 5);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+
+        // ビットを 1 つ書き換えると和が合わなくなる
+        let mut witness = cs.generate_witness();
+        witness[bits[1].0] = f97(1);
+        assert!(!cs.is_satisfied(&witness));
     }
 
     #[test]
-    fn add_const_computes_value_and_adds_constraint() {
+    fn to_bits_rejects_value_out_of_range() {
+        let f97 = |v: i64| FieldElement::new(v, 97);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f97(1));
+        let v = cs.alloc_variable();
+        cs.assign(v, f97(16));
+
+        assert_eq!(
+            cs.to_bits(v, 4),
+            Err(WitnessError::OutOfRange { var: v, bits: 4 })
+        );
+        // 失敗時は何も追加しない
+        assert_eq!(cs.next_var_index, 2);
+        assert!(cs.constraints.is_empty());
+    }
+
+    #[test]
+    fn bit_gates_match_truth_tables() {
+        type Gate = fn(&mut ConstraintSystem, Variable, Variable) -> Variable;
+        // (名前, ゲート, 真理値 [00, 01, 10, 11])
+        let gates: [(&str, Gate, [i64; 4]); 3] = [
+            ("and", ConstraintSystem::and, [0, 0, 0, 1]),
+            ("or", ConstraintSystem::or, [0, 1, 1, 1]),
+            ("xor", ConstraintSystem::xor, [0, 1, 1, 0]),
+        ];
+        for (name, gate, table) in gates {
+            for (i, expected) in table.iter().enumerate() {
+                let (a_val, b_val) = ((i >> 1) as i64, (i & 1) as i64);
+                let mut cs = ConstraintSystem::new();
+                cs.init_one(fe(1));
+                let a = cs.alloc_variable();
+                let b = cs.alloc_variable();
+                cs.assign(a, fe(a_val));
+                cs.assign(b, fe(b_val));
+
+                let c = gate(&mut cs, a, b);
+
+                let case = format!("{}({}, {})", name, a_val, b_val);
+                let witness = cs.generate_witness();
+                assert_eq!(witness[c.0], fe(*expected), "{}", case);
+                assert!(cs.is_satisfied(&witness), "{}", case);
+
+                // 出力を反転させると失敗する
+                let mut tampered = witness.clone();
+                tampered[c.0] = fe(1 - expected);
+                assert!(!cs.is_satisfied(&tampered), "{}", case);
+            }
+        }
+    }
+
+    #[test]
+    fn bit_gates_reject_non_boolean_inputs() {
         let mut cs = ConstraintSystem::new();
         cs.init_one(fe(1));
         let a = cs.alloc_variable();
-        cs.assign(a, fe(3));
+        let b = cs.alloc_variable();
+        cs.assign(a, fe(2));
+        cs.assign(b, fe(1));
+        let _c = cs.and(a, b);
+        assert!(!cs.is_satisfied(&cs.generate_witness()));
+    }
 
-        let c = cs.add_const(a, fe(5));
+    #[test]
+    fn enforce_equal_checks_two_variables() {
+        for (x_val, y_val, expected) in [(3, 3, true), (3, 4, false)] {
+            let mut cs = ConstraintSystem::new();
+            cs.init_one(fe(1));
+            let x = cs.alloc_variable();
+            let y = cs.alloc_variable();
+            cs.assign(x, fe(x_val));
+            cs.assign(y, fe(y_val));
+
+            cs.enforce_equal(x, y);
+
+            assert_eq!(cs.next_var_index, 3);
+            assert_eq!(cs.is_satisfied(&cs.generate_witness()), expected);
+        }
+    }
 
-        // 3 + 5 = 8 ≡ 1 (mod 7)
-        assert_eq!(cs.assignments[c.0], Some(fe(1)));
-        assert_eq!(cs.constraints.len(), 1);
+    #[test]
+    fn enforce_equal_const_checks_against_constant() {
+        for (x_val, expected) in [(5, true), (6, false)] {
+            let mut cs = ConstraintSystem::new();
+            cs.init_one(fe(1));
+            let x = cs.alloc_variable();
+            cs.assign(x, fe(x_val));
 
-        // 制約形: (a + 1·k) * 1 = c
-        let con = &cs.constraints[0];
-        assert_eq!(con.a.terms, vec![(a, fe(1)), (CS_ONE, fe(5))]);
-        assert_eq!(con.b.terms, vec![(CS_ONE, fe(1))]);
-        assert_eq!(con.c.terms, vec![(c, fe(1))]);
+            cs.enforce_equal_const(x, fe(5));
+
+            assert_eq!(cs.next_var_index, 2);
+            assert_eq!(cs.is_satisfied(&cs.generate_witness()), expected);
+        }
+    }
+
+    #[test]
+    fn is_satisfied_accepts_valid_and_rejects_tampered_witness() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        let y = cs.mul(x, x);
+        let _z = cs.add(y, x);
+
+        let mut witness = cs.generate_witness();
+        assert!(cs.is_satisfied(&witness));
+
+        // y = x^2 を壊す
+        witness[y.0] = fe(1);
+        assert!(!cs.is_satisfied(&witness));
+    }
+
+    #[test]
+    fn is_satisfied_rejects_wrong_length_witness() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        let _y = cs.mul(x, x);
+        assert!(!cs.is_satisfied(&[]));
+        assert!(!cs.is_satisfied(&[fe(1), fe(3)]));
+    }
+
+    #[test]
+    fn evaluate_lc_sums_weighted_terms() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        let witness = cs.generate_witness();
+
+        // 2x + 5 = 11 ≡ 4 (mod 7)
+        let mut lc = LinearCombination::new();
+        lc.add_term(x, fe(2));
+        lc.add_term(CS_ONE, fe(5));
+        assert_eq!(cs.evaluate_lc(&lc, &witness), fe(4));
+        assert_eq!(cs.evaluate_lc(&LinearCombination::new(), &witness), fe(0));
+    }
+
+    #[test]
+    fn linear_combination_add_merges_and_scale_evaluates() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        let y = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        cs.assign(y, fe(4));
+        let witness = cs.generate_witness();
+
+        // lc_a = x + 2y, lc_b = 3x + 5
+        let lc_a = LinearCombination::from((x, fe(1))) + (y, fe(2)).into();
+        let lc_b = LinearCombination::from((x, fe(3))) + (CS_ONE, fe(5)).into();
+
+        let sum = lc_a + lc_b;
+        // x の項はまとめられて 4x + 2y + 5
+        assert_eq!(sum.terms, vec![(x, fe(4)), (y, fe(2)), (CS_ONE, fe(5))]);
+
+        // 2 · (4·3 + 2·4 + 5) = 50 ≡ 1 (mod 7)
+        let scaled = sum.scale(&fe(2));
+        assert_eq!(cs.evaluate_lc(&scaled, &witness), fe(1));
+    }
+
+    #[test]
+    fn enforce_lc_reproduces_add_const_constraint() {
+        // add_const と同じ (a + 5·1) * 1 = c を整数係数で書く
+        let mut expected = ConstraintSystem::new();
+        expected.init_one(fe(1));
+        let a = expected.alloc_variable();
+        expected.assign(a, fe(3));
+        let c = expected.add_const(a, fe(5));
+
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let a2 = cs.alloc_variable();
+        let c2 = cs.alloc_variable();
+        cs.assign(a2, fe(3));
+        cs.assign(c2, fe(1));
+        cs.enforce_lc((&[(a2, 1)], 5), (&[], 1), (&[(c2, 1)], 0));
+
+        assert_eq!((a2, c2), (a, c));
+        let (got, want) = (&cs.constraints[0], &expected.constraints[0]);
+        assert_eq!(got.a.terms, want.a.terms);
+        assert_eq!(got.b.terms, want.b.terms);
+        assert_eq!(got.c.terms, want.c.terms);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn enforce_lc_reduces_negative_coefficients() {
+        // (x - 2y) * 1 = -1 を x = 3, y = 2 で満たす
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        let y = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        cs.assign(y, fe(2));
+        cs.enforce_lc((&[(x, 1), (y, -2)], 0), (&[], 1), (&[], -1));
+
+        assert_eq!(cs.constraints[0].a.terms, vec![(x, fe(1)), (y, fe(5))]);
+        assert_eq!(cs.constraints[0].c.terms, vec![(CS_ONE, fe(6))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn linear_combination_from_variable_has_coefficient_one() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        let y = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        cs.assign(y, fe(2));
+        let x_squared = cs.alloc_variable();
+        cs.assign(x_squared, fe(2)); // 9 ≡ 2
+        let witness = cs.generate_witness();
+
+        // 法を持つ項と足すと係数 1 が確定する
+        let lc = LinearCombination::from(x) + (CS_ONE, fe(5)).into();
+        assert_eq!(lc.terms, vec![(x, fe(1)), (CS_ONE, fe(5))]);
+
+        // 変数だけの和は評価・scale の時点で確定し、同じ変数はまとめられる
+        let sum = LinearCombination::from(x) + y.into() + x.into();
+        assert_eq!(cs.evaluate_lc(&sum, &witness), fe(1)); // 2·3 + 2 = 8 ≡ 1
+        assert_eq!(sum.scale(&fe(3)).terms, vec![(x, fe(6)), (y, fe(3))]);
+
+        // enforce では制約系の法で確定する
+        cs.enforce(x.into(), x.into(), x_squared.into());
+        assert_eq!(cs.constraints[0].a.terms, vec![(x, fe(1))]);
+        assert!(cs.is_satisfied(&witness));
     }
 
     #[test]
@@ -481,6 +2478,35 @@ mod tests {
         assert_eq!(cs.next_var_index, 4);
     }
 
+    #[test]
+    fn public_inputs_come_first_and_exclude_cs_one() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let p0 = cs.alloc_public_input();
+        let p1 = cs.alloc_public_input();
+        let s0 = cs.alloc_variable();
+        cs.assign(p0, fe(4));
+        cs.assign(p1, fe(5));
+        cs.assign(s0, fe(6));
+
+        // public のインデックスは private より前
+        assert!(p0.0 < s0.0 && p1.0 < s0.0);
+
+        let witness = cs.generate_witness();
+        assert_eq!(witness, vec![fe(1), fe(4), fe(5), fe(6)]);
+        assert_eq!(cs.public_inputs(&witness), &[fe(4), fe(5)]);
+    }
+
+    #[test]
+    fn public_inputs_empty_without_public_variables() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let s0 = cs.alloc_variable();
+        cs.assign(s0, fe(2));
+        let witness = cs.generate_witness();
+        assert!(cs.public_inputs(&witness).is_empty());
+    }
+
     #[test]
     fn circuit_without_public_inputs_has_one_public_var() {
         // public を一切使わない回路では CS_ONE だけが public (l = 0)
@@ -509,4 +2535,227 @@ mod tests {
         let mut cs = ConstraintSystem::new();
         cs.alloc_public_input();
     }
+
+    #[test]
+    fn to_matrices_places_demo_circuit_coefficients() {
+        // y = x³ + 5（変数: CS_ONE, y, x, x², x³）
+        let cs = x3_plus5_circuit(P);
+
+        let (a, b, c) = cs.to_matrices();
+        assert_eq!(a.len(), 3);
+        assert!(a.iter().chain(&b).chain(&c).all(|row| row.len() == 5));
+
+        // 非ゼロ成分の位置 (制約, 変数, 係数) を列挙する
+        let nonzeros = |m: &DenseMatrix| -> Vec<(usize, usize, FieldElement)> {
+            let mut out = Vec::new();
+            for (j, row) in m.iter().enumerate() {
+                for (i, v) in row.iter().enumerate() {
+                    if !v.is_zero() {
+                        out.push((j, i, v.clone()));
+                    }
+                }
+            }
+            out
+        };
+        // 制約 0: x · x = x² / 制約 1: x² · x = x³ / 制約 2: (x³ + 5) · 1 = y
+        assert_eq!(
+            nonzeros(&a),
+            vec![(0, 2, fe(1)), (1, 3, fe(1)), (2, 0, fe(5)), (2, 4, fe(1))]
+        );
+        assert_eq!(
+            nonzeros(&b),
+            vec![(0, 2, fe(1)), (1, 2, fe(1)), (2, 0, fe(1))]
+        );
+        assert_eq!(
+            nonzeros(&c),
+            vec![(0, 3, fe(1)), (1, 4, fe(1)), (2, 1, fe(1))]
+        );
+    }
+
+    #[test]
+    fn eliminate_unused_drops_stray_variables() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let y = cs.alloc_public_input();
+        cs.assign(y, fe(2));
+        let stray = cs.alloc_variable();
+        cs.assign(stray, fe(6));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        let x2 = cs.mul(x, x);
+        let out = cs.add(x2, y);
+        assert_eq!(cs.num_variables(), 6);
+
+        let mapping = cs.eliminate_unused();
+
+        assert_eq!(cs.num_variables(), 5);
+        assert_eq!(cs.num_public_variables, 2);
+        assert!(!mapping.contains_key(&stray));
+        assert_eq!(mapping[&CS_ONE], CS_ONE);
+        assert_eq!(mapping[&y], y);
+        assert_eq!(mapping[&x], Variable(2));
+        assert_eq!(mapping[&out], Variable(4));
+        // 3^2 + 2 = 11 ≡ 4 (mod 7)
+        assert_eq!(cs.assignments[mapping[&out].0], Some(fe(4)));
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn eliminate_unused_keeps_unreferenced_public_inputs() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let y = cs.alloc_public_input();
+        cs.assign(y, fe(2));
+
+        let mapping = cs.eliminate_unused();
+
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(cs.num_variables(), 2);
+        assert_eq!(cs.public_inputs(&cs.generate_witness()), &[fe(2)]);
+    }
+
+    #[test]
+    fn deduplicate_constraints_removes_exact_duplicates() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        let x2 = cs.mul(x, x);
+        cs.constraints.push(cs.constraints[0].clone());
+        assert_eq!(cs.num_constraints(), 2);
+
+        cs.deduplicate_constraints();
+
+        assert_eq!(cs.num_constraints(), 1);
+        assert_eq!(cs.constraints[0].c.terms, vec![(x2, fe(1))]);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn deduplicate_constraints_compares_normalized_terms() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        let y = cs.alloc_variable();
+        cs.assign(x, fe(2));
+        cs.assign(y, fe(3));
+        // (x + y) · 1 = 5 と、項の順序と 0 係数の項（7 ≡ 0）だけが違う (y + x + 7x) · 1 = 5
+        cs.enforce_lc((&[(x, 1), (y, 1)], 0), (&[], 1), (&[], 5));
+        cs.enforce_lc((&[(y, 1), (x, 1), (x, 7)], 0), (&[], 1), (&[], 5));
+        // 係数の違う制約は残る
+        cs.enforce_lc((&[(x, 1), (y, 2)], 0), (&[], 1), (&[], 1));
+
+        cs.deduplicate_constraints();
+
+        assert_eq!(cs.num_constraints(), 2);
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn format_constraints_renders_demo_circuit_with_names() {
+        // y = x^3 + 5（x = 3, y = 32 ≡ 4 mod 7）
+        let cs = x3_plus5_circuit(P);
+
+        assert_eq!(
+            cs.format_constraints(),
+            "(x) * (x) = v3\n\
+             (v3) * (x) = v4\n\
+             (v4 + 5) * (1) = v1\n"
+        );
+    }
+
+    #[test]
+    fn format_constraints_shows_coefficients_and_empty_lc() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let a = cs.alloc_named("a");
+        let b = cs.alloc_named("b");
+        cs.enforce_lc((&[(a, 2), (b, -1)], 0), (&[], 1), (&[], 0));
+
+        assert_eq!(cs.format_constraints(), "(2·a + 6·b) * (1) = 0\n");
+    }
+
+    #[test]
+    fn eliminate_unused_keeps_names_in_sync() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let _stray = cs.alloc_named("stray");
+        let x = cs.alloc_named("x");
+        cs.assign(x, fe(2));
+        cs.enforce_boolean(x);
+
+        cs.eliminate_unused();
+
+        assert_eq!(cs.names, vec![None, Some("x".to_string())]);
+        assert_eq!(cs.format_constraints(), "(x) * (x + 6) = 0\n");
+    }
+
+    #[test]
+    fn solve_witness_reuses_circuit_for_many_inputs() {
+        // F_17 で y = x^3 + 5 を一度だけ組み、x を差し替えて解き直す
+        let f17 = |v: i64| FieldElement::new(v, 17);
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(f17(1));
+        let y = cs.alloc_public_input();
+        let x = cs.alloc_variable();
+        let read = |w: &[Option<FieldElement>], v: Variable| w[v.0].clone().unwrap();
+        let x2 = cs.alloc_lazy(Box::new(move |w| read(w, x).square()));
+        let x3 = cs.alloc_lazy(Box::new(move |w| &read(w, x2) * &read(w, x)));
+        cs.assign_with(y, Box::new(move |w| &read(w, x3) + &f17(5)));
+        cs.enforce_lc((&[(x, 1)], 0), (&[(x, 1)], 0), (&[(x2, 1)], 0));
+        cs.enforce_lc((&[(x2, 1)], 0), (&[(x, 1)], 0), (&[(x3, 1)], 0));
+        cs.enforce_lc((&[(x3, 1)], 5), (&[], 1), (&[(y, 1)], 0));
+        let num_constraints = cs.num_constraints();
+
+        // x = 2: 8 + 5 = 13
+        cs.assign(x, f17(2));
+        cs.solve_witness();
+        let witness = cs.generate_witness();
+        assert_eq!(cs.public_inputs(&witness), &[f17(13)]);
+        assert!(cs.is_satisfied(&witness));
+
+        // x = 4: 64 + 5 = 69 ≡ 1、x2 = 16
+        cs.assign(x, f17(4));
+        cs.solve_witness();
+        let witness = cs.generate_witness();
+        assert_eq!(witness[x2.0], f17(16));
+        assert_eq!(cs.public_inputs(&witness), &[f17(1)]);
+        assert!(cs.is_satisfied(&witness));
+        assert_eq!(cs.num_constraints(), num_constraints);
+    }
+
+    #[test]
+    fn solve_witness_leaves_lazy_variables_unassigned_until_called() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(3));
+        let double = cs.alloc_lazy(Box::new(move |w| w[x.0].clone().unwrap().double()));
+
+        assert_eq!(
+            cs.try_generate_witness(),
+            Err(WitnessError::Unassigned(double))
+        );
+        cs.solve_witness();
+        assert_eq!(cs.assignments[double.0], Some(fe(6)));
+    }
+
+    #[test]
+    fn to_matrices_sums_duplicate_terms() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(2));
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(x, fe(1));
+        lc_a.add_term(x, fe(2));
+        cs.enforce(
+            lc_a,
+            LinearCombination::from((CS_ONE, fe(1))),
+            LinearCombination::from((CS_ONE, fe(6))),
+        );
+
+        let (a, _, _) = cs.to_matrices();
+        assert_eq!(a, vec![vec![fe(0), fe(3)]]);
+    }
 }
@@ -7,13 +7,19 @@
 //! - [`ToxicWaste`]: Groth16 の秘密値 α, β, γ, δ, τ
 //! - [`QapFr`]: Fr 変換済みの QAP 多項式（setup/prover が τ 評価に使う）
 //! - [`ProvingKey`] / [`VerifyingKey`]: proving key / verifying key の 2 本立て
+//! - [`SetupParams`] / [`FieldToxicWaste`]: 自作体の上での powers of tau とその秘密値 τ
 //!
 //! ## 主要関数
 //! - [`generate_groth16_keys`]: QAP と toxic waste から pk/vk を生成
+//! - [`trusted_setup`]: 自作体の上で τ を引き、`τ^0, ..., τ^d` を返す（群を使わない練習版）
 
 use ark_bn254::{Fr, G1Projective, G2Projective};
 use ark_ec::PrimeGroup;
 use ark_ff::Field;
+use ark_std::rand::Rng;
+use num_bigint::BigInt;
+
+use crate::field::FieldElement;
 
 /// Groth16 の toxic waste（trusted setup の秘密値）。
 ///
@@ -29,6 +35,23 @@ pub struct ToxicWaste {
     pub tau: Fr,
 }
 
+/// [`trusted_setup`] の秘密値 τ（自作 [`FieldElement`] 版の toxic waste）。
+///
+/// [`ToxicWaste`] と同じく、[`SetupParams`] を作ったら呼び出し側で破棄する前提。
+/// τ を知っていれば任意の多項式の τ での値を偽れる。
+pub struct FieldToxicWaste {
+    pub tau: FieldElement,
+}
+
+/// [`trusted_setup`] の公開パラメータ。
+///
+/// 本来は群の元 `[τ^i]_1` だが、ここでは体の元 `τ^i` をそのまま持つ
+/// （楕円曲線を使わない練習版。値そのものなので τ は隠れない）。
+pub struct SetupParams {
+    /// `τ^0, τ^1, ..., τ^degree`（長さ `degree + 1`）
+    pub powers_of_tau: Vec<FieldElement>,
+}
+
 /// QAP 多項式を `Fr` 係数に変換した薄いラッパ。
 ///
 /// `a_polys[i]` / `b_polys[i]` / `c_polys[i]` が変数 `i` の `u_i / v_i / w_i` の
@@ -183,6 +206,35 @@ pub fn generate_groth16_keys(
     (pk, vk)
 }
 
+/// 自作体 `F_p` の上で trusted setup を行い、powers of tau と toxic waste を返す。
+///
+/// 0 でない τ をランダムに引き、`τ^0, τ^1, ..., τ^degree` を計算する。
+/// 次数 `degree` 以下の多項式なら、係数との内積で τ での値が求まる。
+/// 返り値の [`FieldToxicWaste`] は呼び出し側で破棄すること。
+pub fn trusted_setup<R: Rng>(
+    rng: &mut R,
+    degree: usize,
+    p: &BigInt,
+) -> (SetupParams, FieldToxicWaste) {
+    // τ = 0 だと τ^1 以降がすべて 0 になり、多項式を区別できない
+    let tau = loop {
+        let t = FieldElement::random(rng, p);
+        if !t.is_zero() {
+            break t;
+        }
+    };
+
+    let mut powers_of_tau = Vec::with_capacity(degree + 1);
+    let mut power = FieldElement::one(p);
+    for _ in 0..=degree {
+        let next = &power * &tau;
+        powers_of_tau.push(power);
+        power = next;
+    }
+
+    (SetupParams { powers_of_tau }, FieldToxicWaste { tau })
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
@@ -307,4 +359,29 @@ mod tests {
         let qap = sample_qap_fr();
         let _ = generate_groth16_keys(&qap, 2, 2, &toxic);
     }
+
+    #[test]
+    fn trusted_setup_powers_form_geometric_sequence() {
+        let p = BigInt::from(97);
+        let mut rng = ark_std::test_rng();
+        let (params, toxic) = trusted_setup(&mut rng, 5, &p);
+
+        assert_eq!(params.powers_of_tau.len(), 6);
+        assert!(params.powers_of_tau[0].is_one());
+        assert!(!toxic.tau.is_zero());
+        for w in params.powers_of_tau.windows(2) {
+            assert_eq!(w[1], &w[0] * &toxic.tau);
+        }
+        // τ を知らなくても τ^{i-1}·τ^{i+1} = (τ^i)^2 で等比性を確かめられる
+        for w in params.powers_of_tau.windows(3) {
+            assert_eq!(&w[0] * &w[2], w[1].square());
+        }
+    }
+
+    #[test]
+    fn trusted_setup_degree_zero_is_just_one() {
+        let p = BigInt::from(97);
+        let (params, _toxic) = trusted_setup(&mut ark_std::test_rng(), 0, &p);
+        assert_eq!(params.powers_of_tau, vec![FieldElement::one(&p)]);
+    }
 }
@@ -0,0 +1,659 @@
+//! circom / snarkjs のバイナリ形式との相互運用を提供する。
+//!
+//! 外部ツール（circom, snarkjs）で作った回路をこのクレートで扱ったり、
+//! このクレートの回路を外部で検証したりするための入出力。
+//!
+//! ## 対応形式
+//! - `.r1cs`: [`ConstraintSystem::export_r1cs`][] で制約系を書き出し、
+//!   [`ConstraintSystem::import_r1cs`][] で読み込む
+//! - `.wtns`: [`export_witness`][] で witness を書き出し、[`import_witness`][] で読み込む
+//!
+//! 読み込みの失敗は [`FormatError`] で返す。
+//!
+//! ## エンコーディング
+//! 整数はすべてリトルエンディアン。体の元は `n8` バイト固定長のリトルエンディアン
+//! （`n8` は素数 `p` を収める 8 の倍数のバイト数。BN254 なら 32）。
+//! ファイルは `magic (4 bytes) | version: u32 | セクション数: u32` に続いて
+//! `type: u32 | size: u64 | 本体` のセクションが並ぶ。
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use num_bigint::{BigInt, Sign};
+
+use crate::field::{FieldElement, IntoModulus, SharedModulus};
+use crate::r1cs::{ConstraintSystem, LinearCombination, Variable};
+
+/// バイナリ形式の読み込みで起こりうるエラー。
+#[derive(Debug)]
+pub enum FormatError {
+    /// 読み込み元の I/O エラー。
+    Io(io::Error),
+    /// 先頭の magic が期待した形式のものではない。
+    InvalidMagic { expected: [u8; 4], found: [u8; 4] },
+    /// 未対応のバージョン。
+    UnsupportedVersion(u32),
+    /// 必須のセクションがない。
+    MissingSection(u32),
+    /// ファイルの素数が期待した法と一致しない。
+    PrimeMismatch { expected: BigInt, found: BigInt },
+    /// セクションやデータが途中で切れている。
+    Truncated,
+    /// 制約が参照する wire 番号がヘッダの wire 数を超えている。
+    WireOutOfRange { wire: usize, num_wires: usize },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Io(e) => write!(f, "I/O error: {}", e),
+            FormatError::InvalidMagic { expected, found } => write!(
+                f,
+                "invalid magic: expected {:?}, found {:?}",
+                String::from_utf8_lossy(expected),
+                String::from_utf8_lossy(found)
+            ),
+            FormatError::UnsupportedVersion(v) => write!(f, "unsupported version: {}", v),
+            FormatError::MissingSection(t) => write!(f, "missing section of type {}", t),
+            FormatError::PrimeMismatch { expected, found } => {
+                write!(f, "prime mismatch: expected {}, found {}", expected, found)
+            }
+            FormatError::Truncated => write!(f, "unexpected end of data"),
+            FormatError::WireOutOfRange { wire, num_wires } => {
+                write!(f, "wire {} out of range (num_wires = {})", wire, num_wires)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<io::Error> for FormatError {
+    fn from(e: io::Error) -> Self {
+        FormatError::Io(e)
+    }
+}
+
+/// `.r1cs` のヘッダセクション（素数、変数の数、制約の数など）。
+const R1CS_SECTION_HEADER: u32 = 1;
+/// `.r1cs` の制約セクション（各制約の A, B, C）。
+const R1CS_SECTION_CONSTRAINTS: u32 = 2;
+/// `.r1cs` の wire → label の対応セクション。
+const R1CS_SECTION_WIRE2LABEL: u32 = 3;
+
+/// `.wtns` のヘッダセクション（素数、witness の長さ）。
+const WTNS_SECTION_HEADER: u32 = 1;
+/// `.wtns` の値セクション（witness の各値）。
+const WTNS_SECTION_VALUES: u32 = 2;
+
+impl ConstraintSystem {
+    /// 制約系を circom の `.r1cs` バイナリ形式で `w` に書き出す。
+    ///
+    /// wire `i` はこのクレートの変数 `Variable(i)` に対応する（wire 0 = [`CS_ONE`](crate::r1cs::CS_ONE)）。
+    /// circom は public を「出力」と「入力」に分けるが、ここでは区別しないので
+    /// [`CS_ONE`](crate::r1cs::CS_ONE) 以外の public 変数はすべて public 入力として書く。
+    /// 秘密入力と中間変数も区別しないため、秘密入力の数は 0 とする。
+    ///
+    /// 各線形結合は wire ごとに係数をまとめ、wire 番号の昇順で書く（係数 0 の項は省く）。
+    /// 制約系は `init_one` 済みであることが前提（法 `p` を `assignments[0]` から取り出す）。
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn export_r1cs<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let p = &self
+            .assignments
+            .first()
+            .and_then(Option::as_ref)
+            .expect("constraint system not initialized; call init_one() first")
+            .p;
+        let n8 = field_size(p);
+        let num_wires = self.num_variables() as u32;
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&(n8 as u32).to_le_bytes());
+        write_field(&mut header, p, n8);
+        header.extend_from_slice(&num_wires.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes()); // public 出力
+        header.extend_from_slice(&(self.num_public_variables as u32 - 1).to_le_bytes()); // public 入力
+        header.extend_from_slice(&0u32.to_le_bytes()); // 秘密入力
+        header.extend_from_slice(&u64::from(num_wires).to_le_bytes()); // label の数
+        header.extend_from_slice(&(self.num_constraints() as u32).to_le_bytes());
+
+        let mut constraints = Vec::new();
+        for constraint in &self.constraints {
+            for lc in [&constraint.a, &constraint.b, &constraint.c] {
+                write_lc(&mut constraints, lc, n8);
+            }
+        }
+
+        // wire i の label は i（恒等写像）
+        let mut wire2label = Vec::new();
+        for i in 0..u64::from(num_wires) {
+            wire2label.extend_from_slice(&i.to_le_bytes());
+        }
+
+        write_sections(
+            w,
+            b"r1cs",
+            1,
+            &[
+                (R1CS_SECTION_HEADER, header.as_slice()),
+                (R1CS_SECTION_CONSTRAINTS, constraints.as_slice()),
+                (R1CS_SECTION_WIRE2LABEL, wire2label.as_slice()),
+            ],
+        )
+    }
+
+    /// circom の `.r1cs` バイナリ形式を `r` から読み、制約系を組み立てる。
+    ///
+    /// ファイルの素数が `p` と一致しなければ [`FormatError::PrimeMismatch`]、制約が
+    /// ヘッダの wire 数以上の wire を参照していれば [`FormatError::WireOutOfRange`] を返す。
+    /// 変数を確保する前にヘッダの数も確かめ、public 変数が wire 数に収まらなければ
+    /// [`FormatError::WireOutOfRange`]、wire 数が wire → label セクションの長さを超えていれば
+    /// [`FormatError::Truncated`] を返す（壊れたヘッダで巨大な確保をしないため）。
+    /// wire `i` は `Variable(i)` になり、wire 0（定数 1）は [`CS_ONE`](crate::r1cs::CS_ONE)
+    /// として `init_one` 済みになる。public 出力と public 入力はどちらも public 変数として
+    /// 先頭に確保する（circom の wire の並びも `1, 出力, public 入力, ...` の順）。
+    /// 残りの変数は未代入なので、witness は別途 `assign` する。
+    /// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+    #[allow(dead_code)]
+    pub fn import_r1cs<R: Read>(r: &mut R, p: &BigInt) -> Result<ConstraintSystem, FormatError> {
+        let mut bytes = Vec::new();
+        r.read_to_end(&mut bytes)?;
+        let sections = read_sections(&bytes, b"r1cs")?;
+        let section = |t: u32| {
+            sections
+                .get(&t)
+                .copied()
+                .ok_or(FormatError::MissingSection(t))
+        };
+
+        let mut header = Cursor::new(section(R1CS_SECTION_HEADER)?);
+        let n8 = read_field_def(&mut header, p)?;
+        let num_wires = header.read_u32()? as usize;
+        let num_pub_out = header.read_u32()? as usize;
+        let num_pub_in = header.read_u32()? as usize;
+        let _num_prv_in = header.read_u32()?;
+        let _num_labels = header.read_u64()?;
+        let num_constraints = header.read_u32()? as usize;
+
+        let num_public = 1 + num_pub_out + num_pub_in;
+        if num_public > num_wires {
+            return Err(FormatError::WireOutOfRange {
+                wire: num_public - 1,
+                num_wires,
+            });
+        }
+        // wire → label セクションは wire ごとに u64 を 1 つ持つ
+        if section(R1CS_SECTION_WIRE2LABEL)?.len() / 8 < num_wires {
+            return Err(FormatError::Truncated);
+        }
+
+        // 法は 1 度だけ包み、読み込む全係数で共有する
+        let p = p.into_modulus();
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(FieldElement::one(&p));
+        for _ in 1..num_public {
+            cs.alloc_public_input();
+        }
+        while cs.num_variables() < num_wires {
+            cs.alloc_variable();
+        }
+
+        let mut body = Cursor::new(section(R1CS_SECTION_CONSTRAINTS)?);
+        for _ in 0..num_constraints {
+            let a = read_lc(&mut body, n8, num_wires, &p)?;
+            let b = read_lc(&mut body, n8, num_wires, &p)?;
+            let c = read_lc(&mut body, n8, num_wires, &p)?;
+            cs.enforce(a, b, c);
+        }
+        Ok(cs)
+    }
+}
+
+/// witness を snarkjs の `.wtns` バイナリ形式（version 2）で `w` に書き出す。
+///
+/// 各値は `n8` バイト固定長のリトルエンディアンで書く。法 `p` は `witness[0]`
+/// （[`CS_ONE`](crate::r1cs::CS_ONE) の値）から取る。
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+///
+/// # Panics
+/// `witness` が空のとき panic（法 `p` が決まらないため）。
+#[allow(dead_code)]
+pub fn export_witness<W: Write>(witness: &[FieldElement], w: &mut W) -> io::Result<()> {
+    let p = &witness.first().expect("witness が空です").p;
+    let n8 = field_size(p);
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&(n8 as u32).to_le_bytes());
+    write_field(&mut header, p, n8);
+    header.extend_from_slice(&(witness.len() as u32).to_le_bytes());
+
+    let mut values = Vec::new();
+    for v in witness {
+        write_field(&mut values, &v.value, n8);
+    }
+
+    write_sections(
+        w,
+        b"wtns",
+        2,
+        &[
+            (WTNS_SECTION_HEADER, header.as_slice()),
+            (WTNS_SECTION_VALUES, values.as_slice()),
+        ],
+    )
+}
+
+/// snarkjs の `.wtns` バイナリ形式を `r` から読み、witness ベクトルを返す。
+///
+/// ファイルの素数が `p` と一致しなければ [`FormatError::PrimeMismatch`] を返す。
+/// 現在は unit test からのみ呼ばれる。回路で使われ始めたら attribute を外す。
+#[allow(dead_code)]
+pub fn import_witness<R: Read>(r: &mut R, p: &BigInt) -> Result<Vec<FieldElement>, FormatError> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+    let sections = read_sections(&bytes, b"wtns")?;
+    let section = |t: u32| {
+        sections
+            .get(&t)
+            .copied()
+            .ok_or(FormatError::MissingSection(t))
+    };
+
+    let mut header = Cursor::new(section(WTNS_SECTION_HEADER)?);
+    let n8 = read_field_def(&mut header, p)?;
+    let len = header.read_u32()? as usize;
+
+    let p = p.into_modulus();
+    let mut values = Cursor::new(section(WTNS_SECTION_VALUES)?);
+    (0..len)
+        .map(|_| Ok(FieldElement::new(values.read_field(n8)?, &p)))
+        .collect()
+}
+
+/// `magic | version | セクション数` に続けて、各セクションを `type | size | 本体` で書く。
+fn write_sections<W: Write>(
+    w: &mut W,
+    magic: &[u8; 4],
+    version: u32,
+    sections: &[(u32, &[u8])],
+) -> io::Result<()> {
+    w.write_all(magic)?;
+    w.write_all(&version.to_le_bytes())?;
+    w.write_all(&(sections.len() as u32).to_le_bytes())?;
+    for (section_type, body) in sections {
+        w.write_all(&section_type.to_le_bytes())?;
+        w.write_all(&(body.len() as u64).to_le_bytes())?;
+        w.write_all(body)?;
+    }
+    Ok(())
+}
+
+/// ヘッダ先頭の `n8: u32 | 素数: n8 bytes` を読み、素数が `p` と一致するか確かめて `n8` を返す。
+fn read_field_def(header: &mut Cursor, p: &BigInt) -> Result<usize, FormatError> {
+    let n8 = header.read_u32()? as usize;
+    let prime = header.read_field(n8)?;
+    if &prime != p {
+        return Err(FormatError::PrimeMismatch {
+            expected: p.clone(),
+            found: prime,
+        });
+    }
+    Ok(n8)
+}
+
+/// `magic | version | セクション数` を検証し、セクション本体を type ごとに切り出す。
+///
+/// 同じ type のセクションが複数あれば後のものを使う。
+fn read_sections<'a>(
+    bytes: &'a [u8],
+    magic: &[u8; 4],
+) -> Result<BTreeMap<u32, &'a [u8]>, FormatError> {
+    let mut cursor = Cursor::new(bytes);
+    let found: [u8; 4] = cursor.read_bytes(4)?.try_into().unwrap();
+    if &found != magic {
+        return Err(FormatError::InvalidMagic {
+            expected: *magic,
+            found,
+        });
+    }
+    let version = cursor.read_u32()?;
+    if version != 1 && version != 2 {
+        return Err(FormatError::UnsupportedVersion(version));
+    }
+    let num_sections = cursor.read_u32()?;
+    let mut sections = BTreeMap::new();
+    for _ in 0..num_sections {
+        let section_type = cursor.read_u32()?;
+        let size = cursor.read_u64()? as usize;
+        sections.insert(section_type, cursor.read_bytes(size)?);
+    }
+    Ok(sections)
+}
+
+/// 線形結合を `項数: u32 | (wire: u32, 係数: n8 bytes)*` の形式で読む。
+///
+/// wire 番号が `num_wires` 以上なら [`FormatError::WireOutOfRange`] を返す。
+fn read_lc(
+    cursor: &mut Cursor,
+    n8: usize,
+    num_wires: usize,
+    p: &SharedModulus,
+) -> Result<LinearCombination, FormatError> {
+    let mut lc = LinearCombination::new();
+    for _ in 0..cursor.read_u32()? {
+        let wire = cursor.read_u32()? as usize;
+        if wire >= num_wires {
+            return Err(FormatError::WireOutOfRange { wire, num_wires });
+        }
+        let coeff = cursor.read_field(n8)?;
+        lc.add_term(Variable(wire), FieldElement::new(coeff, p));
+    }
+    Ok(lc)
+}
+
+/// バイト列を先頭から読み進めるリトルエンディアンのリーダー。
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Cursor { bytes, pos: 0 }
+    }
+
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], FormatError> {
+        let end = self.pos.checked_add(n).ok_or(FormatError::Truncated)?;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or(FormatError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, FormatError> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, FormatError> {
+        Ok(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+    }
+
+    /// `n8` バイトのリトルエンディアン整数を読む（法での簡約はしない）。
+    fn read_field(&mut self, n8: usize) -> Result<BigInt, FormatError> {
+        Ok(BigInt::from_bytes_le(Sign::Plus, self.read_bytes(n8)?))
+    }
+}
+
+/// 素数 `p` を収める 8 の倍数のバイト数 `n8` を返す。
+fn field_size(p: &BigInt) -> usize {
+    (p.bits() as usize).div_ceil(64) * 8
+}
+
+/// `value` を `n8` バイト固定長のリトルエンディアンで書く（上位側は 0 埋め）。
+fn write_field(buf: &mut Vec<u8>, value: &BigInt, n8: usize) {
+    let (_sign, mut bytes) = value.to_bytes_le();
+    bytes.resize(n8, 0);
+    buf.extend_from_slice(&bytes);
+}
+
+/// 線形結合を `項数: u32 | (wire: u32, 係数: n8 bytes)*` の形式で書く。
+fn write_lc(buf: &mut Vec<u8>, lc: &LinearCombination, n8: usize) {
+    // 同じ wire の項をまとめ、wire 番号の昇順に並べる
+    let merged = lc.normalized_terms();
+    buf.extend_from_slice(&(merged.len() as u32).to_le_bytes());
+    for (wire, coeff) in &merged {
+        buf.extend_from_slice(&(*wire as u32).to_le_bytes());
+        write_field(buf, &coeff.value, n8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r1cs::{x3_plus5_circuit, CS_ONE};
+
+    const P: i64 = 97;
+
+    fn fe(v: i64) -> FieldElement {
+        FieldElement::new(v, P)
+    }
+
+    fn read_u32(bytes: &[u8], pos: &mut usize) -> u32 {
+        let v = u32::from_le_bytes(bytes[*pos..*pos + 4].try_into().unwrap());
+        *pos += 4;
+        v
+    }
+
+    fn read_u64(bytes: &[u8], pos: &mut usize) -> u64 {
+        let v = u64::from_le_bytes(bytes[*pos..*pos + 8].try_into().unwrap());
+        *pos += 8;
+        v
+    }
+
+    #[test]
+    fn export_r1cs_writes_header() {
+        let cs = x3_plus5_circuit(P);
+        let mut bytes = Vec::new();
+        cs.export_r1cs(&mut bytes).unwrap();
+
+        assert_eq!(&bytes[0..4], b"r1cs");
+        let mut pos = 4;
+        assert_eq!(read_u32(&bytes, &mut pos), 1); // version
+        assert_eq!(read_u32(&bytes, &mut pos), 3); // セクション数
+        assert_eq!(read_u32(&bytes, &mut pos), R1CS_SECTION_HEADER);
+        let _size = read_u64(&bytes, &mut pos);
+        assert_eq!(read_u32(&bytes, &mut pos), 8); // n8
+        assert_eq!(bytes[pos], 97); // prime（LE）
+        pos += 8;
+        assert_eq!(read_u32(&bytes, &mut pos), 5); // wire: CS_ONE, y, x, x², x³
+        assert_eq!(read_u32(&bytes, &mut pos), 0); // public 出力
+        assert_eq!(read_u32(&bytes, &mut pos), 1); // public 入力: y
+        assert_eq!(read_u32(&bytes, &mut pos), 0); // 秘密入力
+        assert_eq!(read_u64(&bytes, &mut pos), 5); // label
+        assert_eq!(read_u32(&bytes, &mut pos), 3); // 制約
+    }
+
+    #[test]
+    fn export_then_import_r1cs_round_trips_constraints() {
+        // 読み戻した各制約の A, B, C が、項をまとめて wire 順に並べた形で元と一致する
+        let cs = x3_plus5_circuit(P);
+        let mut bytes = Vec::new();
+        cs.export_r1cs(&mut bytes).unwrap();
+
+        let imported =
+            ConstraintSystem::import_r1cs(&mut bytes.as_slice(), &BigInt::from(P)).unwrap();
+        assert_eq!(imported.num_constraints(), cs.num_constraints());
+        assert_eq!(imported.num_variables(), cs.num_variables());
+        assert_eq!(imported.num_public_variables, cs.num_public_variables);
+        for (read, original) in imported.constraints.iter().zip(&cs.constraints) {
+            assert_eq!(read.a.normalized_terms(), original.a.normalized_terms());
+            assert_eq!(read.b.normalized_terms(), original.b.normalized_terms());
+            assert_eq!(read.c.normalized_terms(), original.c.normalized_terms());
+        }
+    }
+
+    #[test]
+    fn export_r1cs_merges_duplicate_terms() {
+        let mut cs = ConstraintSystem::new();
+        cs.init_one(fe(1));
+        let x = cs.alloc_variable();
+        cs.assign(x, fe(2));
+        let mut lc_a = LinearCombination::new();
+        lc_a.add_term(x, fe(1));
+        lc_a.add_term(x, fe(2));
+        cs.enforce(
+            lc_a,
+            LinearCombination::from((CS_ONE, fe(1))),
+            LinearCombination::from((CS_ONE, fe(6))),
+        );
+
+        let mut bytes = Vec::new();
+        cs.export_r1cs(&mut bytes).unwrap();
+        let imported =
+            ConstraintSystem::import_r1cs(&mut bytes.as_slice(), &BigInt::from(P)).unwrap();
+        assert_eq!(imported.constraints[0].a.terms, vec![(x, fe(3))]);
+    }
+
+    fn bn254() -> BigInt {
+        BigInt::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            10,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn import_r1cs_reads_circom_fixture() {
+        // circom の `y <== x2 * x + 5`（x2 <== x * x）相当。wire: [1, y(出力), x(秘密入力), x2]
+        let p = bn254();
+        let mut file: &[u8] = include_bytes!("testdata/x3_plus5.r1cs");
+        let mut cs = ConstraintSystem::import_r1cs(&mut file, &p).unwrap();
+        assert_eq!(cs.num_constraints(), 2);
+        assert_eq!(cs.num_variables(), 4);
+        assert_eq!(cs.num_public_variables, 2);
+
+        let fe = |v: u64| FieldElement::new(v, p.clone());
+        for (i, v) in [32, 3, 9].into_iter().enumerate() {
+            cs.assign(Variable(i + 1), fe(v));
+        }
+        assert!(cs.is_satisfied(&cs.generate_witness()));
+
+        cs.assign(Variable(1), fe(33));
+        assert!(!cs.is_satisfied(&cs.generate_witness()));
+    }
+
+    #[test]
+    fn import_r1cs_rejects_prime_mismatch() {
+        let mut file: &[u8] = include_bytes!("testdata/x3_plus5.r1cs");
+        let err = ConstraintSystem::import_r1cs(&mut file, &BigInt::from(P))
+            .err()
+            .expect("素数が違うのに読み込めた");
+        match err {
+            FormatError::PrimeMismatch { expected, found } => {
+                assert_eq!(expected, BigInt::from(P));
+                assert_eq!(found, bn254());
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn import_r1cs_rejects_bad_magic_and_truncation() {
+        let p = BigInt::from(P);
+        let mut bytes = Vec::new();
+        x3_plus5_circuit(P).export_r1cs(&mut bytes).unwrap();
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'x';
+        assert!(matches!(
+            ConstraintSystem::import_r1cs(&mut wrong_magic.as_slice(), &p),
+            Err(FormatError::InvalidMagic { .. })
+        ));
+
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(matches!(
+            ConstraintSystem::import_r1cs(&mut &truncated[..], &p),
+            Err(FormatError::Truncated)
+        ));
+    }
+
+    /// `.r1cs` ヘッダの素数に続く `index` 番目の u32（0: wire 数, 1: public 出力数,
+    /// 2: public 入力数）を `f` で書き換え、元の値を返す。
+    fn update_r1cs_header_u32(bytes: &mut [u8], index: usize, f: impl FnOnce(u32) -> u32) -> u32 {
+        // magic + version + セクション数 + (type, size) + n8 + prime の直後
+        let n8 = u32::from_le_bytes(bytes[24..28].try_into().unwrap()) as usize;
+        let offset = 28 + n8 + 4 * index;
+        let old = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        bytes[offset..offset + 4].copy_from_slice(&f(old).to_le_bytes());
+        old
+    }
+
+    #[test]
+    fn import_r1cs_rejects_wire_out_of_range() {
+        let p = BigInt::from(P);
+        let mut bytes = Vec::new();
+        x3_plus5_circuit(P).export_r1cs(&mut bytes).unwrap();
+
+        // ヘッダの wire 数を 1 減らすと、最後の wire を参照する制約が範囲外になる
+        let num_wires = update_r1cs_header_u32(&mut bytes, 0, |n| n - 1);
+
+        match ConstraintSystem::import_r1cs(&mut bytes.as_slice(), &p) {
+            Err(FormatError::WireOutOfRange { wire, num_wires: n }) => {
+                assert_eq!(wire, num_wires as usize - 1);
+                assert_eq!(n, num_wires as usize - 1);
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn import_r1cs_rejects_too_many_public_wires() {
+        let p = BigInt::from(P);
+        let mut bytes = Vec::new();
+        x3_plus5_circuit(P).export_r1cs(&mut bytes).unwrap();
+
+        // public 入力数を wire 数にすると、CS_ONE と合わせて wire 数を 1 つ超える
+        let num_wires = update_r1cs_header_u32(&mut bytes, 0, |n| n);
+        update_r1cs_header_u32(&mut bytes, 2, |_| num_wires);
+
+        match ConstraintSystem::import_r1cs(&mut bytes.as_slice(), &p) {
+            Err(FormatError::WireOutOfRange { wire, num_wires: n }) => {
+                assert_eq!(wire, num_wires as usize);
+                assert_eq!(n, num_wires as usize);
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn import_r1cs_rejects_num_wires_beyond_section() {
+        let p = BigInt::from(P);
+        let mut bytes = Vec::new();
+        x3_plus5_circuit(P).export_r1cs(&mut bytes).unwrap();
+
+        // 巨大な wire 数は確保する前に弾く
+        update_r1cs_header_u32(&mut bytes, 0, |_| u32::MAX);
+        assert!(matches!(
+            ConstraintSystem::import_r1cs(&mut bytes.as_slice(), &p),
+            Err(FormatError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn export_then_import_witness_round_trips() {
+        let cs = x3_plus5_circuit(P);
+        let witness = cs.generate_witness();
+        let mut bytes = Vec::new();
+        export_witness(&witness, &mut bytes).unwrap();
+
+        assert_eq!(&bytes[0..4], b"wtns");
+        // magic + version + セクション数 + 2 セクション分の (type, size) + n8 + prime + 長さ + 値
+        assert_eq!(bytes.len(), 12 + 2 * 12 + 4 + 8 + 4 + 8 * witness.len());
+
+        let back = import_witness(&mut bytes.as_slice(), &BigInt::from(P)).unwrap();
+        assert_eq!(back, witness);
+    }
+
+    #[test]
+    fn export_then_import_witness_round_trips_bn254() {
+        let p = bn254();
+        let witness: Vec<FieldElement> = [1u64, 32, 3, 9]
+            .iter()
+            .map(|&v| FieldElement::new(v, p.clone()))
+            .chain(std::iter::once(FieldElement::new(-1, p.clone())))
+            .collect();
+        let mut bytes = Vec::new();
+        export_witness(&witness, &mut bytes).unwrap();
+
+        let back = import_witness(&mut bytes.as_slice(), &p).unwrap();
+        assert_eq!(back, witness);
+        assert!(matches!(
+            import_witness(&mut bytes.as_slice(), &BigInt::from(P)),
+            Err(FormatError::PrimeMismatch { .. })
+        ));
+    }
+}
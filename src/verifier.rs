@@ -3,7 +3,11 @@
 //! Groth16 実装の Layer 3（プロトコル）。BN254 のペアリング `e: G1 × G2 → GT` を
 //! 用いて、証明の正しさを定数時間で検証する。
 //!
+//! ## 主要型
+//! - [`Verifier`]: QAP の段階での検証 `A·B − C = H·Z`（多項式恒等式）
+//!
 //! ## 主要関数
+//! - [`verify_pinocchio`]: τ 上の体の値で `A·B − C = H·Z` を確かめる（ペアリング前の練習版）
 //! - [`verify`]: Groth16 の検証 `e(A,B) = e(α,β)·e(vk_x,γ)·e(C,δ)`
 //!
 //! 双線形性 `e(aP, bQ) = e(P, Q)^{ab}` により、4 つのペアリングの等式で
@@ -12,9 +16,49 @@
 use ark_bn254::{Bn254, Fr};
 use ark_ec::{pairing::Pairing, CurveGroup};
 
+use crate::field::FieldElement;
+use crate::polynomial::Polynomial;
 use crate::prover::Groth16Proof;
 use crate::setup::VerifyingKey;
 
+/// 消失多項式 `Z(x)` を持ち、QAP の段階で `(A, B, C, H)` を検証する。
+///
+/// ペアリングを使う前の、自作 [`Polynomial`] 上の検証。割り算をやり直さずに
+/// `A·B − C = H·Z` を多項式の恒等式として確かめる（本物の Verifier が
+/// τ 上の 1 点でやることを、多項式全体で行う版）。
+#[derive(Debug, Clone)]
+pub struct Verifier {
+    /// 消失多項式 `Z(x) = (x − 0)(x − 1)...(x − (n − 1))`
+    pub z: Polynomial,
+}
+
+impl Verifier {
+    /// `A(x)·B(x) − C(x) == H(x)·Z(x)` が多項式として成り立つかを返す。
+    pub fn verify(&self, a: &Polynomial, b: &Polynomial, c: &Polynomial, h: &Polynomial) -> bool {
+        &(a * b) - c == h * &self.z
+    }
+}
+
+/// Pinocchio / GGPR 型の検証式 `A(τ)·B(τ) − C(τ) == H(τ)·Z(τ)` を体の上で確かめる。
+///
+/// [`Verifier::verify`]（多項式全体の恒等式）と [`verify`]（ペアリング）の間の段階。
+/// 秘密点 τ 1 点での値だけを比べるので、Schwartz–Zippel の補題により
+/// 恒等式が成り立たなければ高確率（`1 − 2n/p` 程度）で不一致になる。
+/// `A(τ)` などは setup の [`crate::qap::Qap::evaluate_all_at`] の値と witness の内積で作る。
+///
+/// ただし値そのものを渡すので τ の情報も witness も隠れず（non-ZK）、
+/// 値が本当に τ での評価かどうかも確かめられない。群に焼き込んで隠し、
+/// ペアリングで積を取るのが [`verify`] の役割。
+pub fn verify_pinocchio(
+    a_tau: &FieldElement,
+    b_tau: &FieldElement,
+    c_tau: &FieldElement,
+    h_tau: &FieldElement,
+    z_tau: &FieldElement,
+) -> bool {
+    &(a_tau * b_tau) - c_tau == h_tau * z_tau
+}
+
 /// 本式 Groth16 の検証。`e(A,B) == e(α,β)·e(vk_x,γ)·e(C,δ)` をペアリングで確認する。
 ///
 /// `vk`: trusted setup で生成した [`VerifyingKey`]
@@ -64,3 +108,90 @@ pub fn verify(vk: &VerifyingKey, public_inputs: &[Fr], proof: &Groth16Proof) ->
 
     lhs == rhs
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qap::Qap;
+    use crate::r1cs::x3_plus5_circuit;
+    use crate::setup::trusted_setup;
+    use num_bigint::BigInt;
+
+    const P: i64 = 97;
+
+    fn poly(coeffs: &[i64]) -> Polynomial {
+        Polynomial::new(coeffs.iter().map(|&c| FieldElement::new(c, P)).collect())
+    }
+
+    /// Z = x(x − 1), H = x + 2, A = x + 1, B = 3 として C = A·B − H·Z を作る。
+    fn valid_tuple() -> (Verifier, Polynomial, Polynomial, Polynomial, Polynomial) {
+        let verifier = Verifier {
+            z: poly(&[0, -1, 1]),
+        };
+        let a = poly(&[1, 1]);
+        let b = poly(&[3]);
+        let h = poly(&[2, 1]);
+        let c = &(&a * &b) - &(&h * &verifier.z);
+        (verifier, a, b, c, h)
+    }
+
+    #[test]
+    fn verifier_accepts_valid_tuple() {
+        let (verifier, a, b, c, h) = valid_tuple();
+        assert!(verifier.verify(&a, &b, &c, &h));
+    }
+
+    #[test]
+    fn verifier_rejects_corrupted_h() {
+        let (verifier, a, b, c, h) = valid_tuple();
+        let bad_h = h.add_scalar(&FieldElement::new(1, P));
+        assert!(!verifier.verify(&a, &b, &c, &bad_h));
+    }
+
+    /// demo 回路 `y = x^3 + 5`（F_97）の witness から、setup の τ での
+    /// `(A(τ), B(τ), C(τ), H(τ), Z(τ))` を作る。`H` は余りを捨てた商。
+    fn pinocchio_values(tamper: bool) -> [FieldElement; 5] {
+        let p = BigInt::from(P);
+        let fe = |v: u64| FieldElement::from_u64(v, &p);
+        let cs = x3_plus5_circuit(&p);
+        let mut witness = cs.generate_witness();
+        if tamper {
+            // y = 32 を 33 に改ざん
+            witness[1] = fe(33);
+        }
+
+        let qap = Qap::from_r1cs(&cs);
+        let z = qap.target_polynomial(cs.num_constraints(), &p);
+        let (_, waste) = trusted_setup(&mut ark_std::test_rng(), cs.num_constraints(), &p);
+        let tau = waste.tau;
+
+        let (u, v, w) = qap.evaluate_all_at(&tau);
+        let inner = |evals: &[FieldElement]| {
+            evals
+                .iter()
+                .zip(&witness)
+                .fold(fe(0), |acc, (e, wi)| &acc + &(e * wi))
+        };
+        let (a, b, c) = qap.compose(&witness);
+        let (h, _) = (&(&a * &b) - &c).div_rem(&z);
+        [
+            inner(&u),
+            inner(&v),
+            inner(&w),
+            h.evaluate(&tau),
+            z.evaluate(&tau),
+        ]
+    }
+
+    #[test]
+    fn pinocchio_accepts_demo_witness() {
+        let [a, b, c, h, z] = pinocchio_values(false);
+        assert!(verify_pinocchio(&a, &b, &c, &h, &z));
+    }
+
+    #[test]
+    fn pinocchio_rejects_broken_witness() {
+        let [a, b, c, h, z] = pinocchio_values(true);
+        assert!(!verify_pinocchio(&a, &b, &c, &h, &z));
+    }
+}